@@ -3,10 +3,14 @@ use proc_macro_crate::{FoundCrate, crate_name};
 use proc_macro_error2::abort;
 use quote::quote;
 use syn::{
-    AttrStyle, Attribute, Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident,
-    parse_str,
+    AttrStyle, Attribute, Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, LitInt,
+    TraitBoundModifier, Type, TypeParamBound, WherePredicate, ext::IdentExt, parenthesized,
+    parse_str, token,
 };
 
+/// Keep in sync with `hdf5_dst::MAX_MEMBER_NAME_LEN`.
+const MAX_MEMBER_NAME_LEN: usize = 1024;
+
 struct PreDerive {
     attrs: Vec<Attribute>,
     struct_name: Ident,
@@ -77,6 +81,11 @@ fn find_repr(attrs: &[Attribute], expected: &[&str]) -> Option<Ident> {
                 } else {
                     repr = meta.path.get_ident().cloned();
                 }
+            } else if meta.input.peek(token::Paren) {
+                // Skip the arguments of other hints, e.g., `align(16)`.
+                let args;
+                parenthesized!(args in meta.input);
+                args.parse::<proc_macro2::TokenStream>()?;
             }
             Ok(())
         })
@@ -85,7 +94,225 @@ fn find_repr(attrs: &[Attribute], expected: &[&str]) -> Option<Ident> {
     repr
 }
 
-#[proc_macro_derive(H5TypeUnsized)]
+fn find_table(attrs: &[Attribute]) -> bool {
+    let mut table = false;
+    for attr in attrs.iter() {
+        if attr.style != AttrStyle::Outer {
+            continue;
+        }
+        if !attr.path().is_ident("h5") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                table = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported h5 attribute"))
+            }
+        })
+        .unwrap_or_else(|e| abort!(e.span(), e));
+    }
+    table
+}
+
+/// The field attributes `#[h5(index = N)]` and `#[h5(unsized)]`.
+#[derive(Default)]
+struct FieldAttrs {
+    /// The member index override.
+    index: Option<(LitInt, usize)>,
+    /// Marks a DST field which is not recognized by the syntax, e.g., a derived one.
+    is_unsized: bool,
+}
+
+/// Find the attributes of a field.
+fn find_field_attrs(attrs: &[Attribute]) -> FieldAttrs {
+    let mut res = FieldAttrs::default();
+    for attr in attrs.iter() {
+        if attr.style != AttrStyle::Outer {
+            continue;
+        }
+        if !attr.path().is_ident("h5") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("index") {
+                if res.index.is_some() {
+                    return Err(meta.error("duplicate index attribute"));
+                }
+                let lit: LitInt = meta.value()?.parse()?;
+                let value = lit.base10_parse::<usize>()?;
+                res.index = Some((lit, value));
+                Ok(())
+            } else if meta.path.is_ident("unsized") {
+                res.is_unsized = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported h5 attribute"))
+            }
+        })
+        .unwrap_or_else(|e| abort!(e.span(), e));
+    }
+    res
+}
+
+/// Get the member indices, which default to the declaration order.
+/// The indices should be unique and cover `0..n`.
+fn member_indices(fields: &[Field]) -> Vec<usize> {
+    let mut seen = vec![false; fields.len()];
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match find_field_attrs(&field.attrs).index {
+            Some((lit, index)) => {
+                if index >= fields.len() {
+                    abort!(
+                        lit,
+                        "member index {} is out of range 0..{}",
+                        index,
+                        fields.len()
+                    );
+                }
+                if std::mem::replace(&mut seen[index], true) {
+                    abort!(lit, "duplicate member index {}", index);
+                }
+                index
+            }
+            None => {
+                if std::mem::replace(&mut seen[i], true) {
+                    abort!(field.ty, "member index {} is taken by an explicit index", i);
+                }
+                i
+            }
+        })
+        .collect()
+}
+
+fn is_maybe_unsized_param(generics: &Generics, ident: &Ident) -> bool {
+    let is_maybe = |bound: &TypeParamBound| matches!(bound, TypeParamBound::Trait(bound) if matches!(bound.modifier, TraitBoundModifier::Maybe(_)));
+    let inline = generics.params.iter().any(|p| match p {
+        GenericParam::Type(p) => &p.ident == ident && p.bounds.iter().any(is_maybe),
+        _ => false,
+    });
+    let where_clause = generics.where_clause.iter().any(|w| {
+        w.predicates.iter().any(|p| match p {
+            WherePredicate::Type(p) => {
+                matches!(&p.bounded_ty, Type::Path(ty) if ty.path.is_ident(ident))
+                    && p.bounds.iter().any(is_maybe)
+            }
+            _ => false,
+        })
+    });
+    inline || where_clause
+}
+
+/// Determine if the type is a DST, by the syntax only.
+fn is_unsized(ty: &Type, generics: &Generics) -> bool {
+    match ty {
+        Type::Slice(_) => true,
+        Type::Path(ty) => match ty.path.segments.last() {
+            Some(seg) => {
+                seg.ident == "str"
+                    || seg.ident == "UnsizedSlice"
+                    || seg.ident == "UnsizedStr"
+                    || (ty.path.segments.len() == 1 && is_maybe_unsized_param(generics, &seg.ident))
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Determine if the field is a DST, by the syntax or `#[h5(unsized)]`.
+fn is_unsized_field(field: &Field, generics: &Generics) -> bool {
+    is_unsized(&field.ty, generics) || find_field_attrs(&field.attrs).is_unsized
+}
+
+/// Generate the packet table helpers of `#[h5(table)]`.
+///
+/// The helpers of a DST take the pointer metadata of the type, e.g. the length for slices.
+/// A derived DST in the last field is not recognized by the syntax,
+/// and should be marked with `#[h5(unsized)]`.
+fn table_helpers(
+    struct_name: &Ident,
+    generics: &Generics,
+    generic_inputs: &proc_macro2::TokenStream,
+    is_dst: bool,
+) -> proc_macro2::TokenStream {
+    let hl_crate_name = match crate_name("hdf5-hl") {
+        Ok(FoundCrate::Itself) => quote!(crate),
+        Ok(FoundCrate::Name(name)) => {
+            let name = parse_str::<Ident>(&name).unwrap();
+            quote!(::#name)
+        }
+        Err(_) => abort!(struct_name, "#[h5(table)] requires the hdf5-hl dependency"),
+    };
+    if is_dst {
+        quote! {
+            impl #generics #struct_name #generic_inputs {
+                /// Create a packet table of this type.
+                /// The metadata is e.g. the length for slices.
+                pub fn create_table(
+                    loc: &impl #hl_crate_name ::AsGroup,
+                    name: &str,
+                    chunk: usize,
+                    metadata: <Self as ::core::ptr::Pointee>::Metadata,
+                ) -> #hl_crate_name ::Result<#hl_crate_name ::PacketTable> {
+                    #hl_crate_name ::PacketTable::builder(loc)
+                        .chunk(chunk)
+                        .dtype_unsized::<Self>(metadata)
+                        .create(name)
+                }
+
+                /// Open a packet table of this type, checking the stored datatype.
+                /// The metadata is e.g. the length for slices.
+                pub fn open_table(
+                    loc: &impl #hl_crate_name ::AsGroup,
+                    name: &str,
+                    metadata: <Self as ::core::ptr::Pointee>::Metadata,
+                ) -> #hl_crate_name ::Result<#hl_crate_name ::PacketTable> {
+                    #hl_crate_name ::PacketTable::open_as::<Self>(loc, name, metadata)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #generics #struct_name #generic_inputs {
+                /// Create a packet table of this type.
+                pub fn create_table(
+                    loc: &impl #hl_crate_name ::AsGroup,
+                    name: &str,
+                    chunk: usize,
+                ) -> #hl_crate_name ::Result<#hl_crate_name ::PacketTable> {
+                    #hl_crate_name ::PacketTable::builder(loc)
+                        .chunk(chunk)
+                        .dtype_unsized::<Self>(())
+                        .create(name)
+                }
+
+                /// Open a packet table of this type, checking the stored datatype.
+                pub fn open_table(
+                    loc: &impl #hl_crate_name ::AsGroup,
+                    name: &str,
+                ) -> #hl_crate_name ::Result<#hl_crate_name ::PacketTable> {
+                    #hl_crate_name ::PacketTable::open_as::<Self>(loc, name, ())
+                }
+            }
+        }
+    }
+}
+
+/// Abort on an unsized field other than the last one,
+/// so that the error is reported by the derive rather than by the generated code.
+fn check_unsized_fields(fields: &[Field], generics: &Generics) {
+    if let Some((_, init)) = fields.split_last()
+        && let Some(field) = init.iter().find(|field| is_unsized_field(field, generics))
+    {
+        abort!(field.ty, "only the last field may be unsized");
+    }
+}
+
+#[proc_macro_derive(H5TypeUnsized, attributes(h5))]
 pub fn derive_h5type_unsized(input: TokenStream) -> TokenStream {
     let PreDerive {
         attrs,
@@ -97,23 +324,27 @@ pub fn derive_h5type_unsized(input: TokenStream) -> TokenStream {
     } = pre_derive(input);
 
     find_repr(&attrs, &["C", "transparent"]).expect("expect repr(C) or repr(transparent)");
+    let table = find_table(&attrs);
 
-    let calculate_type = match data {
+    let fields = match data {
         Data::Struct(data) => match data.fields {
-            Fields::Named(fields) => {
-                let fields = fields.named.into_iter().collect::<Vec<_>>();
-                let stats = map_compound(fields, &dst_crate_name);
-                quote!(#(#stats)*)
-            }
-            Fields::Unnamed(fields) => {
-                let fields = fields.unnamed.into_iter().collect::<Vec<_>>();
-                let stats = map_compound(fields, &dst_crate_name);
-                quote!(#(#stats)*)
-            }
+            Fields::Named(fields) => fields.named.into_iter().collect::<Vec<_>>(),
+            Fields::Unnamed(fields) => fields.unnamed.into_iter().collect::<Vec<_>>(),
             _ => unimplemented!(),
         },
         _ => unimplemented!(),
     };
+    check_unsized_fields(&fields, &generics);
+    let helpers = if table {
+        let is_dst = fields
+            .last()
+            .is_some_and(|field| is_unsized_field(field, &generics));
+        table_helpers(&struct_name, &generics, &generic_inputs, is_dst)
+    } else {
+        quote!()
+    };
+    let stats = map_compound(fields, &dst_crate_name);
+    let calculate_type = quote!(#(#stats)*);
 
     let output = quote! {
         impl #generics #dst_crate_name ::H5TypeUnsized for #struct_name #generic_inputs {
@@ -122,28 +353,42 @@ pub fn derive_h5type_unsized(input: TokenStream) -> TokenStream {
                 let mut fields = vec![];
                 let layout = ::core::alloc::Layout::new::<()>();
                 #calculate_type
-                let layout = layout.pad_to_align();
-                debug_assert_eq!(layout, ::core::alloc::Layout::for_value(self));
-                let ty = #dst_crate_name ::__internal::CompoundType { fields, size: layout.size() };
+                // The actual layout may be larger because of `align` or trailing padding.
+                let actual = ::core::alloc::Layout::for_value(self);
+                debug_assert!(layout.pad_to_align().size() <= actual.size());
+                debug_assert!(layout.align() <= actual.align());
+                let ty = #dst_crate_name ::__internal::CompoundType { fields, size: actual.size() };
                 #dst_crate_name ::__internal::TypeDescriptor::Compound(ty)
             }
         }
+
+        #helpers
     };
     TokenStream::from(output)
 }
 
 fn map_compound(
-    fields: impl IntoIterator<Item = Field>,
+    fields: Vec<Field>,
     dst_crate_name: &proc_macro2::TokenStream,
 ) -> Vec<proc_macro2::TokenStream> {
+    let indices = member_indices(&fields);
     fields
         .into_iter()
+        .zip(indices)
         .enumerate()
-        .map(|(i, field)| {
+        .map(|(i, (field, index))| {
             let name = field
                 .ident
                 .unwrap_or_else(|| parse_str::<Ident>(&i.to_string()).unwrap());
-            let name_str = name.to_string();
+            // Raw identifiers are stored without the `r#` prefix.
+            let name_str = name.unraw().to_string();
+            if name_str.len() > MAX_MEMBER_NAME_LEN {
+                abort!(
+                    name,
+                    "member name is longer than {} bytes",
+                    MAX_MEMBER_NAME_LEN
+                );
+            }
             quote! {
                 let new_layout = ::core::alloc::Layout::for_value(&self. #name);
                 let (layout, offset) = layout.extend(new_layout).unwrap();
@@ -151,7 +396,7 @@ fn map_compound(
                     #name_str,
                     #dst_crate_name ::H5TypeUnsized::type_descriptor(&self. #name),
                     offset,
-                    #i,
+                    #index,
                 ));
             }
         })
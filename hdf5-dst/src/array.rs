@@ -0,0 +1,110 @@
+use crate::{ext::type_from_null, H5TypeUnsized};
+use hdf5::types::{CompoundField, CompoundType, TypeDescriptor};
+use std::{alloc::Layout, marker::PhantomData, ptr::Pointee};
+
+/// The layout of `N` consecutive DST records with the same metadata.
+///
+/// An array of DST is not expressible in Rust. This type describes it as a compound
+/// with `N` identically-typed members named `0`, `1`, ..., which has the same
+/// layout as `N` consecutive records in a [`FixedVec<T>`](dst_container::FixedVec).
+pub struct UnsizedArray<T: ?Sized, const N: usize> {
+    metadata: <T as Pointee>::Metadata,
+    _p: PhantomData<*const T>,
+}
+
+impl<T: ?Sized, const N: usize> UnsizedArray<T, N> {
+    /// Create the array layout with the metadata of the records.
+    pub fn new(metadata: <T as Pointee>::Metadata) -> Self {
+        Self {
+            metadata,
+            _p: PhantomData,
+        }
+    }
+
+    /// Get the metadata of the records.
+    pub fn metadata(&self) -> <T as Pointee>::Metadata {
+        self.metadata
+    }
+
+    /// Get the layout of one record.
+    pub fn element_layout(&self) -> Layout {
+        let ptr: *const T = std::ptr::from_raw_parts(std::ptr::null::<()>(), self.metadata);
+        // SAFETY: the layout only depends on the metadata.
+        unsafe { Layout::for_value_raw(ptr) }
+    }
+
+    /// Get the layout of the `N` records.
+    pub fn layout(&self) -> Layout {
+        Layout::from_size_align(
+            self.element_layout().size() * N,
+            self.element_layout().align(),
+        )
+        .unwrap()
+    }
+
+    /// Get the offset of the `i`-th record.
+    pub fn offset(&self, i: usize) -> usize {
+        assert!(i < N);
+        self.element_layout().size() * i
+    }
+}
+
+impl<T: ?Sized, const N: usize> Clone for UnsizedArray<T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, const N: usize> Copy for UnsizedArray<T, N> {}
+
+impl<T: ?Sized + H5TypeUnsized, const N: usize> UnsizedArray<T, N> {
+    /// Get the [`TypeDescriptor`] of the `N` records.
+    ///
+    /// This is not an implementation of [`H5TypeUnsized`], because the generic callers
+    /// only pass the pointee metadata, which is `()` for this sized type.
+    /// Pass the descriptor to `dtype_as` instead.
+    pub fn type_descriptor(&self) -> TypeDescriptor {
+        let ty = type_from_null::<T>(self.metadata);
+        let fields = (0..N)
+            .map(|i| CompoundField::new(&i.to_string(), ty.clone(), self.offset(i), i))
+            .collect();
+        TypeDescriptor::Compound(CompoundType {
+            fields,
+            size: self.layout().size(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use dst_container::*;
+    use hdf5::types::TypeDescriptor;
+    use std::mem::MaybeUninit;
+
+    #[test]
+    fn descriptor() {
+        let arr = UnsizedArray::<UnsizedSlice<u32, u64>, 2>::new(3);
+        assert_eq!(arr.element_layout().size(), 32);
+        assert_eq!(arr.layout().size(), 64);
+        let TypeDescriptor::Compound(ty) = arr.type_descriptor() else {
+            unreachable!()
+        };
+        assert_eq!(ty.size, 64);
+        assert_eq!(ty.fields.len(), 2);
+        assert_eq!(ty.fields[0].name, "0");
+        assert_eq!(ty.fields[0].offset, 0);
+        assert_eq!(ty.fields[1].name, "1");
+        assert_eq!(ty.fields[1].offset, 32);
+        assert_eq!(ty.fields[0].ty, ty.fields[1].ty);
+
+        let mut vec = FixedVec::<UnsizedSlice<u32, u64>>::new(3);
+        unsafe {
+            vec.push_with(|data| {
+                data.header.write(0);
+                MaybeUninit::copy_from_slice(&mut data.slice, &[1, 2, 3]);
+            })
+        };
+        assert_eq!(ty.fields[0].ty, vec[0].type_descriptor());
+    }
+}
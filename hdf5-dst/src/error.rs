@@ -0,0 +1,106 @@
+use hdf5::types::TypeDescriptor;
+use std::fmt::{Display, Formatter};
+
+/// The error type of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// Error from HDF5.
+    Hdf5(hdf5::Error),
+    /// The in-memory descriptor doesn't match the datatype of the container.
+    DescriptorMismatch {
+        /// The datatype of the container.
+        expected: Box<TypeDescriptor>,
+        /// The in-memory descriptor.
+        actual: Box<TypeDescriptor>,
+    },
+    /// The shape of the container doesn't match the data.
+    ShapeMismatch {
+        /// The expected rank.
+        ndim: usize,
+        /// The expected number of elements, if 1-dimensional.
+        len: Option<usize>,
+        /// The shape of the container.
+        shape: Vec<usize>,
+    },
+    /// The header of a length-prefixed slice doesn't match the slice length.
+    PrefixMismatch {
+        /// Index of the record, or `None` for the slice length of the non-empty buffer.
+        index: Option<usize>,
+        /// The header, or the slice length of the records.
+        header: usize,
+        /// The slice length.
+        len: usize,
+    },
+    /// The pointee metadata can't be inferred from the container.
+    MetadataInferenceFailed {
+        /// The reason of the failure.
+        reason: &'static str,
+    },
+    /// The compound member name is rejected by [`check_member_name`](crate::check_member_name).
+    InvalidMemberName {
+        /// The name.
+        name: String,
+        /// The reason of the rejection.
+        reason: &'static str,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hdf5(e) => e.fmt(f),
+            Self::DescriptorMismatch { expected, actual } => {
+                write!(f, "Layout mismatch: expected {expected:?}, got {actual:?}.")
+            }
+            Self::ShapeMismatch { ndim, len, shape } => match len {
+                Some(len) if shape.len() == *ndim => {
+                    write!(f, "Expected {} elements, got {len}.", shape[0])
+                }
+                _ => write!(f, "Expected rank {ndim}, got shape {shape:?}."),
+            },
+            Self::PrefixMismatch { index, header, len } => match index {
+                Some(index) => write!(
+                    f,
+                    "The header {header} of record {index} doesn't match the slice length {len}."
+                ),
+                None => write!(
+                    f,
+                    "The slice length {header} of the records doesn't match the buffer {len}."
+                ),
+            },
+            Self::MetadataInferenceFailed { reason } => {
+                write!(f, "Failed to infer the metadata: {reason}.")
+            }
+            Self::InvalidMemberName { name, reason } => {
+                write!(f, "Invalid member name {name:?}: {reason}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Hdf5(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<hdf5::Error> for Error {
+    fn from(value: hdf5::Error) -> Self {
+        Self::Hdf5(value)
+    }
+}
+
+impl From<Error> for hdf5::Error {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::Hdf5(e) => e,
+            e => hdf5::Error::Internal(e.to_string()),
+        }
+    }
+}
+
+/// The result type of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
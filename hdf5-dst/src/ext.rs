@@ -1,10 +1,12 @@
 use std::ptr::Pointee;
 
-use crate::H5TypeUnsized;
+use crate::{check_descriptor, Error, H5TypeUnsized, Result};
 use dst_container::*;
 use hdf5::{
-    h5try, types::TypeDescriptor, Attribute, AttributeBuilder, AttributeBuilderEmpty, Container,
-    Dataset, DatasetBuilder, DatasetBuilderEmpty, Datatype, Extents, Object, Result,
+    h5try,
+    types::{CompoundField, CompoundType, TypeDescriptor},
+    Attribute, AttributeBuilder, AttributeBuilderEmpty, Container, Dataset, DatasetBuilder,
+    DatasetBuilderEmpty, Datatype, Extents, H5Type, Object,
 };
 use hdf5_sys::{
     h5a::{H5Aread, H5Awrite},
@@ -36,6 +38,23 @@ pub trait ContainerExt {
         &self,
         val: &mut T::Target,
     ) -> Result<()>;
+
+    /// Check that the datatype of `T` with the pointee metadata matches
+    /// the datatype of the dataset/attribute, without reading or writing.
+    fn check_layout<T: ?Sized + H5TypeUnsized>(
+        &self,
+        metadata: <T as Pointee>::Metadata,
+    ) -> Result<()>;
+
+    /// Reads a 1-dimensional dataset/attribute of length-prefixed slices.
+    ///
+    /// The headers are read first, and they should all equal the slice length.
+    /// If the buffer is empty, it is recreated with that length.
+    /// Returns [`Error::MetadataInferenceFailed`] if the records have no `header` member.
+    fn read_unsized_prefixed<H: H5Type + Into<usize>, T: H5Type>(
+        &self,
+        buf: &mut FixedVec<UnsizedSlice<H, T>>,
+    ) -> Result<()>;
 }
 
 /// Determine if the container is attribute.
@@ -43,67 +62,92 @@ fn is_attr(obj: &Object) -> bool {
     obj.id_type() == H5I_ATTR
 }
 
-fn write_container(c: &Container, mem_dtype: TypeDescriptor, buf: *const ()) -> Result<()> {
+/// Check the in-memory descriptor against the datatype of the container.
+fn check_dtype(c: &Container, mem_dtype: &TypeDescriptor) -> Result<Datatype> {
     let file_dtype = c.dtype()?;
-    let mem_dtype = Datatype::from_descriptor(&mem_dtype)?;
-    debug_assert_eq!(file_dtype, mem_dtype);
+    let mem = Datatype::from_descriptor(mem_dtype)?;
+    if file_dtype != mem {
+        return Err(Error::DescriptorMismatch {
+            expected: Box::new(file_dtype.to_descriptor()?),
+            actual: Box::new(mem_dtype.clone()),
+        });
+    }
+    Ok(mem)
+}
+
+/// Read or write the whole container with the in-memory descriptor.
+///
+/// The descriptor is always checked before reading, because a mismatched read
+/// fills the buffer with garbage. Writes are only checked in debug builds.
+fn io_container(c: &Container, mem_dtype: TypeDescriptor, buf: *mut (), write: bool) -> Result<()> {
+    let mem_dtype = if !write || cfg!(debug_assertions) {
+        check_dtype(c, &mem_dtype)?
+    } else {
+        Datatype::from_descriptor(&mem_dtype)?
+    };
+    io_container_as(c, &mem_dtype, buf, write)
+}
 
+/// Read or write the whole container with the in-memory datatype, without checking.
+fn io_container_as(c: &Container, mem_dtype: &Datatype, buf: *mut (), write: bool) -> Result<()> {
     let obj_id = c.id();
     let tp_id = mem_dtype.id();
-    if is_attr(c) {
-        h5try!(H5Awrite(obj_id, tp_id, buf.cast()));
-    } else {
-        h5try!(H5Dwrite(
+    match (is_attr(c), write) {
+        (true, true) => h5try!(H5Awrite(obj_id, tp_id, buf.cast())),
+        (true, false) => h5try!(H5Aread(obj_id, tp_id, buf.cast())),
+        (false, true) => h5try!(H5Dwrite(
             obj_id,
             tp_id,
             H5S_ALL,
             H5S_ALL,
             H5P_DEFAULT,
             buf.cast()
-        ));
-    }
-    Ok(())
-}
-
-fn read_container(c: &Container, mem_dtype: TypeDescriptor, buf: *mut ()) -> Result<()> {
-    let file_dtype = c.dtype()?;
-    let mem_dtype = Datatype::from_descriptor(&mem_dtype)?;
-    debug_assert_eq!(file_dtype, mem_dtype);
-
-    let obj_id = c.id();
-    let tp_id = mem_dtype.id();
-    if is_attr(c) {
-        h5try!(H5Aread(obj_id, tp_id, buf.cast()));
-    } else {
-        h5try!(H5Dread(
+        )),
+        (false, false) => h5try!(H5Dread(
             obj_id,
             tp_id,
             H5S_ALL,
             H5S_ALL,
             H5P_DEFAULT,
             buf.cast()
-        ));
+        )),
+    };
+    Ok(())
+}
+
+fn write_container(c: &Container, mem_dtype: TypeDescriptor, buf: *const ()) -> Result<()> {
+    io_container(c, mem_dtype, buf.cast_mut(), true)
+}
+
+fn read_container(c: &Container, mem_dtype: TypeDescriptor, buf: *mut ()) -> Result<()> {
+    io_container(c, mem_dtype, buf, false)
+}
+
+/// Check the rank of the container, and the length if it is 1-dimensional.
+fn check_shape(c: &Container, ndim: usize, len: Option<usize>) -> Result<()> {
+    let shape = c.shape();
+    if shape.len() != ndim || len.is_some_and(|len| shape[0] != len) {
+        return Err(Error::ShapeMismatch { ndim, len, shape });
     }
     Ok(())
 }
 
 impl ContainerExt for Container {
     fn write_unsized<T: ?Sized + H5TypeUnsized>(&self, v: &FixedVec<T>) -> Result<()> {
-        debug_assert_eq!(self.ndim(), 1);
-        debug_assert_eq!(self.shape()[0], v.len());
+        check_shape(self, 1, Some(v.len()))?;
         let (ptr, _) = v.as_ptr().to_raw_parts();
         // SAFETY: only the metadata of the reference is used.
         write_container(self, unsafe { v.get_unchecked(0) }.type_descriptor(), ptr)
     }
 
     fn write_scalar_unsized<T: ?Sized + H5TypeUnsized>(&self, val: &T) -> Result<()> {
-        debug_assert_eq!(self.ndim(), 0);
+        check_shape(self, 0, None)?;
         let (ptr, _) = (val as *const T).to_raw_parts();
         write_container(self, val.type_descriptor(), ptr)
     }
 
     fn read_unsized<T: ?Sized + H5TypeUnsized>(&self, v: &mut FixedVec<T>) -> Result<()> {
-        debug_assert_eq!(self.ndim(), 1);
+        check_shape(self, 1, None)?;
         let old_len = v.len();
         let new_len = self.shape()[0];
         v.reserve(new_len);
@@ -121,12 +165,77 @@ impl ContainerExt for Container {
         &self,
         val: &mut T::Target,
     ) -> Result<()> {
-        debug_assert_eq!(self.ndim(), 0);
+        check_shape(self, 0, None)?;
         let (ptr, metadata) = (val as *mut T::Target).to_raw_parts();
         // SAFETY: also done in dst-container
         let val_src: &T = unsafe { &*std::ptr::from_raw_parts(ptr, metadata) };
         read_container(self, val_src.type_descriptor(), ptr)
     }
+
+    fn check_layout<T: ?Sized + H5TypeUnsized>(
+        &self,
+        metadata: <T as Pointee>::Metadata,
+    ) -> Result<()> {
+        check_dtype(self, &type_from_null::<T>(metadata))?;
+        Ok(())
+    }
+
+    fn read_unsized_prefixed<H: H5Type + Into<usize>, T: H5Type>(
+        &self,
+        buf: &mut FixedVec<UnsizedSlice<H, T>>,
+    ) -> Result<()> {
+        check_shape(self, 1, None)?;
+        let count = self.shape()[0];
+        if count == 0 {
+            return Ok(());
+        }
+        match self.dtype()?.to_descriptor()? {
+            TypeDescriptor::Compound(ty) if ty.fields.iter().any(|f| f.name == "header") => {}
+            TypeDescriptor::Compound(_) => {
+                return Err(Error::MetadataInferenceFailed {
+                    reason: "the compound has no header member",
+                });
+            }
+            _ => {
+                return Err(Error::MetadataInferenceFailed {
+                    reason: "the datatype is not a compound",
+                });
+            }
+        }
+        // Read the header member only.
+        let header_dtype = Datatype::from_descriptor(&TypeDescriptor::Compound(CompoundType {
+            fields: vec![CompoundField::new("header", H::type_descriptor(), 0, 0)],
+            size: size_of::<H>(),
+        }))?;
+        let mut headers = Vec::<H>::with_capacity(count);
+        io_container_as(self, &header_dtype, headers.as_mut_ptr().cast(), false)?;
+        // SAFETY: read successfully.
+        unsafe {
+            headers.set_len(count);
+        }
+        let mut headers = headers.into_iter().map(Into::into);
+        let len = headers.next().unwrap_or_default();
+        if let Some((index, header)) = headers.enumerate().find(|(_, header)| *header != len) {
+            return Err(Error::PrefixMismatch {
+                index: Some(index + 1),
+                header,
+                len,
+            });
+        }
+        if buf.is_empty() {
+            *buf = FixedVec::new(len);
+        } else {
+            let (_, metadata) = buf.as_ptr().to_raw_parts();
+            if metadata != len {
+                return Err(Error::PrefixMismatch {
+                    index: None,
+                    header: len,
+                    len: metadata,
+                });
+            }
+        }
+        self.read_unsized(buf)
+    }
 }
 
 /// DST extensions for [`DatasetBuilder`] and [`AttributeBuilder`].
@@ -157,7 +266,9 @@ pub trait ContainerBuilderExt: Sized {
     ) -> Self::DataUnsizedBuilder<'a, T>;
 }
 
-fn type_from_null<T: ?Sized + H5TypeUnsized>(metadata: <T as Pointee>::Metadata) -> TypeDescriptor {
+pub(crate) fn type_from_null<T: ?Sized + H5TypeUnsized>(
+    metadata: <T as Pointee>::Metadata,
+) -> TypeDescriptor {
     // SAFETY: is it safe?
     unsafe {
         let ptr: *const T = std::ptr::from_raw_parts(std::ptr::null::<()>(), metadata);
@@ -213,6 +324,7 @@ pub struct DatasetBuilderDataUnsized<'a, T: ?Sized> {
 impl<T: ?Sized + H5TypeUnsized> DatasetBuilderDataUnsized<'_, T> {
     /// Create the [`Dataset`] and fill the value.
     pub fn create<'n>(self, name: impl Into<Option<&'n str>>) -> Result<Dataset> {
+        check_descriptor(&type_from_null::<T>(self.data.metadata()))?;
         let dataset = self.builder.shape(self.data.shape()).create(name.into())?;
         match self.data {
             UnsizedData::Scalar(data) => dataset.write_scalar_unsized(data),
@@ -257,6 +369,7 @@ pub struct AttributeBuilderDataUnsized<'a, T: ?Sized> {
 impl<T: ?Sized + H5TypeUnsized> AttributeBuilderDataUnsized<'_, T> {
     /// Create the [`Attribute`] and fill the value.
     pub fn create(self, name: impl AsRef<str>) -> Result<Attribute> {
+        check_descriptor(&type_from_null::<T>(self.data.metadata()))?;
         let attr = self
             .builder
             .shape(self.data.shape())
@@ -299,6 +412,7 @@ impl ContainerBuilderExt for AttributeBuilder {
 mod test {
     use crate::*;
     use dst_container::*;
+    use hdf5::H5Type;
     use std::mem::MaybeUninit;
     use tempfile::NamedTempFile;
 
@@ -336,6 +450,161 @@ mod test {
         }
     }
 
+    #[test]
+    fn shape_mismatch() {
+        let file = NamedTempFile::new().unwrap();
+
+        let mut vec: FixedVec<Data> = FixedVec::new(2);
+        for i in 0..3 {
+            unsafe {
+                vec.push_with(|slice| {
+                    slice.header.write(i);
+                    MaybeUninit::copy_from_slice(&mut slice.slice, &[i as u64; 2]);
+                })
+            };
+        }
+        let scalar: Box<Data> = unsafe {
+            Box::<Data>::new_unsized_with(2, |slice| {
+                slice.header.write(42);
+                MaybeUninit::copy_from_slice(&mut slice.slice, &[4, 2]);
+            })
+        };
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let array = data
+            .new_dataset_builder()
+            .empty_unsized::<Data>(2)
+            .shape(3)
+            .create("array")
+            .unwrap();
+        let single = data
+            .new_dataset_builder()
+            .empty_unsized::<Data>(2)
+            .create("scalar")
+            .unwrap();
+
+        array.write_unsized(&vec).unwrap();
+        assert!(array.write_scalar_unsized(scalar.as_ref()).is_err());
+        let mut read_vec: FixedVec<Data> = FixedVec::new(2);
+        array.read_unsized(&mut read_vec).unwrap();
+        assert_eq!(read_vec[2].header, 2);
+
+        single.write_scalar_unsized(scalar.as_ref()).unwrap();
+        assert!(single.write_unsized(&vec).is_err());
+        assert!(single.read_unsized(&mut read_vec).is_err());
+        assert_eq!(read_vec.len(), 3);
+
+        let mut short = FixedVec::<Data>::new(2);
+        unsafe {
+            short.push_with(|slice| {
+                slice.header.write(0);
+                MaybeUninit::copy_from_slice(&mut slice.slice, &[0, 0]);
+            })
+        };
+        assert!(matches!(
+            array.write_unsized(&short),
+            Err(Error::ShapeMismatch {
+                ndim: 1,
+                len: Some(1),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn read_unsized_prefixed() {
+        type Prefixed = UnsizedSlice<u16, u64>;
+
+        fn prefixed(len: usize, headers: &[u16]) -> FixedVec<Prefixed> {
+            let mut vec = FixedVec::<Prefixed>::new(len);
+            for (i, &header) in headers.iter().enumerate() {
+                unsafe {
+                    vec.push_with(|slice| {
+                        slice.header.write(header);
+                        for (j, s) in slice.slice.iter_mut().enumerate() {
+                            s.write((i * len + j) as u64);
+                        }
+                    })
+                };
+            }
+            vec
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let data = hdf5::File::create(file.path()).unwrap();
+        for (name, len) in [("two", 2), ("five", 5)] {
+            let vec = prefixed(len, &[len as u16; 3]);
+            let dataset = data
+                .new_dataset_builder()
+                .with_data_unsized::<Prefixed>(&vec)
+                .create(name)
+                .unwrap();
+            let mut read = FixedVec::<Prefixed>::new(1);
+            dataset.read_unsized_prefixed(&mut read).unwrap();
+            assert_eq!(read.len(), 3);
+            for i in 0..3 {
+                assert_eq!(read[i].header as usize, len);
+                assert_eq!(&read[i].slice, &vec[i].slice);
+            }
+            dataset.read_unsized_prefixed(&mut read).unwrap();
+            assert_eq!(read.len(), 6);
+        }
+
+        let vec = prefixed(2, &[2, 3]);
+        let dataset = data
+            .new_dataset_builder()
+            .with_data_unsized::<Prefixed>(&vec)
+            .create("mismatch")
+            .unwrap();
+        assert!(matches!(
+            dataset.read_unsized_prefixed(&mut FixedVec::<Prefixed>::new(2)),
+            Err(Error::PrefixMismatch {
+                index: Some(1),
+                header: 3,
+                len: 2
+            })
+        ));
+        let two = data.dataset("two").unwrap();
+        assert!(matches!(
+            two.read_unsized_prefixed(&mut prefixed(3, &[3])),
+            Err(Error::PrefixMismatch {
+                index: None,
+                header: 2,
+                len: 3
+            })
+        ));
+
+        let plain = data
+            .new_dataset::<u64>()
+            .shape(3)
+            .create("plain")
+            .unwrap();
+        assert!(matches!(
+            plain.read_unsized_prefixed(&mut FixedVec::<Prefixed>::new(2)),
+            Err(Error::MetadataInferenceFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn check_layout() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let dataset = data
+            .new_dataset_builder()
+            .empty_unsized::<Data>(6)
+            .shape(2)
+            .create("data")
+            .unwrap();
+        dataset.check_layout::<Data>(6).unwrap();
+        assert!(dataset.check_layout::<Data>(5).is_err());
+        assert!(dataset.check_layout::<UnsizedSlice<u32, u32>>(6).is_err());
+        assert!(matches!(
+            dataset.check_layout::<u32>(()),
+            Err(Error::DescriptorMismatch { actual, .. }) if *actual == <u32 as H5Type>::type_descriptor()
+        ));
+    }
+
     #[test]
     fn attribute() {
         let file = NamedTempFile::new().unwrap();
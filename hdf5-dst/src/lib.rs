@@ -1,12 +1,22 @@
 //! DST extension APIs for [`hdf5`].
 
 #![feature(ptr_metadata)]
+#![feature(layout_for_ptr)]
 #![cfg_attr(test, feature(maybe_uninit_write_slice))]
 #![warn(missing_docs)]
 
+mod array;
+pub use array::*;
+
+mod error;
+pub use error::*;
+
 mod ext;
 pub use ext::*;
 
+mod name;
+pub use name::*;
+
 use dst_container::{UnsizedSlice, UnsizedStr};
 use hdf5::{
     types::{CompoundField, CompoundType, TypeDescriptor},
@@ -132,4 +142,116 @@ mod test {
         assert_eq!(ty.fields[1].offset, 8);
         assert_eq!(ty.fields[2].offset, 16);
     }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C)]
+    struct Inner1 {
+        a: u8,
+        b: u32,
+    }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C)]
+    struct Inner2 {
+        x: f64,
+        y: u16,
+    }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C)]
+    struct Nested {
+        inner1: Inner1,
+        flag: u8,
+        inner2: Inner2,
+    }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C, align(16))]
+    struct Aligned {
+        a: u8,
+    }
+
+    #[test]
+    fn nested_sized() {
+        let TypeDescriptor::Compound(ty) = Nested::default().type_descriptor() else {
+            unreachable!()
+        };
+        assert_eq!(ty.size, size_of::<Nested>());
+        assert_eq!(ty.fields[0].offset, std::mem::offset_of!(Nested, inner1));
+        assert_eq!(ty.fields[1].offset, std::mem::offset_of!(Nested, flag));
+        assert_eq!(ty.fields[2].offset, std::mem::offset_of!(Nested, inner2));
+        assert_eq!(ty.fields[0].ty, Inner1::default().type_descriptor());
+        let TypeDescriptor::Compound(inner2) = &ty.fields[2].ty else {
+            unreachable!()
+        };
+        assert_eq!(inner2.size, size_of::<Inner2>());
+        assert_eq!(inner2.fields[1].offset, std::mem::offset_of!(Inner2, y));
+
+        let TypeDescriptor::Compound(ty) = Aligned::default().type_descriptor() else {
+            unreachable!()
+        };
+        assert_eq!(ty.size, 16);
+    }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C)]
+    struct Reordered {
+        #[h5(index = 2)]
+        b: u64,
+        #[h5(index = 0)]
+        c: u16,
+        #[h5(index = 1)]
+        a: u8,
+    }
+
+    #[derive(H5TypeUnsized, Default)]
+    #[repr(C)]
+    struct PartlyReordered {
+        #[h5(index = 1)]
+        x: u32,
+        #[h5(index = 0)]
+        y: u32,
+        z: u8,
+    }
+
+    #[test]
+    fn member_index() {
+        let TypeDescriptor::Compound(ty) = Reordered::default().type_descriptor() else {
+            unreachable!()
+        };
+        let names = ty
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["b", "c", "a"]);
+        let indices = ty.fields.iter().map(|f| f.index).collect::<Vec<_>>();
+        assert_eq!(indices, [2, 0, 1]);
+        assert_eq!(ty.fields[0].offset, std::mem::offset_of!(Reordered, b));
+        assert_eq!(ty.fields[1].offset, std::mem::offset_of!(Reordered, c));
+        assert_eq!(ty.fields[2].offset, std::mem::offset_of!(Reordered, a));
+
+        let TypeDescriptor::Compound(ty) = PartlyReordered::default().type_descriptor() else {
+            unreachable!()
+        };
+        let indices = ty.fields.iter().map(|f| f.index).collect::<Vec<_>>();
+        assert_eq!(indices, [1, 0, 2]);
+    }
+
+    #[derive(MaybeUninitProject, H5TypeUnsized)]
+    #[repr(C)]
+    struct Raw {
+        r#type: i32,
+        slice: [u8],
+    }
+
+    #[test]
+    fn raw_ident() {
+        let raw: Box<Raw> = unsafe { Box::<Raw>::new_zeroed_unsized(2).assume_init() };
+        let TypeDescriptor::Compound(ty) = raw.type_descriptor() else {
+            unreachable!()
+        };
+        assert_eq!(ty.fields[0].name, "type");
+        check_descriptor(&TypeDescriptor::Compound(ty)).unwrap();
+    }
 }
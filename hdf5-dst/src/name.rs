@@ -0,0 +1,89 @@
+use crate::{Error, Result};
+use hdf5::types::TypeDescriptor;
+
+/// The maximum length of a compound member name in bytes.
+///
+/// HDF5 itself has no hard limit, but longer names are impractical
+/// and rejected by some tools.
+pub const MAX_MEMBER_NAME_LEN: usize = 1024;
+
+/// Check a compound member name.
+///
+/// The names are stored as UTF-8 bytes. Empty names, names containing NUL,
+/// and names longer than [`MAX_MEMBER_NAME_LEN`] are rejected.
+pub fn check_member_name(name: &str) -> Result<()> {
+    let reason = if name.is_empty() {
+        "empty"
+    } else if name.contains('\0') {
+        "contains NUL"
+    } else if name.len() > MAX_MEMBER_NAME_LEN {
+        "longer than MAX_MEMBER_NAME_LEN"
+    } else {
+        return Ok(());
+    };
+    Err(Error::InvalidMemberName {
+        name: name.to_string(),
+        reason,
+    })
+}
+
+/// Check the member names in the descriptor recursively.
+pub fn check_descriptor(ty: &TypeDescriptor) -> Result<()> {
+    match ty {
+        TypeDescriptor::Compound(ty) => ty.fields.iter().try_for_each(|f| {
+            check_member_name(&f.name)?;
+            check_descriptor(&f.ty)
+        }),
+        TypeDescriptor::FixedArray(ty, _) | TypeDescriptor::VarLenArray(ty) => check_descriptor(ty),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use hdf5::{
+        types::{CompoundField, CompoundType, TypeDescriptor},
+        H5Type,
+    };
+
+    fn compound(name: &str) -> TypeDescriptor {
+        TypeDescriptor::Compound(CompoundType {
+            fields: vec![CompoundField::new(
+                name,
+                <i32 as H5Type>::type_descriptor(),
+                0,
+                0,
+            )],
+            size: 4,
+        })
+    }
+
+    #[test]
+    fn names() {
+        check_member_name("温度").unwrap();
+        check_member_name(&"a".repeat(MAX_MEMBER_NAME_LEN)).unwrap();
+        assert!(check_member_name("").is_err());
+        assert!(check_member_name("a\0b").is_err());
+        assert!(check_member_name(&"a".repeat(MAX_MEMBER_NAME_LEN + 1)).is_err());
+
+        check_descriptor(&compound("温度")).unwrap();
+        assert!(check_descriptor(&compound("")).is_err());
+        let nested = TypeDescriptor::VarLenArray(Box::new(compound("a\0b")));
+        assert!(check_descriptor(&nested).is_err());
+    }
+
+    #[test]
+    fn utf8_round_trip() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let ty = compound("温度");
+        let dataset = file
+            .new_dataset_builder()
+            .empty_as(&ty)
+            .shape(1)
+            .create("data")
+            .unwrap();
+        assert_eq!(dataset.dtype().unwrap().to_descriptor().unwrap(), ty);
+    }
+}
@@ -0,0 +1,12 @@
+use hdf5_dst::H5TypeUnsized;
+
+#[derive(H5TypeUnsized)]
+#[repr(C)]
+struct Foo {
+    #[h5(index = 1)]
+    a: i32,
+    #[h5(index = 1)]
+    b: i32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use hdf5_dst::H5TypeUnsized;
+
+#[derive(H5TypeUnsized)]
+#[repr(C)]
+struct Foo {
+    slice: [f32],
+    field: i32,
+}
+
+fn main() {}
@@ -0,0 +1,194 @@
+//! Benchmarks of [`PacketTable`] writing.
+//!
+//! Set `HDF5_HL_BENCH_DIR` to place the temporary files on a specific file system.
+//!
+//! To compare the buffered writer with per-record push, save a baseline and
+//! compare against it:
+//!
+//! ```sh
+//! cargo bench --bench packet_table -- --save-baseline main buffer_vs_push
+//! cargo bench --bench packet_table -- --baseline main buffer_vs_push
+//! ```
+//!
+//! `buffer_vs_push/buffered` is expected to beat `buffer_vs_push/push`.
+//!
+//! `chunk_batches` compares appending many small batches one by one
+//! with [`PacketTable::append_many`].
+//!
+//! `descriptor_cache` compares appending small batches to a [`PacketTable`],
+//! which checks the element type per call, with a [`TypedPacketTable`],
+//! which caches the [`Datatype`](hdf5::Datatype).
+//!
+//! Every iteration appends to a fresh table created outside the timing,
+//! so that the samples measure the same workload rather than a growing table.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use hdf5_hl::*;
+use tempfile::NamedTempFile;
+
+const RECORDS: i32 = 65536;
+
+fn temp_file() -> NamedTempFile {
+    match std::env::var_os("HDF5_HL_BENCH_DIR") {
+        Some(dir) => NamedTempFile::new_in(dir).unwrap(),
+        None => NamedTempFile::new().unwrap(),
+    }
+}
+
+/// A table in its own file, dropped before the file.
+struct Fixture<T> {
+    table: T,
+    _file: hdf5::File,
+    _temp: NamedTempFile,
+}
+
+/// Create an empty table of `i32`, and wrap it by `f`.
+fn fixture<T>(chunk: usize, f: impl FnOnce(PacketTable) -> T) -> Fixture<T> {
+    let temp = temp_file();
+    let file = hdf5::File::create(temp.path()).unwrap();
+    let table = PacketTable::builder(&file)
+        .chunk(chunk)
+        .dtype::<i32>()
+        .create("data")
+        .unwrap();
+    Fixture {
+        table: f(table),
+        _file: file,
+        _temp: temp,
+    }
+}
+
+fn append(table: &mut PacketTable, buf: usize) {
+    if buf == 1 {
+        for i in 0..RECORDS {
+            table.push(&i).unwrap();
+        }
+    } else {
+        let mut writer = PacketTableBufWriter::<i32>::new(table, buf);
+        for i in 0..RECORDS {
+            writer.push(i).unwrap();
+        }
+    }
+}
+
+fn chunk_buffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chunk_buffer");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+    group.sample_size(10);
+    for chunk in [16, 1024, 65536] {
+        for buf in [1, 16, 1024, 65536] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("chunk_{chunk}"), buf),
+                &buf,
+                |b, &buf| {
+                    b.iter_batched(
+                        || fixture(chunk, |table| table),
+                        |mut f| {
+                            append(&mut f.table, buf);
+                            f
+                        },
+                        BatchSize::PerIteration,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn chunk_batches(c: &mut Criterion) {
+    const BATCH: usize = 16;
+
+    let mut group = c.benchmark_group("chunk_batches");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+    group.sample_size(10);
+    let data = (0..RECORDS).collect::<Vec<_>>();
+    let batches = data.chunks(BATCH).collect::<Vec<_>>();
+    for chunk in [1024, 65536] {
+        group.bench_function(BenchmarkId::new("append", chunk), |b| {
+            b.iter_batched(
+                || fixture(chunk, |table| table),
+                |mut f| {
+                    for batch in &batches {
+                        f.table.append(batch).unwrap();
+                    }
+                    f
+                },
+                BatchSize::PerIteration,
+            )
+        });
+        group.bench_function(BenchmarkId::new("append_many", chunk), |b| {
+            b.iter_batched(
+                || fixture(chunk, |table| table),
+                |mut f| {
+                    f.table.append_many(&batches).unwrap();
+                    f
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn descriptor_cache(c: &mut Criterion) {
+    const BATCH: usize = 16;
+
+    let mut group = c.benchmark_group("descriptor_cache");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+    group.sample_size(10);
+    let data = (0..RECORDS).collect::<Vec<_>>();
+    group.bench_function("plain", |b| {
+        b.iter_batched(
+            || fixture(1024, |table| table),
+            |mut f| {
+                for batch in data.chunks(BATCH) {
+                    f.table.append(batch).unwrap();
+                }
+                f
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("typed", |b| {
+        b.iter_batched(
+            || fixture(1024, |table| table.typed::<i32>().unwrap()),
+            |mut f| {
+                for batch in data.chunks(BATCH) {
+                    f.table.append(batch).unwrap();
+                }
+                f
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn buffer_vs_push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("buffer_vs_push");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+    group.sample_size(10);
+    for (name, buf) in [("push", 1), ("buffered", 1024)] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || fixture(1024, |table| table),
+                |mut f| {
+                    append(&mut f.table, buf);
+                    f
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    chunk_buffer,
+    chunk_batches,
+    descriptor_cache,
+    buffer_vs_push
+);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A token to cancel long-running operations from another thread.
+///
+/// The operations check the token between batches,
+/// and return [`Error::Cancelled`](crate::Error::Cancelled) once it is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a new token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the operations observing this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Determine if the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn cancel() {
+        let token = CancelToken::new();
+        let cloned = token.clone();
+        assert!(!cloned.is_cancelled());
+        std::thread::spawn(move || token.cancel()).join().unwrap();
+        assert!(cloned.is_cancelled());
+    }
+}
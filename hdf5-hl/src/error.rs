@@ -0,0 +1,581 @@
+use hdf5::types::TypeDescriptor;
+use std::{
+    ffi::NulError,
+    fmt::{Display, Formatter},
+    io,
+    num::TryFromIntError,
+};
+
+/// The error type of this crate.
+///
+/// The variants carry the data of the failure, e.g., the counts, the paths
+/// and the type descriptors, and the messages are formatted only when displayed.
+/// The failures without a dedicated variant are [`Error::Other`].
+#[derive(Debug)]
+pub enum Error {
+    /// Error from HDF5.
+    Hdf5(hdf5::Error),
+    /// Error from [`hdf5_dst`], except the HDF5 errors, which are converted to [`Error::Hdf5`].
+    Dst(hdf5_dst::Error),
+    /// Error from the reader or writer of the raw bytes.
+    Io(io::Error),
+    /// The memory size of a packet doesn't match the datatype size.
+    PacketSize {
+        /// Size of the datatype.
+        expected: usize,
+        /// Size of the packet in memory.
+        actual: usize,
+    },
+    /// Not enough packets to read.
+    NotEnoughPackets {
+        /// Start index of the read.
+        start: u64,
+        /// Number of packets requested.
+        len: usize,
+        /// Number of packets in the table.
+        num_packets: u64,
+    },
+    /// The range exceeds the packet table.
+    InvalidRange {
+        /// Start index of the range.
+        start: u64,
+        /// End index of the range.
+        end: u64,
+        /// Number of packets in the table.
+        num_packets: u64,
+    },
+    /// The length to truncate to is beyond the end of the packet table.
+    TruncateBeyondEnd {
+        /// The length to truncate to.
+        len: u64,
+        /// Number of packets in the table.
+        num_packets: u64,
+    },
+    /// The range bounds overflow.
+    RangeOverflow,
+    /// A number doesn't fit in the integer type, e.g., the number of packets in `usize`.
+    IntOverflow,
+    /// The chunk to read or process is zero.
+    ZeroChunk,
+    /// The buffer length is zero.
+    ZeroBufLen,
+    /// The append exceeds the max extent of the dataset.
+    CapacityExceeded {
+        /// Number of packets to append.
+        len: u64,
+        /// Number of packets which still fit.
+        remaining: u64,
+    },
+    /// The dataset is not chunked, so it cannot be opened as a packet table.
+    NotChunked,
+    /// The dataset is not 1-dimensional.
+    WrongRank {
+        /// Rank of the dataset.
+        ndim: usize,
+    },
+    /// The max extent of the dataset is not unlimited, so it cannot be appended to.
+    NotUnlimited {
+        /// The max extent.
+        max: u64,
+    },
+    /// The packet table is in a file opened read-only.
+    ///
+    /// It is checked before appending only with the strict
+    /// [`ValidationProfile`](crate::ValidationProfile).
+    ReadOnly {
+        /// Path of the packet table.
+        path: String,
+    },
+    /// The index moves out of the packet table.
+    InvalidIndex {
+        /// The current index.
+        index: u64,
+        /// The offset to move.
+        delta: i64,
+        /// Number of packets in the table.
+        num_packets: u64,
+    },
+    /// The index after a read is not expected.
+    InconsistentIndex {
+        /// The expected index.
+        expected: u64,
+        /// The actual index.
+        actual: u64,
+    },
+    /// Failed to restore the index after a failed read.
+    IndexRestore {
+        /// The index to restore.
+        index: u64,
+        /// The error of the read.
+        error: Box<Error>,
+        /// The error of restoring the index.
+        restore_error: Box<Error>,
+    },
+    /// Failed to roll back a failed append in
+    /// [`PacketTable::append_checked`](crate::PacketTable::append_checked)
+    /// or [`TableGroupWriter::flush`](crate::TableGroupWriter::flush).
+    Rollback {
+        /// Number of packets before the append.
+        len: u64,
+        /// The error of the append.
+        error: Box<Error>,
+        /// The error of the rollback.
+        rollback_error: Box<Error>,
+    },
+    /// Failed to append a batch in [`PacketTable::append_many`](crate::PacketTable::append_many).
+    PartialAppend {
+        /// Number of batches appended before the failure.
+        batches: usize,
+        /// The error of the failed batch.
+        error: Box<Error>,
+    },
+    /// Failed to read the stream in
+    /// [`PacketTable::append_raw_from`](crate::PacketTable::append_raw_from).
+    PartialImport {
+        /// Number of packets appended before the failure.
+        committed: u64,
+        /// The error of the read.
+        source: Box<Error>,
+    },
+    /// The stream ends in the middle of a packet in
+    /// [`PacketTable::append_raw_from`](crate::PacketTable::append_raw_from).
+    TruncatedPacket {
+        /// Number of packets appended.
+        committed: u64,
+        /// Number of the bytes of the incomplete packet.
+        trailing_bytes: usize,
+    },
+    /// Failed to create a table in
+    /// [`PacketTableBuilderTyped::create_many`](crate::PacketTableBuilderTyped::create_many).
+    PartialCreate {
+        /// Name of the failed table.
+        name: String,
+        /// Number of tables created before the failure.
+        created: usize,
+        /// Whether the created tables have been removed.
+        rolled_back: bool,
+        /// The error of the failed table.
+        error: Box<Error>,
+    },
+    /// The stored datatype doesn't match the expected one.
+    SchemaMismatch {
+        /// The expected datatype.
+        expected: Box<TypeDescriptor>,
+        /// The stored datatype.
+        actual: Box<TypeDescriptor>,
+    },
+    /// The output descriptor of a [`RecordTransform`](crate::RecordTransform)
+    /// doesn't match the stored datatype.
+    TransformMismatch {
+        /// The output descriptor of the transform.
+        output: Box<TypeDescriptor>,
+    },
+    /// The type of the fill value doesn't match the datatype.
+    FillValueMismatch {
+        /// The type of the fill value.
+        fill: Box<TypeDescriptor>,
+    },
+    /// The pointee metadata of the packets doesn't match the one of a
+    /// [`TypedPacketTable`](crate::TypedPacketTable), e.g., the slice lengths differ.
+    MetadataMismatch {
+        /// Size of a packet with the expected metadata.
+        expected_size: usize,
+        /// Size of a packet with the actual metadata.
+        actual_size: usize,
+    },
+    /// The operation is cancelled by a [`CancelToken`](crate::CancelToken).
+    Cancelled {
+        /// Number of packets processed before the cancellation.
+        processed: u64,
+        /// Whether the packets written before the cancellation have been removed.
+        /// It is `false` for the readers, and for the writers which keep them,
+        /// as documented by each operation.
+        rolled_back: bool,
+    },
+    /// The bytes of a journal entry don't match its packets in
+    /// [`replay_until`](crate::replay_until).
+    JournalMismatch {
+        /// Sequence number of the entry.
+        seq: u64,
+        /// Size of the packets of the entry in bytes.
+        expected: usize,
+        /// Number of the bytes read or pulled from the source.
+        actual: usize,
+    },
+    /// A journaled append doesn't start at the end of the replayed table in
+    /// [`replay_until`](crate::replay_until).
+    JournalAppendMismatch {
+        /// Sequence number of the entry.
+        seq: u64,
+        /// The first packet appended by the entry.
+        start: u64,
+        /// Number of packets in the replayed table.
+        num_packets: u64,
+    },
+    /// The row-aligned tables have different numbers of packets.
+    RowMismatch {
+        /// Number of packets of each table.
+        counts: Vec<u64>,
+    },
+    /// The requested filter is not available.
+    FilterUnavailable {
+        /// The filter id.
+        id: i32,
+    },
+    /// The packet table builder is rejected before creating.
+    Builder(BuilderError),
+    /// The object already exists, and is not overwritten.
+    AlreadyExists {
+        /// The name of the object.
+        name: String,
+    },
+    /// The object at the path is not a group.
+    NotAGroup {
+        /// The path of the object.
+        path: String,
+    },
+    /// The group doesn't exist, and the missing groups are not created.
+    MissingGroup {
+        /// The path of the group.
+        path: String,
+    },
+    /// The name contains an interior NUL byte.
+    InvalidName {
+        /// The name, with invalid UTF-8 replaced.
+        name: String,
+        /// Byte offset of the first NUL.
+        position: usize,
+    },
+    /// Other errors.
+    Other(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hdf5(e) => e.fmt(f),
+            Self::Dst(e) => e.fmt(f),
+            Self::Io(e) => e.fmt(f),
+            Self::PacketSize { expected, actual } => {
+                write!(
+                    f,
+                    "Packet size mismatch: expected {expected}, got {actual}."
+                )
+            }
+            Self::NotEnoughPackets {
+                start,
+                len,
+                num_packets,
+            } => write!(
+                f,
+                "Not enough packets: requested {len} from {start}, but the table has {num_packets}."
+            ),
+            Self::InvalidRange {
+                start,
+                end,
+                num_packets,
+            } => write!(f, "Invalid range {start}..{end} for {num_packets} packets."),
+            Self::TruncateBeyondEnd { len, num_packets } => write!(
+                f,
+                "Cannot truncate {num_packets} packets to {len}: truncating never grows the table."
+            ),
+            Self::RangeOverflow => write!(f, "The range bounds overflow."),
+            Self::IntOverflow => write!(f, "The number overflows the integer type."),
+            Self::ZeroChunk => write!(f, "The chunk should not be zero."),
+            Self::ZeroBufLen => write!(f, "The buffer length should not be zero."),
+            Self::CapacityExceeded { len, remaining } => write!(
+                f,
+                "Cannot append {len} packets: only {remaining} packets fit in the table."
+            ),
+            Self::NotChunked => write!(f, "The dataset is not chunked."),
+            Self::WrongRank { ndim } => write!(
+                f,
+                "The dataset should be 1-dimensional, but its rank is {ndim}."
+            ),
+            Self::NotUnlimited { max } => {
+                write!(f, "The max extent {max} of the dataset is not unlimited.")
+            }
+            Self::ReadOnly { path } => {
+                write!(f, "Cannot append to {path}: the file is opened read-only.")
+            }
+            Self::InvalidIndex {
+                index,
+                delta,
+                num_packets,
+            } => write!(
+                f,
+                "Cannot advance index {index} by {delta} for {num_packets} packets."
+            ),
+            Self::InconsistentIndex { expected, actual } => {
+                write!(f, "Inconsistent index: expected {expected}, got {actual}.")
+            }
+            Self::IndexRestore {
+                index,
+                error,
+                restore_error,
+            } => write!(
+                f,
+                "{error} Failed to restore the index to {index}: {restore_error}"
+            ),
+            Self::Rollback {
+                len,
+                error,
+                rollback_error,
+            } => write!(
+                f,
+                "{error} Failed to roll back to {len} packets: {rollback_error}"
+            ),
+            Self::PartialAppend { batches, error } => {
+                write!(f, "{error} {batches} batches have been appended.")
+            }
+            Self::PartialImport { committed, source } => {
+                write!(f, "{source} {committed} packets have been appended.")
+            }
+            Self::TruncatedPacket {
+                committed,
+                trailing_bytes,
+            } => write!(
+                f,
+                "The stream ends with {trailing_bytes} bytes of an incomplete packet. {committed} packets have been appended."
+            ),
+            Self::PartialCreate {
+                name,
+                created,
+                rolled_back,
+                error,
+            } => {
+                write!(f, "Failed to create {name}: {error} ")?;
+                if *rolled_back {
+                    write!(f, "{created} created tables have been removed.")
+                } else {
+                    write!(f, "{created} tables have been created.")
+                }
+            }
+            Self::SchemaMismatch { expected, actual } => {
+                write!(f, "Schema mismatch:")?;
+                write_schema_diff(f, expected, actual)
+            }
+            Self::TransformMismatch { output } => write!(
+                f,
+                "The output {output:?} of the transform doesn't match the datatype."
+            ),
+            Self::FillValueMismatch { fill } => write!(
+                f,
+                "The fill value type {fill:?} doesn't match the datatype."
+            ),
+            Self::MetadataMismatch {
+                expected_size,
+                actual_size,
+            } => write!(
+                f,
+                "Metadata mismatch: expected packets of {expected_size} bytes, got {actual_size}."
+            ),
+            Self::Cancelled {
+                processed,
+                rolled_back,
+            } => {
+                write!(f, "Cancelled after {processed} packets.")?;
+                if *rolled_back {
+                    write!(f, " The written packets are rolled back.")?;
+                }
+                Ok(())
+            }
+            Self::JournalMismatch {
+                seq,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {expected} bytes for the journal entry {seq}, got {actual}."
+            ),
+            Self::JournalAppendMismatch {
+                seq,
+                start,
+                num_packets,
+            } => write!(
+                f,
+                "The journal entry {seq} appends at {start}, but the table has {num_packets} packets."
+            ),
+            Self::RowMismatch { counts } => {
+                write!(f, "The tables are not row-aligned: {counts:?} packets.")
+            }
+            Self::FilterUnavailable { id } => {
+                write!(f, "The filter {id} is not available.")
+            }
+            Self::Builder(e) => e.fmt(f),
+            Self::AlreadyExists { name } => write!(f, "The object {name} already exists."),
+            Self::NotAGroup { path } => write!(f, "{path} is not a group."),
+            Self::MissingGroup { path } => write!(f, "The group {path} doesn't exist."),
+            Self::InvalidName { name, position } => {
+                write!(f, "Invalid name {name:?}: interior NUL at byte {position}.")
+            }
+            Self::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// The error of a [`PacketTableBuilder`](crate::PacketTableBuilder)
+/// that is rejected before creating, e.g., the chunk violates a constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuilderError {
+    /// Neither the chunk nor the plist chunk is set, and not chunking automatically.
+    MissingChunk,
+    /// The chunk is zero.
+    ZeroChunk,
+    /// The chunk exceeds the cap of the builder.
+    ChunkTooLarge {
+        /// The chunk.
+        chunk: u64,
+        /// The cap.
+        max: u64,
+    },
+    /// The plist chunk is not 1-dimensional.
+    InvalidPlistChunk {
+        /// Rank of the plist chunk.
+        ndim: usize,
+    },
+    /// The chunk differs from the plist chunk.
+    PlistChunkMismatch {
+        /// The chunk.
+        chunk: u64,
+        /// The plist chunk.
+        plist: u64,
+    },
+}
+
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingChunk => write!(
+                f,
+                "Either plist or chunk need to be set, or use chunk_auto."
+            ),
+            Self::ZeroChunk => write!(f, "The chunk should not be zero."),
+            Self::ChunkTooLarge { chunk, max } => {
+                write!(f, "The chunk {chunk} exceeds the max chunk {max}.")
+            }
+            Self::InvalidPlistChunk { ndim } => {
+                write!(
+                    f,
+                    "The plist chunk should be 1-dimensional, got rank {ndim}."
+                )
+            }
+            Self::PlistChunkMismatch { chunk, plist } => {
+                write!(
+                    f,
+                    "The chunk {chunk} doesn't match the plist chunk {plist}."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+impl From<BuilderError> for Error {
+    fn from(value: BuilderError) -> Self {
+        Self::Builder(value)
+    }
+}
+
+/// Write the differences of the compound members,
+/// with `-` for the expected ones and `+` for the stored ones.
+fn write_schema_diff(
+    f: &mut Formatter<'_>,
+    expected: &TypeDescriptor,
+    actual: &TypeDescriptor,
+) -> std::fmt::Result {
+    match (expected, actual) {
+        (TypeDescriptor::Compound(expected), TypeDescriptor::Compound(actual)) => {
+            for field in &expected.fields {
+                if !actual
+                    .fields
+                    .iter()
+                    .any(|a| a.name == field.name && a.ty == field.ty)
+                {
+                    write!(f, "\n- {}: {:?}", field.name, field.ty)?;
+                }
+            }
+            for field in &actual.fields {
+                if !expected
+                    .fields
+                    .iter()
+                    .any(|e| e.name == field.name && e.ty == field.ty)
+                {
+                    write!(f, "\n+ {}: {:?}", field.name, field.ty)?;
+                }
+            }
+            if expected.size != actual.size {
+                write!(f, "\n- size {}\n+ size {}", expected.size, actual.size)?;
+            }
+            Ok(())
+        }
+        _ => write!(f, "\n- {expected:?}\n+ {actual:?}"),
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Hdf5(e) => Some(e),
+            Self::Dst(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Builder(e) => Some(e),
+            Self::IndexRestore { error, .. }
+            | Self::Rollback { error, .. }
+            | Self::PartialAppend { error, .. }
+            | Self::PartialCreate { error, .. } => Some(error.as_ref()),
+            Self::PartialImport { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<hdf5::Error> for Error {
+    fn from(value: hdf5::Error) -> Self {
+        Self::Hdf5(value)
+    }
+}
+
+impl From<hdf5_dst::Error> for Error {
+    fn from(value: hdf5_dst::Error) -> Self {
+        match value {
+            hdf5_dst::Error::Hdf5(e) => Self::Hdf5(e),
+            e => Self::Dst(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(_: TryFromIntError) -> Self {
+        Self::IntOverflow
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(value: NulError) -> Self {
+        let position = value.nul_position();
+        Self::InvalidName {
+            name: String::from_utf8_lossy(&value.into_vec()).into_owned(),
+            position,
+        }
+    }
+}
+
+impl From<Error> for hdf5::Error {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::Hdf5(e) => e,
+            e => hdf5::Error::Internal(e.to_string()),
+        }
+    }
+}
+
+/// The result type of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
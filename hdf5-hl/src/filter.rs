@@ -0,0 +1,178 @@
+use crate::{Error, Result};
+use hdf5::h5call;
+use hdf5_sys::{
+    h5i::hid_t,
+    h5p::{H5Pget_filter2, H5Pget_nfilters, H5Premove_filter, H5Pset_filter},
+    h5z::{
+        H5Z_filter_t, H5Zfilter_avail, H5Z_FILTER_ALL, H5Z_FILTER_DEFLATE, H5Z_FILTER_FLETCHER32,
+        H5Z_FILTER_SHUFFLE, H5Z_FLAG_MANDATORY,
+    },
+};
+use std::{ffi::c_uint, ptr::null_mut};
+
+/// The registered id of the zstd filter plugin.
+pub const ZSTD_FILTER_ID: H5Z_filter_t = 32015;
+
+/// The registered id of the blosc filter.
+pub const BLOSC_FILTER_ID: H5Z_filter_t = 32001;
+
+/// The registered id of the lzf filter.
+pub const LZF_FILTER_ID: H5Z_filter_t = 32000;
+
+/// Determine if the filter is available, either built in or loaded as a plugin.
+///
+/// The blosc and lzf filters bundled with `hdf5` are registered first
+/// if the corresponding features are enabled.
+pub fn filter_available(id: H5Z_filter_t) -> bool {
+    #[cfg(feature = "blosc")]
+    if id == BLOSC_FILTER_ID {
+        return blosc_filter_available();
+    }
+    #[cfg(feature = "lzf")]
+    if id == LZF_FILTER_ID {
+        return lzf_filter_available();
+    }
+    h5call!(H5Zfilter_avail(id)).is_ok_and(|avail| avail > 0)
+}
+
+/// Determine if the zstd filter plugin is available.
+pub fn zstd_filter_available() -> bool {
+    filter_available(ZSTD_FILTER_ID)
+}
+
+/// Register the blosc filter bundled with `hdf5`, and determine if it is available.
+#[cfg(feature = "blosc")]
+pub fn blosc_filter_available() -> bool {
+    hdf5::filters::blosc_available()
+}
+
+/// Register the lzf filter bundled with `hdf5`, and determine if it is available.
+#[cfg(feature = "lzf")]
+pub fn lzf_filter_available() -> bool {
+    hdf5::filters::lzf_available()
+}
+
+/// A filter requested on the builder, applied when the table is created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FilterRequest {
+    pub id: H5Z_filter_t,
+    pub flags: c_uint,
+    pub cd_values: Vec<c_uint>,
+}
+
+impl FilterRequest {
+    pub fn new(id: H5Z_filter_t, cd_values: impl Into<Vec<c_uint>>) -> Self {
+        Self {
+            id,
+            flags: H5Z_FLAG_MANDATORY,
+            cd_values: cd_values.into(),
+        }
+    }
+
+    /// Read the filters already in the pipeline of the plist, in order.
+    pub fn read_pipeline(plist: hid_t) -> Result<Vec<Self>> {
+        let n = h5call!(H5Pget_nfilters(plist))?;
+        (0..n as c_uint)
+            .map(|idx| -> Result<Self> {
+                let mut flags = 0;
+                let mut cd_len = 16;
+                let mut cd_values = vec![0; cd_len];
+                let id = h5call!(H5Pget_filter2(
+                    plist,
+                    idx,
+                    &mut flags,
+                    &mut cd_len,
+                    cd_values.as_mut_ptr(),
+                    0,
+                    null_mut(),
+                    null_mut()
+                ))?;
+                if cd_len > cd_values.len() {
+                    cd_values.resize(cd_len, 0);
+                    h5call!(H5Pget_filter2(
+                        plist,
+                        idx,
+                        &mut flags,
+                        &mut cd_len,
+                        cd_values.as_mut_ptr(),
+                        0,
+                        null_mut(),
+                        null_mut()
+                    ))?;
+                }
+                cd_values.truncate(cd_len);
+                Ok(Self {
+                    id,
+                    flags,
+                    cd_values,
+                })
+            })
+            .collect()
+    }
+
+    /// Add the requested filters to the pipeline of the plist.
+    ///
+    /// If the plist already has filters, the pipeline is rebuilt so that shuffle
+    /// stays before the compression filters and fletcher32 after them.
+    /// The filters already in the plist keep their flags and parameters.
+    pub fn extend_pipeline(plist: hid_t, filters: &[Self]) -> Result<()> {
+        let existing = Self::read_pipeline(plist)?;
+        if existing.is_empty() {
+            return filters.iter().try_for_each(|filter| filter.apply(plist));
+        }
+        let find = |id| {
+            existing
+                .iter()
+                .chain(filters)
+                .find(|filter| filter.id == id)
+                .cloned()
+        };
+        let shuffle = find(H5Z_FILTER_SHUFFLE);
+        let fletcher32 = find(H5Z_FILTER_FLETCHER32);
+        h5call!(H5Premove_filter(plist, H5Z_FILTER_ALL))?;
+        shuffle.iter().try_for_each(|filter| filter.set(plist))?;
+        existing
+            .iter()
+            .filter(|filter| !filter.is_reordered())
+            .try_for_each(|filter| filter.set(plist))?;
+        filters
+            .iter()
+            .filter(|filter| !filter.is_reordered())
+            .try_for_each(|filter| filter.apply(plist))?;
+        fletcher32.iter().try_for_each(|filter| filter.set(plist))
+    }
+
+    fn is_reordered(&self) -> bool {
+        self.id == H5Z_FILTER_SHUFFLE || self.id == H5Z_FILTER_FLETCHER32
+    }
+
+    /// Add the filter to the pipeline of the plist, failing if it is unavailable.
+    pub fn apply(&self, plist: hid_t) -> Result<()> {
+        if self.id == H5Z_FILTER_DEFLATE
+            && let Some(&level) = self.cd_values.first().filter(|&&level| level > 9)
+        {
+            return Err(Error::Other(format!("Invalid deflate level {level}.")));
+        }
+        if self.id == BLOSC_FILTER_ID
+            && let Some(&level) = self.cd_values.get(4).filter(|&&level| level > 9)
+        {
+            return Err(Error::Other(format!("Invalid blosc level {level}.")));
+        }
+        if !filter_available(self.id) {
+            return Err(Error::FilterUnavailable { id: self.id });
+        }
+        self.set(plist)
+    }
+
+    /// Add the filter to the pipeline of the plist as is.
+    fn set(&self, plist: hid_t) -> Result<()> {
+        h5call!(H5Pset_filter(
+            plist,
+            self.id,
+            self.flags,
+            self.cd_values.len(),
+            self.cd_values.as_ptr()
+        ))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+//! Deterministic fixture files for test suites.
+//!
+//! [`standard_file`] creates a documented set of objects, and returns a
+//! [`Manifest`] describing every created object, so that tests could assert
+//! against it without hardcoding the contents.
+//!
+//! | Path | Kind | Contents |
+//! |------|------|----------|
+//! | [`I32_TABLE`] | packet table | 64 `i32` in chunks of 16 |
+//! | [`SCALE_ATTR`], [`UNITS_ATTR`] | attributes of [`I32_TABLE`] | an `f64` and a string |
+//! | [`I32_JOURNAL`] | sidecar of [`I32_TABLE`] | the write [`JournalEntry`] records |
+//! | [`COMPOUND_TABLE`] | packet table | 64 [`FixtureRecord`] |
+//! | [`DST_DATASET`] | dataset | 64 [`FixtureUnsized`] with metadata [`DST_METADATA`] |
+//! | [`VARLEN_TABLE`] | packet table | 64 `VarLenArray<i32>` |
+
+use crate::*;
+use dst_container::*;
+use hdf5::{
+    types::{TypeDescriptor, VarLenArray, VarLenUnicode},
+    H5Type,
+};
+use hdf5_dst::ContainerBuilderExt;
+use std::path::Path;
+
+/// The compound record stored in [`COMPOUND_TABLE`].
+#[derive(Debug, Clone, Copy, PartialEq, H5Type)]
+#[repr(C)]
+pub struct FixtureRecord {
+    /// Index of the record.
+    pub id: u32,
+    /// A pseudo-random value.
+    pub value: f64,
+}
+
+/// The DST record stored in [`DST_DATASET`].
+pub type FixtureUnsized = UnsizedSlice<u32, u64>;
+
+/// Path of the `i32` packet table.
+pub const I32_TABLE: &str = "i32_table";
+/// Path of the [`FixtureRecord`] packet table.
+pub const COMPOUND_TABLE: &str = "compound_table";
+/// Path of the [`FixtureUnsized`] dataset.
+pub const DST_DATASET: &str = "dst_dataset";
+/// Path of the `VarLenArray<i32>` packet table.
+pub const VARLEN_TABLE: &str = "varlen_table";
+/// Name of the `f64` attribute on [`I32_TABLE`].
+pub const SCALE_ATTR: &str = "scale";
+/// Name of the string attribute on [`I32_TABLE`].
+pub const UNITS_ATTR: &str = "units";
+/// Path of the journal of [`I32_TABLE`].
+pub const I32_JOURNAL: &str = "i32_journal";
+
+/// The slice length of [`FixtureUnsized`] records in [`DST_DATASET`].
+pub const DST_METADATA: usize = 6;
+
+const TABLE_LEN: usize = 64;
+const CHUNK: usize = 16;
+const UNITS: &str = "counts";
+
+/// Kind of a fixture object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// A packet table.
+    PacketTable,
+    /// A plain dataset.
+    Dataset,
+    /// An attribute attached to [`ManifestEntry::parent`].
+    Attribute,
+    /// A packet table describing [`ManifestEntry::parent`], such as its journal.
+    Sidecar,
+}
+
+/// Description of one fixture object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    /// Name of the object.
+    pub path: String,
+    /// The object this attribute is attached to, if any.
+    pub parent: Option<String>,
+    /// Kind of the object.
+    pub kind: ObjectKind,
+    /// Datatype of the object.
+    pub dtype: TypeDescriptor,
+    /// Number of elements.
+    pub count: usize,
+    /// [`checksum`] of the element bytes in order.
+    /// For variable length elements, only the payload bytes are hashed.
+    pub checksum: u64,
+}
+
+/// Description of a fixture file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    /// Seed used to generate the file.
+    pub seed: u64,
+    /// All created objects.
+    pub objects: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Find the entry with the specified path.
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.objects.iter().find(|entry| entry.path == path)
+    }
+}
+
+/// FNV-1a hash of the bytes.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn bytes_of<T: ?Sized>(val: &T) -> &[u8] {
+    // SAFETY: fixture types are plain data without padding.
+    unsafe { std::slice::from_raw_parts(val as *const T as *const u8, size_of_val(val)) }
+}
+
+/// Hash the members of the entries separately to skip the padding.
+fn journal_checksum(entries: &[JournalEntry]) -> u64 {
+    let mut bytes = vec![];
+    for e in entries {
+        bytes.extend_from_slice(bytes_of(&e.seq));
+        bytes.push(e.op as u8);
+        bytes.extend_from_slice(bytes_of(&e.start));
+        bytes.extend_from_slice(bytes_of(&e.len));
+    }
+    checksum(&bytes)
+}
+
+/// SplitMix64 generator, stable across platforms.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Create the standard fixture file at the path.
+///
+/// The contents are stable for a given seed.
+pub fn standard_file(path: impl AsRef<Path>, seed: u64) -> Result<Manifest> {
+    let mut rng = Rng(seed);
+    let file = hdf5::File::create(path)?;
+    let mut objects = vec![];
+
+    let ints = (0..TABLE_LEN)
+        .map(|_| rng.next() as i32)
+        .collect::<Vec<_>>();
+    let scale = (rng.next() >> 11) as f64 / (1u64 << 53) as f64;
+    let mut table = PacketTable::builder(&file)
+        .chunk(CHUNK)
+        .attr(SCALE_ATTR, scale)
+        .attr_str(UNITS_ATTR, UNITS)
+        .dtype::<i32>()
+        .create(I32_TABLE)?;
+    let journal = PacketTable::builder(&file)
+        .chunk(CHUNK)
+        .dtype::<JournalEntry>()
+        .create(I32_JOURNAL)?;
+    table.set_journal(Some(journal))?;
+    table.append(&ints)?;
+    objects.push(ManifestEntry {
+        path: I32_TABLE.to_string(),
+        parent: None,
+        kind: ObjectKind::PacketTable,
+        dtype: i32::type_descriptor(),
+        count: ints.len(),
+        checksum: checksum(bytes_of(ints.as_slice())),
+    });
+
+    objects.push(ManifestEntry {
+        path: SCALE_ATTR.to_string(),
+        parent: Some(I32_TABLE.to_string()),
+        kind: ObjectKind::Attribute,
+        dtype: f64::type_descriptor(),
+        count: 1,
+        checksum: checksum(bytes_of(&scale)),
+    });
+    objects.push(ManifestEntry {
+        path: UNITS_ATTR.to_string(),
+        parent: Some(I32_TABLE.to_string()),
+        kind: ObjectKind::Attribute,
+        dtype: VarLenUnicode::type_descriptor(),
+        count: 1,
+        checksum: checksum(UNITS.as_bytes()),
+    });
+
+    let entries = PacketTable::open(&file, I32_JOURNAL)?.read_all::<JournalEntry>()?;
+    objects.push(ManifestEntry {
+        path: I32_JOURNAL.to_string(),
+        parent: Some(I32_TABLE.to_string()),
+        kind: ObjectKind::Sidecar,
+        dtype: JournalEntry::type_descriptor(),
+        count: entries.len(),
+        checksum: journal_checksum(&entries),
+    });
+
+    let records = (0..TABLE_LEN as u32)
+        .map(|id| FixtureRecord {
+            id,
+            value: rng.next() as f64,
+        })
+        .collect::<Vec<_>>();
+    PacketTable::builder(&file)
+        .chunk(CHUNK)
+        .dtype::<FixtureRecord>()
+        .create(COMPOUND_TABLE)?
+        .append(&records)?;
+    // Hash the members separately to skip the padding.
+    let mut bytes = vec![];
+    for r in &records {
+        bytes.extend_from_slice(bytes_of(&r.id));
+        bytes.extend_from_slice(bytes_of(&r.value));
+    }
+    objects.push(ManifestEntry {
+        path: COMPOUND_TABLE.to_string(),
+        parent: None,
+        kind: ObjectKind::PacketTable,
+        dtype: FixtureRecord::type_descriptor(),
+        count: records.len(),
+        checksum: checksum(&bytes),
+    });
+
+    let mut vec: FixedVec<FixtureUnsized> = FixedVec::new(DST_METADATA);
+    let mut bytes = vec![];
+    for _ in 0..TABLE_LEN {
+        let header = rng.next() as u32;
+        let slice = (0..DST_METADATA).map(|_| rng.next()).collect::<Vec<_>>();
+        bytes.extend_from_slice(bytes_of(&header));
+        bytes.extend_from_slice(bytes_of(slice.as_slice()));
+        unsafe {
+            vec.push_with(|data| {
+                data.header.write(header);
+                for (dst, src) in data.slice.iter_mut().zip(&slice) {
+                    dst.write(*src);
+                }
+            })
+        };
+    }
+    file.new_dataset_builder()
+        .with_data_unsized::<FixtureUnsized>(&vec)
+        .create(DST_DATASET)?;
+    objects.push(ManifestEntry {
+        path: DST_DATASET.to_string(),
+        parent: None,
+        kind: ObjectKind::Dataset,
+        dtype: hdf5_dst::H5TypeUnsized::type_descriptor(&vec[0]),
+        count: vec.len(),
+        checksum: checksum(&bytes),
+    });
+
+    let mut table = PacketTable::builder(&file)
+        .chunk(CHUNK)
+        .dtype::<VarLenArray<i32>>()
+        .create(VARLEN_TABLE)?;
+    let mut bytes = vec![];
+    for _ in 0..TABLE_LEN {
+        let len = (rng.next() % 8) as usize;
+        let arr = (0..len).map(|_| rng.next() as i32).collect::<Vec<_>>();
+        bytes.extend_from_slice(bytes_of(arr.as_slice()));
+        table.push(&VarLenArray::from_slice(&arr))?;
+    }
+    objects.push(ManifestEntry {
+        path: VARLEN_TABLE.to_string(),
+        parent: None,
+        kind: ObjectKind::PacketTable,
+        dtype: VarLenArray::<i32>::type_descriptor(),
+        count: TABLE_LEN,
+        checksum: checksum(&bytes),
+    });
+
+    Ok(Manifest { seed, objects })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hdf5_dst::ContainerExt;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn deterministic() {
+        let file1 = NamedTempFile::new().unwrap();
+        let file2 = NamedTempFile::new().unwrap();
+        let file3 = NamedTempFile::new().unwrap();
+        let manifest1 = standard_file(file1.path(), 114514).unwrap();
+        let manifest2 = standard_file(file2.path(), 114514).unwrap();
+        let manifest3 = standard_file(file3.path(), 1919810).unwrap();
+        assert_eq!(manifest1.objects, manifest2.objects);
+        assert_ne!(manifest1.objects, manifest3.objects);
+    }
+
+    #[test]
+    fn manifest() {
+        let file = NamedTempFile::new().unwrap();
+        let manifest = standard_file(file.path(), 114514).unwrap();
+        let file = hdf5::File::open(file.path()).unwrap();
+
+        let entry = manifest.get(I32_TABLE).unwrap();
+        let table = PacketTable::open(&file, I32_TABLE).unwrap();
+        assert_eq!(table.dtype().unwrap().to_descriptor().unwrap(), entry.dtype);
+        assert_eq!(table.num_packets().unwrap(), entry.count as u64);
+        let ints = table.read::<i32>(0, entry.count).unwrap();
+        assert_eq!(checksum(bytes_of(ints.as_slice())), entry.checksum);
+
+        let entry = manifest.get(SCALE_ATTR).unwrap();
+        assert_eq!(entry.parent.as_deref(), Some(I32_TABLE));
+        let scale = table
+            .dataset()
+            .unwrap()
+            .attr(SCALE_ATTR)
+            .unwrap()
+            .read_scalar::<f64>()
+            .unwrap();
+        assert_eq!(checksum(bytes_of(&scale)), entry.checksum);
+
+        let entry = manifest.get(UNITS_ATTR).unwrap();
+        assert_eq!(entry.parent.as_deref(), Some(I32_TABLE));
+        let units = table
+            .dataset()
+            .unwrap()
+            .attr(UNITS_ATTR)
+            .unwrap()
+            .read_scalar::<VarLenUnicode>()
+            .unwrap();
+        assert_eq!(checksum(units.as_bytes()), entry.checksum);
+
+        let entry = manifest.get(I32_JOURNAL).unwrap();
+        assert_eq!(entry.kind, ObjectKind::Sidecar);
+        assert_eq!(entry.parent.as_deref(), Some(I32_TABLE));
+        let journal = PacketTable::open(&file, I32_JOURNAL).unwrap();
+        assert_eq!(
+            journal.dtype().unwrap().to_descriptor().unwrap(),
+            entry.dtype
+        );
+        let entries = journal.read_all::<JournalEntry>().unwrap();
+        assert_eq!(entries.len(), entry.count);
+        assert_eq!(journal_checksum(&entries), entry.checksum);
+
+        let entry = manifest.get(COMPOUND_TABLE).unwrap();
+        let table = PacketTable::open(&file, COMPOUND_TABLE).unwrap();
+        assert_eq!(table.dtype().unwrap().to_descriptor().unwrap(), entry.dtype);
+        let records = table.read::<FixtureRecord>(0, entry.count).unwrap();
+        let mut bytes = vec![];
+        for r in &records {
+            bytes.extend_from_slice(bytes_of(&r.id));
+            bytes.extend_from_slice(bytes_of(&r.value));
+        }
+        assert_eq!(checksum(&bytes), entry.checksum);
+
+        let entry = manifest.get(DST_DATASET).unwrap();
+        let dataset = file.dataset(DST_DATASET).unwrap();
+        assert_eq!(
+            dataset.dtype().unwrap().to_descriptor().unwrap(),
+            entry.dtype
+        );
+        let mut vec: FixedVec<FixtureUnsized> = FixedVec::new(DST_METADATA);
+        dataset.read_unsized(&mut vec).unwrap();
+        assert_eq!(vec.len(), entry.count);
+        let mut bytes = vec![];
+        for i in 0..vec.len() {
+            bytes.extend_from_slice(bytes_of(&vec[i].header));
+            bytes.extend_from_slice(bytes_of(&vec[i].slice));
+        }
+        assert_eq!(checksum(&bytes), entry.checksum);
+
+        let entry = manifest.get(VARLEN_TABLE).unwrap();
+        let table = PacketTable::open(&file, VARLEN_TABLE).unwrap();
+        assert_eq!(table.table_type().unwrap(), PacketTableType::VarLen);
+        let mut bytes = vec![];
+        for arr in table.iter::<VarLenArray<i32>>() {
+            bytes.extend_from_slice(bytes_of(arr.unwrap().as_slice()));
+        }
+        assert_eq!(checksum(&bytes), entry.checksum);
+    }
+}
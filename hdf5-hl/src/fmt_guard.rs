@@ -0,0 +1,30 @@
+//! Detect HDF5 calls from formatting paths.
+//!
+//! Formatting may happen while the caller holds the HDF5 lock,
+//! so the `Debug` impls should only use cached data.
+
+use std::cell::Cell;
+
+thread_local! {
+    static IN_FMT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as formatting until dropped.
+pub(crate) struct FmtGuard(bool);
+
+impl FmtGuard {
+    pub fn enter() -> Self {
+        Self(IN_FMT.replace(true))
+    }
+}
+
+impl Drop for FmtGuard {
+    fn drop(&mut self) {
+        IN_FMT.set(self.0);
+    }
+}
+
+/// Determine if the current thread is formatting.
+pub(crate) fn in_fmt() -> bool {
+    IN_FMT.get()
+}
@@ -0,0 +1,312 @@
+use crate::{Error, PacketTable, PacketTableBuilder, Result};
+use hdf5::{
+    from_id, h5try,
+    plist::{link_create::CharEncoding, LinkCreate},
+    File, Group, LocationType,
+};
+use hdf5_sys::{h5g::H5Gcreate2, h5p::H5P_DEFAULT};
+use std::ffi::CString;
+
+/// Types that could be used as the location of packet tables.
+pub trait AsGroup {
+    /// Get the [`Group`] reference.
+    fn as_group(&self) -> &Group;
+}
+
+impl AsGroup for Group {
+    fn as_group(&self) -> &Group {
+        self
+    }
+}
+
+impl AsGroup for File {
+    fn as_group(&self) -> &Group {
+        self
+    }
+}
+
+impl<T: AsGroup + ?Sized> AsGroup for &T {
+    fn as_group(&self) -> &Group {
+        (**self).as_group()
+    }
+}
+
+/// Options of the links created by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkOptions {
+    create_intermediate: bool,
+    char_encoding: Option<CharEncoding>,
+}
+
+impl Default for LinkOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkOptions {
+    /// Create the default options: intermediate groups are created,
+    /// and the names are encoded in ASCII, or UTF-8 if they aren't ASCII.
+    pub const fn new() -> Self {
+        Self {
+            create_intermediate: true,
+            char_encoding: None,
+        }
+    }
+
+    /// Set whether the missing intermediate groups are created.
+    pub const fn create_intermediate(mut self, create: bool) -> Self {
+        self.create_intermediate = create;
+        self
+    }
+
+    /// Set the character encoding of the link names, instead of choosing by the name.
+    pub const fn char_encoding(mut self, encoding: CharEncoding) -> Self {
+        self.char_encoding = Some(encoding);
+        self
+    }
+
+    /// Build the link creation property list for the link name.
+    pub fn lcpl(&self, name: &str) -> Result<LinkCreate> {
+        let encoding = self.char_encoding.unwrap_or(if name.is_ascii() {
+            CharEncoding::Ascii
+        } else {
+            CharEncoding::Utf8
+        });
+        Ok(LinkCreate::build()
+            .create_intermediate_group(self.create_intermediate)
+            .char_encoding(encoding)
+            .finish()?)
+    }
+}
+
+/// Extension methods of [`Group`] for packet tables.
+/// They are also usable on [`File`].
+///
+/// ```
+/// use hdf5_hl::GroupExt;
+///
+/// # let path = tempfile::NamedTempFile::new().unwrap();
+/// let file = hdf5::File::create(path.path())?;
+/// let mut table = file.new_packet_table().chunk(16).dtype::<i32>().create("data")?;
+/// table.append(&[1, 1, 4, 5, 1, 4])?;
+/// drop(table);
+///
+/// let table = file.packet_table("data")?;
+/// assert_eq!(table.read_all::<i32>()?, [1, 1, 4, 5, 1, 4]);
+/// assert!(file.packet_table_opt("missing")?.is_none());
+/// # Ok::<(), hdf5_hl::Error>(())
+/// ```
+pub trait GroupExt {
+    /// Open an existing packet table.
+    fn packet_table(&self, name: impl AsRef<str>) -> Result<PacketTable>;
+
+    /// Open an existing packet table, or return `None` if the link doesn't exist.
+    fn packet_table_opt(&self, name: impl AsRef<str>) -> Result<Option<PacketTable>>;
+
+    /// Create a packet table builder in this group.
+    fn new_packet_table(&self) -> PacketTableBuilder;
+
+    /// Open the group at the path, creating the missing ones.
+    ///
+    /// Existing groups are opened, and an existing object which is not a group
+    /// results in [`Error::NotAGroup`] naming its path. If the intermediate groups
+    /// are not created, a missing one results in [`Error::MissingGroup`].
+    fn ensure_group(&self, path: &str, options: &LinkOptions) -> Result<Group>;
+
+    /// Get the names of the members which could be opened as packet tables,
+    /// i.e., the chunked 1-dimensional datasets.
+    /// Errors other than a member not being a packet table are returned.
+    fn packet_tables(&self) -> Result<Vec<String>>;
+}
+
+impl GroupExt for Group {
+    fn packet_table(&self, name: impl AsRef<str>) -> Result<PacketTable> {
+        PacketTable::open(self, name)
+    }
+
+    fn packet_table_opt(&self, name: impl AsRef<str>) -> Result<Option<PacketTable>> {
+        let name = name.as_ref();
+        if !self.link_exists(name) {
+            return Ok(None);
+        }
+        self.packet_table(name).map(Some)
+    }
+
+    fn new_packet_table(&self) -> PacketTableBuilder {
+        PacketTable::builder(self)
+    }
+
+    fn ensure_group(&self, path: &str, options: &LinkOptions) -> Result<Group> {
+        let components = path
+            .split('/')
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>();
+        let mut prefix = if path.starts_with('/') {
+            "/".to_string()
+        } else {
+            String::new()
+        };
+        for (i, component) in components.iter().enumerate() {
+            if !prefix.is_empty() && !prefix.ends_with('/') {
+                prefix.push('/');
+            }
+            prefix.push_str(component);
+            if self.link_exists(&prefix) {
+                if self.loc_type_by_name(&prefix)? != LocationType::Group {
+                    return Err(Error::NotAGroup { path: prefix });
+                }
+                continue;
+            }
+            if i + 1 < components.len() && !options.create_intermediate {
+                return Err(Error::MissingGroup { path: prefix });
+            }
+            let lcpl = options.lcpl(component)?;
+            let name = CString::new(prefix.as_str())?;
+            let id = h5try!(H5Gcreate2(
+                self.id(),
+                name.as_ptr(),
+                lcpl.id(),
+                H5P_DEFAULT,
+                H5P_DEFAULT
+            ));
+            // Close the created group.
+            drop(unsafe { from_id::<Group>(id) }?);
+        }
+        if components.is_empty() {
+            Ok(self.group(if prefix.is_empty() { "." } else { "/" })?)
+        } else {
+            Ok(self.group(&prefix)?)
+        }
+    }
+
+    fn packet_tables(&self) -> Result<Vec<String>> {
+        let mut names = vec![];
+        for name in self.member_names()? {
+            if self.loc_type_by_name(&name)? != LocationType::Dataset {
+                continue;
+            }
+            // The dataset is closed when dropped.
+            let dset = self.dataset(&name)?;
+            if dset.is_chunked() && dset.ndim() == 1 {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_util::open_ids, *};
+    use hdf5::plist::link_create::CharEncoding;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn group_ext() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let group = data.create_group("group").unwrap();
+        group
+            .new_packet_table()
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap()
+            .append(&[1, 2, 3])
+            .unwrap();
+        assert_eq!(group.packet_table("data").unwrap().len().unwrap(), 3);
+        assert!(data.packet_table_opt("group/data").unwrap().is_some());
+        assert!(data.packet_table_opt("data").unwrap().is_none());
+        assert!(data.packet_table("data").is_err());
+    }
+
+    #[test]
+    fn packet_tables() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        data.new_dataset::<i32>().create("scalar").unwrap();
+        data.new_dataset::<i32>()
+            .chunk((2, 2))
+            .shape((4, 4))
+            .create("matrix")
+            .unwrap();
+        data.create_group("group").unwrap();
+        for name in ["a", "b"] {
+            data.new_packet_table()
+                .chunk(16)
+                .dtype::<i32>()
+                .create(name)
+                .unwrap();
+        }
+        let ids = open_ids(&data);
+        assert_eq!(data.packet_tables().unwrap(), ["a", "b"]);
+        for name in data.packet_tables().unwrap() {
+            data.packet_table(name).unwrap();
+        }
+        assert!(data
+            .group("group")
+            .unwrap()
+            .packet_tables()
+            .unwrap()
+            .is_empty());
+        assert_eq!(open_ids(&data), ids);
+
+        // The errors other than not being a packet table are returned.
+        data.link_soft("/missing", "dangling").unwrap();
+        assert!(data.packet_tables().is_err());
+    }
+
+    #[test]
+    fn ensure_group() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let options = LinkOptions::new();
+        let group = data.ensure_group("a/b/c", &options).unwrap();
+        assert_eq!(group.name(), "/a/b/c");
+        group.new_dataset::<i32>().create("data").unwrap();
+        let group = data.ensure_group("/a/b/c/", &options).unwrap();
+        assert!(group.link_exists("data"));
+
+        let e = data.ensure_group("a/b/c/data/d", &options).unwrap_err();
+        assert!(matches!(&e, Error::NotAGroup { path } if path == "a/b/c/data"));
+        assert!(e.to_string().contains("a/b/c/data "));
+        assert!(matches!(
+            data.ensure_group("x/y", &options.create_intermediate(false)),
+            Err(Error::MissingGroup { path }) if path == "x"
+        ));
+        assert!(!data.link_exists("x"));
+
+        // Non-ASCII names are encoded in UTF-8 by default.
+        let group = data.ensure_group("温度/传感器", &options).unwrap();
+        assert_eq!(group.name(), "/温度/传感器");
+        assert_eq!(
+            data.group("温度").unwrap().member_names().unwrap(),
+            ["传感器"]
+        );
+        let options = options.char_encoding(CharEncoding::Utf8);
+        let group = data.ensure_group("湿度", &options).unwrap();
+        assert_eq!(group.name(), "/湿度");
+    }
+
+    #[test]
+    fn lcpl() {
+        let options = LinkOptions::new();
+        assert_eq!(
+            options.lcpl("ascii").unwrap().char_encoding(),
+            CharEncoding::Ascii
+        );
+        assert_eq!(
+            options.lcpl("温度").unwrap().char_encoding(),
+            CharEncoding::Utf8
+        );
+        let options = options.char_encoding(CharEncoding::Ascii);
+        assert_eq!(
+            options.lcpl("温度").unwrap().char_encoding(),
+            CharEncoding::Ascii
+        );
+    }
+}
@@ -1,11 +1,45 @@
 //! HDF5 High-level APIs for Rust.
 
 #![feature(ptr_metadata)]
-#![cfg_attr(test, feature(test))]
+#![feature(layout_for_ptr)]
+#![cfg_attr(test, feature(maybe_uninit_write_slice))]
 #![warn(missing_docs)]
 
-#[cfg(test)]
-extern crate test;
+mod cancel;
+pub use cancel::*;
+
+mod error;
+pub use error::*;
+
+mod filter;
+pub use filter::*;
+
+mod group;
+pub use group::*;
+
+mod pool;
+pub use pool::*;
+
+mod progress;
+pub use progress::*;
 
 mod pt;
 pub use pt::*;
+
+#[cfg(feature = "fmt-guard")]
+mod fmt_guard;
+
+mod transform;
+pub use transform::*;
+
+mod validation;
+pub use validation::*;
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+
+#[cfg(test)]
+mod test_alloc;
+
+#[cfg(test)]
+mod test_util;
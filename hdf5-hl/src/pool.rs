@@ -0,0 +1,226 @@
+use dst_container::*;
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    ptr::Pointee,
+    sync::{Mutex, MutexGuard},
+};
+
+/// A pool of reusable [`FixedVec`] staging buffers.
+///
+/// The buffers are keyed by metadata. When a buffer is released to a full pool,
+/// the least recently released one is evicted.
+pub struct StagingPool<T: ?Sized> {
+    buffers: Mutex<VecDeque<(<T as Pointee>::Metadata, FixedVec<T>)>>,
+    max_buffers: usize,
+}
+
+impl<T: ?Sized> StagingPool<T> {
+    /// Create a pool holding at most `max_buffers` idle buffers.
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: Mutex::new(VecDeque::with_capacity(max_buffers)),
+            max_buffers,
+        }
+    }
+
+    fn buffers(&self) -> MutexGuard<'_, VecDeque<(<T as Pointee>::Metadata, FixedVec<T>)>> {
+        // The queue is always consistent, even if a thread panicked.
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The maximum number of idle buffers.
+    pub fn max_buffers(&self) -> usize {
+        self.max_buffers
+    }
+
+    /// The number of idle buffers.
+    pub fn len(&self) -> usize {
+        self.buffers().len()
+    }
+
+    /// Determine if there is no idle buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all idle buffers.
+    pub fn clear(&self) {
+        self.buffers().clear();
+    }
+
+    /// Acquire an empty buffer with at least the capacity.
+    /// An idle buffer with the same metadata is reused, otherwise a new one is allocated.
+    pub fn acquire(&self, metadata: <T as Pointee>::Metadata, capacity: usize) -> PooledVec<'_, T> {
+        let vec = {
+            let mut buffers = self.buffers();
+            buffers
+                .iter()
+                .rposition(|(m, _)| *m == metadata)
+                .and_then(|i| buffers.remove(i))
+                .map(|(_, vec)| vec)
+        };
+        let vec = match vec {
+            Some(mut vec) => {
+                vec.reserve(capacity);
+                vec
+            }
+            None => FixedVec::with_capacity(metadata, capacity),
+        };
+        PooledVec {
+            vec: Some(vec),
+            metadata,
+            pool: Some(self),
+        }
+    }
+
+    fn release(&self, metadata: <T as Pointee>::Metadata, mut vec: FixedVec<T>) {
+        if self.max_buffers == 0 {
+            return;
+        }
+        vec.clear();
+        let mut buffers = self.buffers();
+        if buffers.len() >= self.max_buffers {
+            buffers.pop_front();
+        }
+        buffers.push_back((metadata, vec));
+    }
+}
+
+impl<T: ?Sized> Debug for StagingPool<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StagingPool")
+            .field("len", &self.len())
+            .field("max_buffers", &self.max_buffers)
+            .finish()
+    }
+}
+
+/// A [`FixedVec`] returned to its [`StagingPool`] on drop.
+pub struct PooledVec<'a, T: ?Sized> {
+    vec: Option<FixedVec<T>>,
+    metadata: <T as Pointee>::Metadata,
+    pool: Option<&'a StagingPool<T>>,
+}
+
+impl<T: ?Sized> PooledVec<'_, T> {
+    /// Wrap a buffer not belonging to any pool.
+    pub fn unpooled(metadata: <T as Pointee>::Metadata, capacity: usize) -> Self {
+        Self {
+            vec: Some(FixedVec::with_capacity(metadata, capacity)),
+            metadata,
+            pool: None,
+        }
+    }
+
+    /// Take the buffer out, so that it won't return to the pool.
+    pub fn into_inner(mut self) -> FixedVec<T> {
+        self.vec.take().unwrap()
+    }
+}
+
+impl<T: ?Sized> Deref for PooledVec<'_, T> {
+    type Target = FixedVec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.vec.as_ref().unwrap()
+    }
+}
+
+impl<T: ?Sized> DerefMut for PooledVec<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.vec.as_mut().unwrap()
+    }
+}
+
+impl<T: ?Sized> Drop for PooledVec<'_, T> {
+    fn drop(&mut self) {
+        if let (Some(vec), Some(pool)) = (self.vec.take(), self.pool) {
+            pool.release(self.metadata, vec);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use dst_container::*;
+
+    fn data_ptr<T: ?Sized>(vec: &mut FixedVec<T>) -> *const () {
+        unsafe { vec.get_unchecked_mut(0) as *mut T }
+            .to_raw_parts()
+            .0 as *const ()
+    }
+
+    #[test]
+    fn reuse() {
+        let pool = StagingPool::<[i32]>::new(2);
+        let ptr = {
+            let mut vec = pool.acquire(3, 16);
+            vec.push_clone(&[1, 2, 3][..]);
+            data_ptr(&mut vec)
+        };
+        assert_eq!(pool.len(), 1);
+        for _ in 0..4 {
+            let mut vec = pool.acquire(3, 16);
+            assert!(vec.is_empty());
+            assert_eq!(data_ptr(&mut vec), ptr);
+            vec.push_clone(&[4, 5, 6][..]);
+        }
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn metadata_mismatch() {
+        let pool = StagingPool::<[i32]>::new(2);
+        let ptr = {
+            let mut vec = pool.acquire(3, 16);
+            data_ptr(&mut vec)
+        };
+        {
+            let mut vec = pool.acquire(2, 16);
+            assert_ne!(data_ptr(&mut vec), ptr);
+            // The buffer with metadata 3 is still idle.
+            assert_eq!(pool.len(), 1);
+        }
+        assert_eq!(pool.len(), 2);
+        let mut vec = pool.acquire(3, 16);
+        assert_eq!(data_ptr(&mut vec), ptr);
+    }
+
+    #[test]
+    fn capacity_bound() {
+        let pool = StagingPool::<i32>::new(2);
+        let mut a = pool.acquire((), 16);
+        let mut b = pool.acquire((), 16);
+        let mut c = pool.acquire((), 16);
+        let (pb, pc) = (data_ptr(&mut b), data_ptr(&mut c));
+        let _ = data_ptr(&mut a);
+        assert!(pool.is_empty());
+        drop(a);
+        drop(b);
+        drop(c);
+        // The first released buffer is evicted.
+        assert_eq!(pool.len(), 2);
+        let mut d = pool.acquire((), 16);
+        let mut e = pool.acquire((), 16);
+        assert_eq!(data_ptr(&mut d), pc);
+        assert_eq!(data_ptr(&mut e), pb);
+        drop(d);
+        drop(e);
+        assert_eq!(pool.len(), 2);
+
+        let pool = StagingPool::<i32>::new(0);
+        drop(pool.acquire((), 16));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn into_inner() {
+        let pool = StagingPool::<i32>::new(2);
+        let vec = pool.acquire((), 16).into_inner();
+        assert_eq!(vec.len(), 0);
+        assert!(pool.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A progress event of a bulk operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressEvent {
+    /// Name of the operation, e.g., `"copy_to"`.
+    pub operation: &'static str,
+    /// Number of packets processed.
+    pub done: u64,
+    /// Total number of packets, if known.
+    pub total: Option<u64>,
+    /// Number of bytes processed in memory.
+    pub bytes: u64,
+}
+
+/// A receiver of [`ProgressEvent`]s.
+///
+/// The events are rate-limited: at most about 100 events for an operation with known total,
+/// and one event per 100 milliseconds otherwise. The last event reports the exact total
+/// when the operation succeeds, and no event is sent after the operation returns.
+pub trait Progress: Send + Sync {
+    /// Receive an event.
+    fn report(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent) + Send + Sync> Progress for F {
+    fn report(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// A [`Progress`] ignoring all events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn report(&self, _event: &ProgressEvent) {}
+}
+
+/// A [`Progress`] recording all events.
+#[derive(Debug, Default)]
+pub struct ProgressCollector {
+    events: Mutex<Vec<ProgressEvent>>,
+}
+
+impl ProgressCollector {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the recorded events.
+    pub fn events(&self) -> Vec<ProgressEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Clear the recorded events.
+    pub fn clear(&self) {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Progress for ProgressCollector {
+    fn report(&self, event: &ProgressEvent) {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(event.clone());
+    }
+}
+
+const MAX_EVENTS: u64 = 100;
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// Rate limiter of the progress events of one operation.
+pub(crate) struct ProgressReporter<'a> {
+    progress: Option<&'a dyn Progress>,
+    operation: &'static str,
+    total: Option<u64>,
+    next: u64,
+    last: Instant,
+    reported: Option<u64>,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub(crate) fn new(
+        progress: Option<&'a dyn Progress>,
+        operation: &'static str,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            progress,
+            operation,
+            total,
+            next: 0,
+            last: Instant::now(),
+            reported: None,
+        }
+    }
+
+    fn report(&mut self, done: u64, bytes: u64) {
+        let Some(progress) = self.progress else {
+            return;
+        };
+        progress.report(&ProgressEvent {
+            operation: self.operation,
+            done,
+            total: self.total,
+            bytes,
+        });
+        self.reported = Some(done);
+        self.last = Instant::now();
+        if let Some(total) = self.total {
+            self.next = done + (total / MAX_EVENTS).max(1);
+        }
+    }
+
+    /// Report the progress if the rate limit allows.
+    pub(crate) fn update(&mut self, done: u64, bytes: u64) {
+        if self.progress.is_none() || self.reported == Some(done) {
+            return;
+        }
+        let due = match self.total {
+            Some(total) => done >= self.next || done == total,
+            None => self.last.elapsed() >= INTERVAL,
+        };
+        if due {
+            self.report(done, bytes);
+        }
+    }
+
+    /// Report the final progress of a successful operation.
+    pub(crate) fn finish(mut self, done: u64, bytes: u64) {
+        if self.reported != Some(done) {
+            self.report(done, bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rate_limit() {
+        let collector = ProgressCollector::new();
+        let mut reporter = ProgressReporter::new(Some(&collector), "test", Some(1000));
+        for done in 1..=1000 {
+            reporter.update(done, done * 4);
+        }
+        reporter.finish(1000, 4000);
+        let events = collector.events();
+        assert!(events.len() <= MAX_EVENTS as usize + 1);
+        assert!(events.windows(2).all(|w| w[0].done < w[1].done));
+        assert_eq!(events.last().unwrap().done, 1000);
+        assert_eq!(events.last().unwrap().bytes, 4000);
+
+        collector.clear();
+        let mut reporter = ProgressReporter::new(Some(&collector), "test", None);
+        for done in 1..=1000 {
+            reporter.update(done, done);
+        }
+        reporter.finish(1000, 1000);
+        let events = collector.events();
+        assert!(!events.is_empty());
+        assert_eq!(events.last().unwrap().done, 1000);
+        assert_eq!(events.last().unwrap().total, None);
+    }
+}
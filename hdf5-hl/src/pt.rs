@@ -1,10 +1,66 @@
+mod aggregate;
+pub use aggregate::*;
+
+mod attrs;
+use attrs::AttrValue;
+
 mod buf_writer;
 pub use buf_writer::*;
 
+mod iter;
+pub use iter::*;
+
+mod journal;
+pub use journal::*;
+
+mod defaults;
+
+mod diagnose;
+pub use diagnose::*;
+
+mod fields;
+
+mod group_writer;
+pub use group_writer::*;
+
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "serde_json")]
+mod ndjson;
+
+mod probe;
+pub use probe::*;
+
+mod raw;
+
+mod reader;
+pub use reader::*;
+
+mod schema;
+
+mod stats;
+pub use stats::*;
+
+mod typed;
+pub use typed::*;
+
+mod views;
+pub use views::*;
+
+use crate::{
+    validation_profile, AsGroup, CancelToken, BuilderError, Error, FilterRequest, GroupExt,
+    LinkOptions, PooledVec, Progress, ProgressReporter, RecordTransform, Result, StagingPool,
+    TableTransform, ValidationProfile, ZSTD_FILTER_ID,
+};
 use dst_container::*;
+#[cfg(feature = "blosc")]
+use hdf5::filters::{Blosc, BloscShuffle};
 use hdf5::{
-    from_id, h5call, h5lock, h5try, plist::DatasetCreate, types::TypeDescriptor, Dataset, Datatype,
-    Dimension, Error, Group, H5Type, Result,
+    from_id, h5call, h5lock, h5try,
+    plist::{DatasetAccess, DatasetCreate},
+    types::TypeDescriptor,
+    Dataset, Dataspace, Datatype, Dimension, Extents, Group, H5Type, LocationType,
 };
 use hdf5_dst::H5TypeUnsized;
 use hdf5_hl_sys::h5pt::{
@@ -13,14 +69,31 @@ use hdf5_hl_sys::h5pt::{
     H5PTread_packets, H5PTset_index,
 };
 use hdf5_sys::{
+    h5::hsize_t,
+    h5d::{H5Dopen2, H5Dwrite},
+    h5f::{H5Fget_intent, H5F_ACC_RDWR},
     h5i::{
         hid_t,
         H5I_type_t::{self, H5I_BADID, H5I_NTYPES},
         H5Iget_type, H5Iinc_ref,
     },
-    h5p::H5P_DEFAULT,
+    h5l::H5Lmove,
+    h5p::{
+        H5Premove_filter, H5Pset_attr_creation_order, H5Pset_chunk, H5Pset_fill_value,
+        H5Pset_obj_track_times, H5P_CRT_ORDER_INDEXED, H5P_CRT_ORDER_TRACKED, H5P_DEFAULT,
+    },
+    h5t::{H5Tcommit2, H5Tget_size, H5Topen2},
+    h5z::{H5Z_FILTER_ALL, H5Z_FILTER_DEFLATE, H5Z_FILTER_FLETCHER32, H5Z_FILTER_SHUFFLE},
+};
+use std::{
+    any::TypeId,
+    ffi::{c_uint, CStr, CString},
+    fmt::Debug,
+    mem::MaybeUninit,
+    ops::{Bound, RangeBounds, RangeInclusive},
+    ptr::Pointee,
+    sync::Arc,
 };
-use std::{ffi::CString, fmt::Debug, mem::MaybeUninit, ptr::Pointee};
 
 /// The packet type of a packet table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,15 +105,148 @@ pub enum PacketTableType {
     VarLen = 1,
 }
 
+/// Get the size of an unsized value with the metadata.
+fn size_of_metadata<T: ?Sized>(metadata: <T as Pointee>::Metadata) -> usize {
+    let ptr: *const T = std::ptr::from_raw_parts(std::ptr::null::<()>(), metadata);
+    // SAFETY: the metadata comes from a valid allocation.
+    unsafe { std::mem::size_of_val_raw(ptr) }
+}
+
+/// The operations [`PacketTable::append_checked`] is built on.
+trait AppendTarget {
+    fn num_packets(&self) -> Result<u64>;
+    fn append<T: H5Type>(&mut self, slice: &[T]) -> Result<()>;
+    fn truncate(&mut self, len: u64) -> Result<()>;
+}
+
+impl AppendTarget for PacketTable {
+    fn num_packets(&self) -> Result<u64> {
+        PacketTable::num_packets(self)
+    }
+
+    fn append<T: H5Type>(&mut self, slice: &[T]) -> Result<()> {
+        PacketTable::append(self, slice)
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<()> {
+        PacketTable::truncate(self, len)
+    }
+}
+
+/// Append the slice, and truncate back to the original length if it fails.
+fn append_rollback<T: H5Type>(target: &mut impl AppendTarget, slice: &[T]) -> Result<()> {
+    let len = target.num_packets()?;
+    let Err(error) = target.append(slice) else {
+        return Ok(());
+    };
+    let rollback = target.num_packets().and_then(|num| {
+        if num != len {
+            target.truncate(len)
+        } else {
+            Ok(())
+        }
+    });
+    match rollback {
+        Ok(()) => Err(error),
+        Err(rollback_error) => Err(Error::Rollback {
+            len,
+            error: Box::new(error),
+            rollback_error: Box::new(rollback_error),
+        }),
+    }
+}
+
+/// The link operations [`replace_link`] is built on.
+trait LinkTarget {
+    fn move_link(&self, src: &str, dst: &str) -> Result<()>;
+    fn unlink(&self, name: &str) -> Result<()>;
+}
+
+impl LinkTarget for Group {
+    /// Move the link, creating the new one with [`LinkOptions`].
+    fn move_link(&self, src: &str, dst: &str) -> Result<()> {
+        let c_src = CString::new(src)?;
+        let c_dst = CString::new(dst)?;
+        let lcpl = LinkOptions::new().lcpl(dst)?;
+        h5try!(H5Lmove(
+            self.id(),
+            c_src.as_ptr(),
+            self.id(),
+            c_dst.as_ptr(),
+            lcpl.id(),
+            H5P_DEFAULT
+        ));
+        Ok(())
+    }
+
+    fn unlink(&self, name: &str) -> Result<()> {
+        Ok(Group::unlink(self, name)?)
+    }
+}
+
+/// Move the table created with a temporary name over the table name.
+///
+/// The old table, if any, is moved to the backup name first, and moved back
+/// if moving the new table fails. The new table is unlinked on failure.
+/// A crash in between may leave the temporary or backup links in the file.
+fn replace_link(
+    target: &impl LinkTarget,
+    create_name: &str,
+    table_name: &str,
+    backup: Option<&str>,
+) -> Result<()> {
+    let res = match backup {
+        Some(backup) => target.move_link(table_name, backup).and_then(|()| {
+            target.move_link(create_name, table_name).inspect_err(|_| {
+                // The original error is more relevant than a failed restore.
+                target.move_link(backup, table_name).ok();
+            })
+        }),
+        None => target.move_link(create_name, table_name),
+    };
+    if let Err(error) = res {
+        // The original error is more relevant than a failed cleanup.
+        target.unlink(create_name).ok();
+        return Err(error);
+    }
+    match backup {
+        Some(backup) => target.unlink(backup),
+        None => Ok(()),
+    }
+}
+
 /// The HDF5 Packet Table is designed to allow records to be appended to and read from a table.
 /// Packet Table datasets are chunked, allowing them to grow as needed.
-#[repr(transparent)]
-pub struct PacketTable(hid_t);
+pub struct PacketTable {
+    id: hid_t,
+    packet_size: usize,
+    profile: Option<ValidationProfile>,
+    cancel: Option<CancelToken>,
+    progress: Option<Arc<dyn Progress>>,
+    transform: Option<TableTransform>,
+    max_packets: Option<u64>,
+    path: String,
+    // The descriptor of the datatype, if it is supported by `hdf5`.
+    descriptor: Option<TypeDescriptor>,
+    // The sized input types already checked, with the profile they were checked with.
+    checked_types: Vec<(TypeId, ValidationProfile)>,
+    journal: Option<Box<TypedPacketTable<JournalEntry>>>,
+}
 
-// Object impls.
 impl Debug for PacketTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "<HDF5: packet table>")
+        #[cfg(feature = "fmt-guard")]
+        let _guard = crate::fmt_guard::FmtGuard::enter();
+        // Only use cached data, because the caller may hold the HDF5 lock.
+        write!(
+            f,
+            "<HDF5: packet table {:?}, packet size {}",
+            self.path, self.packet_size
+        )?;
+        if let Some(max) = self.max_packets {
+            write!(f, ", max packets {max}")?;
+        }
+        write!(f, ">")
     }
 }
 
@@ -48,124 +254,707 @@ impl Debug for PacketTable {
 impl PacketTable {
     #[doc(hidden)]
     pub const fn id(&self) -> hid_t {
-        self.0
+        self.id
+    }
+
+    /// Get the id to call HDF5 with, checking that the table is not being formatted.
+    pub(crate) fn checked_id(&self) -> hid_t {
+        #[cfg(feature = "fmt-guard")]
+        debug_assert!(
+            !crate::fmt_guard::in_fmt(),
+            "HDF5 is called while formatting a packet table."
+        );
+        self.id
     }
 
     #[doc(hidden)]
     pub fn id_type(&self) -> H5I_type_t {
-        if self.id() <= 0 {
+        if self.checked_id() <= 0 {
             H5I_BADID
         } else {
-            match h5lock!(H5Iget_type(self.id())) {
+            match h5lock!(H5Iget_type(self.checked_id())) {
                 tp if tp > H5I_BADID && tp < H5I_NTYPES => tp,
                 _ => H5I_BADID,
             }
         }
     }
 
-    pub(crate) fn from_id(id: hid_t) -> Self {
-        Self(id)
+    pub(crate) fn from_id(id: hid_t) -> Result<Self> {
+        let mut table = Self {
+            id,
+            packet_size: 0,
+            profile: None,
+            cancel: None,
+            progress: None,
+            transform: None,
+            max_packets: None,
+            path: String::new(),
+            descriptor: None,
+            checked_types: Vec::new(),
+            journal: None,
+        };
+        let ty = h5try!(H5PTget_type(id));
+        table.packet_size = h5lock!(H5Tget_size(ty));
+        if table.packet_size == 0 {
+            return Err(Error::Other("Failed to get the packet size.".to_string()));
+        }
+        table.descriptor = table.type_descriptor().ok();
+        let dset = table.dataset()?;
+        table.path = dset.name();
+        // The max extent never changes, even when the dataset is resized.
+        if let Extents::Simple(extents) = dset.space()?.extents()? {
+            table.max_packets = extents
+                .iter()
+                .next()
+                .and_then(|e| e.max)
+                .map(|max| max as u64);
+        }
+        Ok(table)
     }
 }
 
 impl PacketTable {
     /// Create a packet table builder from a specified location.
-    pub fn builder(loc: &Group) -> PacketTableBuilder {
-        PacketTableBuilder::new(loc)
+    pub fn builder(loc: &impl AsGroup) -> PacketTableBuilder {
+        PacketTableBuilder::new(loc.as_group())
     }
 
     /// Open an existing packet table.
-    pub fn open(loc: &Group, dset_name: impl AsRef<str>) -> Result<Self> {
-        let dset_name = CString::new(dset_name.as_ref()).map_err(|e| e.to_string())?;
-        let table = h5try!(H5PTopen(loc.id(), dset_name.as_ptr()));
-        Ok(Self::from_id(table))
+    ///
+    /// Fails with [`Error::FilterUnavailable`] if a filter of the table is not available,
+    /// e.g., blosc or lzf without the corresponding feature, see [`PacketTable::check_filters`].
+    pub fn open(loc: &impl AsGroup, dset_name: impl AsRef<str>) -> Result<Self> {
+        let dset_name = CString::new(dset_name.as_ref())?;
+        let table = h5try!(H5PTopen(loc.as_group().id(), dset_name.as_ptr()));
+        let table = Self::from_id(table)?;
+        table.check_filters()?;
+        Ok(table)
+    }
+
+    /// Open the packet table from an existing [`Dataset`].
+    /// The dataset should be chunked and 1-dimensional,
+    /// otherwise [`Error::NotChunked`] or [`Error::WrongRank`] is returned.
+    pub fn from_dataset(dset: &Dataset) -> Result<Self> {
+        if !dset.is_chunked() {
+            return Err(Error::NotChunked);
+        }
+        let ndim = dset.ndim();
+        if ndim != 1 {
+            return Err(Error::WrongRank { ndim });
+        }
+        let file = dset.file()?;
+        Self::open(&file, dset.name())
+    }
+
+    /// Adopt an existing 1-dimensional chunked [`Dataset`] as a packet table to append to.
+    ///
+    /// Unlike [`PacketTable::from_dataset`], the dataset should also have unlimited
+    /// max extent, otherwise the appending will fail later, so [`Error::NotUnlimited`]
+    /// is returned. The preconditions are checked in the order of chunked, rank and extent.
+    pub fn adopt(dset: &Dataset) -> Result<Self> {
+        if !dset.is_chunked() {
+            return Err(Error::NotChunked);
+        }
+        let ndim = dset.ndim();
+        if ndim != 1 {
+            return Err(Error::WrongRank { ndim });
+        }
+        if let Extents::Simple(extents) = dset.space()?.extents()?
+            && let Some(max) = extents.iter().next().and_then(|e| e.max)
+        {
+            return Err(Error::NotUnlimited { max: max as u64 });
+        }
+        Self::from_dataset(dset)
+    }
+
+    /// Open another handle of the same packet table.
+    /// The index, the [`ValidationProfile`] override and the [`CancelToken`] are not shared.
+    pub fn try_clone(&self) -> Result<Self> {
+        Self::from_dataset(&self.dataset()?)
+    }
+
+    /// Get the [`ValidationProfile`] of the packet table.
+    /// It falls back to the global one if not overridden.
+    #[inline]
+    pub fn validation_profile(&self) -> ValidationProfile {
+        self.profile.unwrap_or_else(validation_profile)
+    }
+
+    /// Override the [`ValidationProfile`] of the packet table.
+    /// Set `None` to use the global one.
+    pub fn set_validation_profile(&mut self, profile: Option<ValidationProfile>) {
+        self.profile = profile;
+    }
+
+    /// Set the [`CancelToken`] observed by the long-running operations.
+    ///
+    /// The token is checked between batches of [`PacketTable::copy_raw_to`],
+    /// [`PacketTable::append_raw_from`], [`PacketTable::copy_to`],
+    /// [`PacketTable::for_each_packet`] and [`PacketTable::for_each_packet_unsized`].
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.set_cancel_token(Some(token));
+        self
+    }
+
+    /// Set or remove the [`CancelToken`].
+    pub fn set_cancel_token(&mut self, token: Option<CancelToken>) {
+        self.cancel = token;
+    }
+
+    /// Get the [`CancelToken`].
+    pub fn cancel_token(&self) -> Option<&CancelToken> {
+        self.cancel.as_ref()
+    }
+
+    /// Set the [`Progress`] receiving the events of the long-running operations.
+    ///
+    /// The operations observing the [`CancelToken`] and [`PacketTable::read_all`] report progress.
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.set_progress(Some(progress));
+        self
+    }
+
+    /// Set or remove the [`Progress`].
+    pub fn set_progress(&mut self, progress: Option<Arc<dyn Progress>>) {
+        self.progress = progress;
+    }
+
+    pub(crate) fn reporter(
+        &self,
+        operation: &'static str,
+        total: Option<u64>,
+    ) -> ProgressReporter<'_> {
+        ProgressReporter::new(self.progress.as_deref(), operation, total)
+    }
+
+    /// Set the [`RecordTransform`] applied to the appended and overwritten records.
+    ///
+    /// The output descriptor of `input` should match the stored datatype.
+    /// The transform is not applied to [`PacketTable::copy_to`], which copies the stored packets.
+    pub fn with_transform(
+        mut self,
+        transform: Box<dyn RecordTransform>,
+        input: TypeDescriptor,
+    ) -> Result<Self> {
+        let transform = TableTransform::new(transform.into(), input)?;
+        if Datatype::from_descriptor(&transform.output)? != self.dtype()? {
+            return Err(Error::TransformMismatch {
+                output: Box::new(transform.output),
+            });
+        }
+        self.transform = Some(transform);
+        self.checked_types.clear();
+        Ok(self)
+    }
+
+    /// Return [`Error::Cancelled`] if the token is cancelled.
+    /// The writers keep the packets written before the cancellation.
+    pub(crate) fn check_cancelled(&self, processed: u64) -> Result<()> {
+        match &self.cancel {
+            Some(token) if token.is_cancelled() => Err(Error::Cancelled {
+                processed,
+                rolled_back: false,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the memory size of a packet against the datatype size.
+    /// It is a memory-safety check, and cannot be skipped.
+    fn check_packet_size(&self, size: usize) -> Result<()> {
+        if size != self.packet_size {
+            return Err(Error::PacketSize {
+                expected: self.packet_size,
+                actual: size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the memory size of an unsized packet with the metadata.
+    fn check_metadata<T: ?Sized>(&self, metadata: <T as Pointee>::Metadata) -> Result<()> {
+        self.check_packet_size(size_of_metadata::<T>(metadata))
+    }
+
+    /// Get the size of the appended records, which differs from the packet size
+    /// if a transform is set.
+    pub(crate) fn input_size(&self) -> usize {
+        self.transform
+            .as_ref()
+            .map_or(self.packet_size, |t| t.input.size())
+    }
+
+    /// Check the memory size of an appended packet.
+    /// It is a memory-safety check, and cannot be skipped.
+    fn check_input_size(&self, size: usize) -> Result<()> {
+        let expected = self.input_size();
+        if size != expected {
+            return Err(Error::PacketSize {
+                expected,
+                actual: size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the type of the written records against the datatype,
+    /// or the input of the transform if set.
+    ///
+    /// The standard [`ValidationProfile`] compares the descriptor with the cached one,
+    /// and only compares the [`Datatype`]s if they differ, e.g., for reordered members.
+    /// The strict one always compares the [`Datatype`]s.
+    ///
+    /// A passed check of a sized type is cached by its `ty`, so the descriptor is only
+    /// built once, unless the profile becomes stricter. The unsized types pass `None`,
+    /// because their descriptors depend on the metadata, not only the type.
+    fn check_input_type(
+        &mut self,
+        ty: Option<TypeId>,
+        actual: impl FnOnce() -> TypeDescriptor,
+    ) -> Result<()> {
+        let profile = self.validation_profile();
+        if !profile.cheap_checks()
+            || self
+                .checked_types
+                .iter()
+                .any(|&(id, checked)| Some(id) == ty && checked >= profile)
+        {
+            return Ok(());
+        }
+        let actual = actual();
+        let expected = match &self.transform {
+            Some(transform) => Some(&transform.input),
+            None => self.descriptor.as_ref(),
+        };
+        if profile.expensive_checks() || expected != Some(&actual) {
+            let dtype = match &self.transform {
+                Some(transform) => Datatype::from_descriptor(&transform.input)?,
+                None => self.dtype()?,
+            };
+            if Datatype::from_descriptor(&actual)? != dtype {
+                return Err(Error::SchemaMismatch {
+                    expected: Box::new(dtype.to_descriptor()?),
+                    actual: Box::new(actual),
+                });
+            }
+        }
+        if let Some(ty) = ty {
+            self.checked_types.push((ty, profile));
+        }
+        Ok(())
+    }
+
+    /// Append `len` records, passing them through the transform if set.
+    /// The size of the records should have been checked.
+    ///
+    /// With the strict [`ValidationProfile`], it fails with [`Error::ReadOnly`]
+    /// before calling HDF5 if the file is opened read-only.
+    pub(crate) fn append_ptr(&mut self, len: usize, ptr: *const ()) -> Result<()> {
+        let ptr = match &mut self.transform {
+            // SAFETY: the size of the records is checked.
+            Some(transform) => unsafe { transform.apply(ptr, len) }?.as_ptr() as *const (),
+            None => ptr,
+        };
+        self.append_stored(len, ptr)
+    }
+
+    /// Append `len` packets in the stored layout, bypassing the transform.
+    /// The size of the packets should have been checked.
+    ///
+    /// The appended range is logged if the table is journaled.
+    fn append_stored(&mut self, len: usize, ptr: *const ()) -> Result<()> {
+        if self.validation_profile().expensive_checks() && !self.is_writable()? {
+            return Err(Error::ReadOnly {
+                path: self.path.clone(),
+            });
+        }
+        self.check_capacity(len)?;
+        h5try!(H5PTappend(self.checked_id(), len, ptr as *const _));
+        if self.journal.is_some() {
+            let start = self.num_packets()? - len as u64;
+            self.log(JournalOp::Append, start, len as u64)?;
+        }
+        Ok(())
     }
 
     /// Push one element into the packet table.
-    pub fn push<T: ?Sized>(&mut self, val: &T) -> Result<()> {
+    pub fn push<T: ?Sized + H5TypeUnsized>(&mut self, val: &T) -> Result<()> {
+        self.check_input_size(size_of_val(val))?;
+        self.check_input_type(None, || val.type_descriptor())?;
         let (ptr, _) = (val as *const T).to_raw_parts();
-        h5try!(H5PTappend(self.id(), 1, ptr as *const _));
-        Ok(())
+        self.append_ptr(1, ptr)
     }
 
     /// Append a slice into the packet table.
-    pub fn append<T>(&mut self, slice: &[T]) -> Result<()> {
-        h5try!(H5PTappend(
-            self.id(),
-            slice.len(),
-            slice.as_ptr() as *const _
+    pub fn append<T: H5Type>(&mut self, slice: &[T]) -> Result<()> {
+        if slice.is_empty() {
+            return Ok(());
+        }
+        self.check_input_size(size_of::<T>())?;
+        self.check_input_type(Some(TypeId::of::<T>()), <T as H5Type>::type_descriptor)?;
+        self.append_ptr(slice.len(), slice.as_ptr() as *const _)
+    }
+
+    /// Append a slice into the packet table, and shrink the table back
+    /// to the original number of packets if the append fails.
+    ///
+    /// True atomicity isn't possible: HDF5 may have extended the dataset
+    /// and written part of the packets before failing, and another handle may observe them.
+    /// The rollback is best effort, by [truncating](PacketTable::truncate) the table.
+    /// If the rollback also fails, [`Error::Rollback`] reports both errors.
+    pub fn append_checked<T: H5Type>(&mut self, slice: &[T]) -> Result<()> {
+        append_rollback(self, slice)
+    }
+
+    /// Append several slices into the packet table, holding the HDF5 lock once.
+    ///
+    /// The packets and the capacity are checked once for all batches.
+    /// The batches are appended in order. If one fails, the previous batches are kept,
+    /// and [`Error::PartialAppend`] reports how many batches have been appended.
+    /// The appended packets are logged as one journal entry.
+    pub fn append_many<T: H5Type>(&mut self, batches: &[&[T]]) -> Result<()> {
+        let total = batches.iter().map(|batch| batch.len()).sum::<usize>();
+        if total == 0 {
+            return Ok(());
+        }
+        self.check_input_size(size_of::<T>())?;
+        self.check_input_type(Some(TypeId::of::<T>()), <T as H5Type>::type_descriptor)?;
+        if self.validation_profile().expensive_checks() && !self.is_writable()? {
+            return Err(Error::ReadOnly {
+                path: self.path.clone(),
+            });
+        }
+        self.check_capacity(total)?;
+        let start = if self.journal.is_some() {
+            self.num_packets()?
+        } else {
+            0
+        };
+        let id = self.checked_id();
+        let mut appended = 0;
+        let res = h5lock!({
+            for (i, batch) in batches.iter().enumerate() {
+                if batch.is_empty() {
+                    continue;
+                }
+                let ptr = batch.as_ptr() as *const ();
+                let ptr = match &mut self.transform {
+                    // SAFETY: the size of the records is checked.
+                    Some(transform) => match unsafe { transform.apply(ptr, batch.len()) } {
+                        Ok(buffer) => buffer.as_ptr() as *const (),
+                        Err(e) => return Err((i, e)),
+                    },
+                    None => ptr,
+                };
+                if let Err(e) = hdf5::h5check(H5PTappend(id, batch.len(), ptr as *const _)) {
+                    return Err((i, e.into()));
+                }
+                appended += batch.len();
+            }
+            Ok(())
+        });
+        let logged = if appended > 0 {
+            self.log(JournalOp::Append, start, appended as u64)
+        } else {
+            Ok(())
+        };
+        res.map_err(|(batches, e)| Error::PartialAppend {
+            batches,
+            error: Box::new(e),
+        })?;
+        logged
+    }
+
+    /// Overwrite the existing packets from the start index with the slice.
+    ///
+    /// It writes the inner [`Dataset`] directly, bypassing the packet table API.
+    /// The table is not extended, so the range should be in `0..num_packets`.
+    /// The records are passed through the [`RecordTransform`] if set.
+    pub fn overwrite<T: H5Type>(&mut self, start: u64, slice: &[T]) -> Result<()> {
+        self.check_remaining(start, slice.len())?;
+        if slice.is_empty() {
+            return Ok(());
+        }
+        self.check_input_size(size_of::<T>())?;
+        self.check_input_type(Some(TypeId::of::<T>()), <T as H5Type>::type_descriptor)?;
+        self.overwrite_raw(start, slice.len(), slice.as_ptr() as *const _)
+    }
+
+    /// Overwrite the existing packet at the index.
+    ///
+    /// See [`PacketTable::overwrite`].
+    pub fn write_at<T: H5Type>(&mut self, index: u64, val: &T) -> Result<()> {
+        self.overwrite(index, std::slice::from_ref(val))
+    }
+
+    /// Overwrite the existing packet at the index with an unsized value.
+    ///
+    /// See [`PacketTable::overwrite`].
+    pub fn write_at_unsized<T: ?Sized + H5TypeUnsized>(
+        &mut self,
+        index: u64,
+        val: &T,
+    ) -> Result<()> {
+        self.check_remaining(index, 1)?;
+        self.check_input_size(size_of_val(val))?;
+        self.check_input_type(None, || val.type_descriptor())?;
+        let (ptr, _) = (val as *const T).to_raw_parts();
+        self.overwrite_raw(index, 1, ptr)
+    }
+
+    /// Shrink the packet table to `len` packets.
+    ///
+    /// The inner [`Dataset`] is resized, and the packet table is reopened,
+    /// because HDF5 caches the number of packets. The settings are kept,
+    /// and the index is clamped to the new length.
+    /// Growing the table fails with [`Error::TruncateBeyondEnd`].
+    pub fn truncate(&mut self, len: u64) -> Result<()> {
+        let num = self.num_packets()?;
+        if len > num {
+            return Err(Error::TruncateBeyondEnd {
+                len,
+                num_packets: num,
+            });
+        }
+        let dset = self.dataset()?;
+        dset.resize(len as usize)?;
+        self.reload(Self::from_dataset(&dset)?)?;
+        self.log(JournalOp::Truncate, len, num - len)
+    }
+
+    /// Reopen the packet table at the path where it was opened,
+    /// and replace the handle and all cached states with the new ones.
+    ///
+    /// The handle caches the number of packets, the packet size and the max extent,
+    /// and refers to the object rather than the path.
+    /// [`PacketTable::truncate`] refreshes implicitly, but the changes by other handles,
+    /// e.g., appends from other processes, or replacing the link,
+    /// require calling it explicitly.
+    /// The index is kept, and clamped to the number of packets.
+    /// If a [`RecordTransform`] is set, its output should still match the datatype,
+    /// otherwise it fails and the old handle is kept.
+    pub fn refresh(&mut self) -> Result<()> {
+        let file = self.dataset()?.file()?;
+        let table = Self::open(&file, &self.path)?;
+        self.reload(table)
+    }
+
+    /// Replace the handle and the cached states with the ones of `table`.
+    fn reload(&mut self, mut table: Self) -> Result<()> {
+        if let Some(transform) = &self.transform
+            && Datatype::from_descriptor(&transform.output)? != table.dtype()?
+        {
+            return Err(Error::TransformMismatch {
+                output: Box::new(transform.output.clone()),
+            });
+        }
+        let index = self.index()?;
+        // The old handle is closed when `table` is dropped.
+        std::mem::swap(&mut self.id, &mut table.id);
+        self.packet_size = table.packet_size;
+        self.max_packets = table.max_packets;
+        self.path = std::mem::take(&mut table.path);
+        self.descriptor = table.descriptor.take();
+        self.checked_types.clear();
+        let num = self.num_packets()?;
+        self.set_index(index.min(num))
+    }
+
+    /// Write `len` input records, passing them through the transform if set.
+    /// The size of the records should have been checked.
+    fn overwrite_raw(&mut self, start: u64, len: usize, ptr: *const ()) -> Result<()> {
+        let dset = self.dataset()?;
+        let dtype = self.dtype()?;
+        let end = Self::checked_end(start, len)?;
+        let file_space = dset.space()?.select(start as usize..end as usize)?;
+        let mem_space = Dataspace::try_new(len)?;
+        let ptr = match &mut self.transform {
+            // SAFETY: the size of the records is checked.
+            Some(transform) => unsafe { transform.apply(ptr, len) }?.as_ptr() as *const (),
+            None => ptr,
+        };
+        h5try!(H5Dwrite(
+            dset.id(),
+            dtype.id(),
+            mem_space.id(),
+            file_space.id(),
+            H5P_DEFAULT,
+            ptr as *const _
         ));
-        Ok(())
+        self.log(JournalOp::Overwrite, start, len as u64)
     }
 
     /// Append an unsized vector into the packet table.
-    pub fn append_unsized<T: ?Sized>(&mut self, vec: &FixedVec<T>) -> Result<()> {
-        let (ptr, _) = vec.as_ptr().to_raw_parts();
-        h5try!(H5PTappend(self.id(), vec.len(), ptr as *const _));
-        Ok(())
+    pub fn append_unsized<T: ?Sized + H5TypeUnsized>(&mut self, vec: &FixedVec<T>) -> Result<()> {
+        if vec.is_empty() {
+            return Ok(());
+        }
+        let (ptr, metadata) = vec.as_ptr().to_raw_parts();
+        self.check_input_size(size_of_metadata::<T>(metadata))?;
+        self.check_input_type(None, || vec[0].type_descriptor())?;
+        self.append_ptr(vec.len(), ptr)
     }
 
     /// Get the inner [`Dataset`] from the packet table.
     pub fn dataset(&self) -> Result<Dataset> {
-        let dset = h5try!(H5PTget_dataset(self.id()));
+        let dset = h5try!(H5PTget_dataset(self.checked_id()));
         h5lock!(H5Iinc_ref(dset));
-        unsafe { from_id(dset) }
+        Ok(unsafe { from_id(dset) }?)
     }
 
     /// Determine if the current packet table is valid.
     pub fn validate(&self) -> Result<()> {
-        h5try!(H5PTis_valid(self.id()));
+        h5try!(H5PTis_valid(self.checked_id()));
         Ok(())
     }
 
     /// Determines whether a packet table contains variable-length or fixed-length packets.
     pub fn table_type(&self) -> Result<PacketTableType> {
-        let ty = h5try!(H5PTis_varlen(self.id()));
+        let ty = h5try!(H5PTis_varlen(self.checked_id()));
         match ty {
             0 => Ok(PacketTableType::Fixed),
             1 => Ok(PacketTableType::VarLen),
-            _ => Err(Error::Internal("Invalid packet table type.".to_string())),
+            _ => Err(Error::Other("Invalid packet table type.".to_string())),
         }
     }
 
+    /// Determines whether a packet table contains variable-length packets.
+    pub fn is_varlen(&self) -> Result<bool> {
+        Ok(self.table_type()? == PacketTableType::VarLen)
+    }
+
+    /// Determines whether a packet table contains fixed-length packets.
+    pub fn is_fixed(&self) -> Result<bool> {
+        Ok(self.table_type()? == PacketTableType::Fixed)
+    }
+
+    /// Determines whether the file of the packet table is opened for writing.
+    pub fn is_writable(&self) -> Result<bool> {
+        let file = self.dataset()?.file()?;
+        let mut intent: c_uint = 0;
+        h5try!(H5Fget_intent(file.id(), &mut intent));
+        Ok(intent & H5F_ACC_RDWR != 0)
+    }
+
     /// Get the inner [`Datatype`] from the packet table.
     pub fn dtype(&self) -> Result<Datatype> {
-        let ty = h5try!(H5PTget_type(self.id()));
+        let ty = h5try!(H5PTget_type(self.checked_id()));
         h5lock!(H5Iinc_ref(ty));
-        unsafe { from_id(ty) }
+        Ok(unsafe { from_id(ty) }?)
+    }
+
+    /// Get the [`TypeDescriptor`] of the packets,
+    /// e.g., to discover the compound members before reading.
+    pub fn type_descriptor(&self) -> Result<TypeDescriptor> {
+        Ok(self.dtype()?.to_descriptor()?)
     }
 
     /// Get the number of packets.
     pub fn num_packets(&self) -> Result<u64> {
         let mut len = 0;
-        h5try!(H5PTget_num_packets(self.id(), &mut len));
+        h5try!(H5PTget_num_packets(self.checked_id(), &mut len));
         Ok(len)
     }
 
+    /// Get the number of packets as `usize`.
+    pub fn len(&self) -> Result<usize> {
+        let len = self.num_packets()?;
+        Ok(usize::try_from(len)?)
+    }
+
+    /// Determine if the packet table contains no packets.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.num_packets()? == 0)
+    }
+
+    /// Get the number of packets which could still be appended,
+    /// or `None` if the max extent of the dataset is unlimited.
+    pub fn capacity_remaining(&self) -> Result<Option<u64>> {
+        match self.max_packets {
+            Some(max) => Ok(Some(max.saturating_sub(self.num_packets()?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Check that `len` packets could be appended.
+    pub(crate) fn check_capacity(&self, len: usize) -> Result<()> {
+        if let Some(remaining) = self.capacity_remaining()?
+            && len as u64 > remaining
+        {
+            return Err(Error::CapacityExceeded {
+                len: len as u64,
+                remaining,
+            });
+        }
+        Ok(())
+    }
+
+    /// Resolve the range of packets to `start..end`, checking it against [`Self::num_packets`].
+    pub(crate) fn resolve_range(&self, range: impl RangeBounds<u64>) -> Result<(u64, u64)> {
+        let num = self.num_packets()?;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).ok_or(Error::RangeOverflow)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).ok_or(Error::RangeOverflow)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => num,
+        };
+        if start > end || end > num {
+            return Err(Error::InvalidRange {
+                start,
+                end,
+                num_packets: num,
+            });
+        }
+        Ok((start, end))
+    }
+
     /// Reset the current index to 0.
     pub fn reset_index(&mut self) -> Result<()> {
-        h5try!(H5PTcreate_index(self.id()));
+        h5try!(H5PTcreate_index(self.checked_id()));
         Ok(())
     }
 
     /// Set the current index.
+    ///
+    /// The index is not checked against [`Self::num_packets`]. If it is beyond the end,
+    /// [`Self::read_next_one`] returns `None`, and the other reads from the index fail.
     pub fn set_index(&mut self, index: u64) -> Result<()> {
-        h5try!(H5PTset_index(self.id(), index));
+        h5try!(H5PTset_index(self.checked_id(), index));
         Ok(())
     }
 
     /// Get the current index.
     pub fn index(&self) -> Result<u64> {
         let mut index = 0;
-        h5try!(H5PTget_index(self.id(), &mut index));
+        h5try!(H5PTget_index(self.checked_id(), &mut index));
         Ok(index)
     }
 
+    /// Move the current index by a signed offset.
+    /// The new index should be in `0..=num_packets`.
+    pub fn advance_index(&mut self, delta: i64) -> Result<()> {
+        let index = self.index()?;
+        let num = self.num_packets()?;
+        match index.checked_add_signed(delta) {
+            Some(new_index) if new_index <= num => self.set_index(new_index),
+            _ => Err(Error::InvalidIndex {
+                index,
+                delta,
+                num_packets: num,
+            }),
+        }
+    }
+
     fn read_impl<T>(
         &self,
         len: usize,
         f: impl FnOnce(&mut [MaybeUninit<T>]) -> Result<()>,
     ) -> Result<Vec<T>> {
+        self.check_packet_size(size_of::<T>())?;
         let mut vec = Vec::with_capacity(len);
         let uninit = vec.spare_capacity_mut();
         f(uninit)?;
@@ -176,25 +965,163 @@ impl PacketTable {
         Ok(vec)
     }
 
+    /// Read exactly `buf.len()` packets from a specified packet index
+    /// into the caller-owned storage, which could be reused between reads.
+    ///
+    /// The buffer is initialized only if the read succeeds,
+    /// and it is up to the caller to assume it initialized.
+    pub fn read_into_uninit<T>(&self, start: u64, buf: &mut [MaybeUninit<T>]) -> Result<()> {
+        // SAFETY: the buffer is valid for `buf.len()` packets.
+        unsafe { self.read_to_ptr(start, buf.len(), buf.as_mut_ptr() as *mut T) }
+    }
+
+    /// Read `count` packets from a specified packet index to the pointer,
+    /// e.g., a memory-mapped region.
+    ///
+    /// The packet size is checked, but the pointer is not.
+    ///
+    /// # Safety
+    ///
+    /// `dst` should be valid for writes of `count` values of `T`, and properly aligned.
+    /// The values are initialized only if the read succeeds.
+    pub unsafe fn read_to_ptr<T>(&self, start: u64, count: usize, dst: *mut T) -> Result<()> {
+        Self::checked_end(start, count)?;
+        self.check_packet_size(size_of::<T>())?;
+        h5try!(H5PTread_packets(
+            self.checked_id(),
+            start,
+            count,
+            dst as *mut _
+        ));
+        Ok(())
+    }
+
     /// Read from a specified packet index and take some data.
     pub fn read<T>(&self, start: u64, len: usize) -> Result<Vec<T>> {
-        self.read_impl(len, |uninit| {
-            h5try!(H5PTread_packets(
-                self.id(),
-                start,
+        self.read_impl(len, |uninit| self.read_into_uninit(start, uninit))
+    }
+
+    /// Read all packets.
+    pub fn read_all<T>(&self) -> Result<Vec<T>> {
+        let len = self.len()?;
+        let reporter = self.reporter("read_all", Some(len as u64));
+        let vec = self.read(0, len)?;
+        reporter.finish(len as u64, (len * self.packet_size) as u64);
+        Ok(vec)
+    }
+
+    /// Check the index after a [`H5PTget_next`] call started at `index`.
+    ///
+    /// On failure, the index is restored, because HDF5 may advance it after a
+    /// partial conversion. On success, the index should advance exactly `len`.
+    fn check_next_index<R>(&mut self, index: u64, len: usize, res: Result<R>) -> Result<R> {
+        match res {
+            Ok(res) => {
+                if self.validation_profile().cheap_checks() {
+                    let expected = Self::checked_end(index, len)?;
+                    let actual = self.index()?;
+                    if actual != expected {
+                        return Err(Error::InconsistentIndex { expected, actual });
+                    }
+                }
+                Ok(res)
+            }
+            Err(e) => match self.set_index(index) {
+                Ok(()) => Err(e),
+                Err(restore_error) => Err(Error::IndexRestore {
+                    index,
+                    error: Box::new(e),
+                    restore_error: Box::new(restore_error),
+                }),
+            },
+        }
+    }
+
+    /// Read from current index and update the index if the operation succeeds.
+    pub fn read_next<T>(&mut self, len: usize) -> Result<Vec<T>> {
+        let index = self.index()?;
+        self.check_remaining(index, len)?;
+        let res = self.read_impl(len, |uninit| {
+            h5try!(H5PTget_next(
+                self.checked_id(),
                 len,
                 uninit.as_mut_ptr() as *mut _
             ));
             Ok(())
-        })
+        });
+        self.check_next_index(index, len, res)
     }
 
-    /// Read from current index and update the index if the operation succeeds.
-    pub fn read_next<T>(&mut self, len: usize) -> Result<Vec<T>> {
-        self.read_impl(len, |uninit| {
-            h5try!(H5PTget_next(self.id(), len, uninit.as_mut_ptr() as *mut _));
-            Ok(())
-        })
+    /// Read one packet from current index and update the index if the operation succeeds.
+    /// Returns `None` if the index has reached the end of the table.
+    pub fn read_next_one<T>(&mut self) -> Result<Option<T>> {
+        let index = self.index()?;
+        if index >= self.num_packets()? {
+            return Ok(None);
+        }
+        self.check_packet_size(size_of::<T>())?;
+        let mut val = MaybeUninit::uninit();
+        let res = h5call!(H5PTget_next(
+            self.checked_id(),
+            1,
+            val.as_mut_ptr() as *mut _
+        ))
+        .map_err(Error::from);
+        self.check_next_index(index, 1, res)?;
+        // SAFETY: read succeeded.
+        Ok(Some(unsafe { val.assume_init() }))
+    }
+
+    /// Get `start + len`, failing with [`Error::RangeOverflow`] instead of wrapping.
+    pub(crate) fn checked_end(start: u64, len: usize) -> Result<u64> {
+        start.checked_add(len as u64).ok_or(Error::RangeOverflow)
+    }
+
+    fn check_remaining(&self, start: u64, len: usize) -> Result<()> {
+        let num = self.num_packets()?;
+        if Self::checked_end(start, len)? > num {
+            return Err(Error::NotEnoughPackets {
+                start,
+                len,
+                num_packets: num,
+            });
+        }
+        Ok(())
+    }
+
+    /// Read exactly `N` packets from a specified packet index.
+    pub fn read_array<T, const N: usize>(&self, start: u64) -> Result<[T; N]> {
+        self.check_remaining(start, N)?;
+        let mut arr = MaybeUninit::<[T; N]>::uninit();
+        // SAFETY: the array is valid for `N` packets, and read succeeded.
+        unsafe {
+            self.read_to_ptr(start, N, arr.as_mut_ptr() as *mut T)?;
+            Ok(arr.assume_init())
+        }
+    }
+
+    /// Read the packet at the specified index.
+    /// Returns [`Error::NotEnoughPackets`] if the index is out of range.
+    pub fn get<T>(&self, index: u64) -> Result<T> {
+        let [val] = self.read_array::<T, 1>(index)?;
+        Ok(val)
+    }
+
+    /// Read exactly `N` packets from current index and update the index if the operation succeeds.
+    pub fn read_next_array<T, const N: usize>(&mut self) -> Result<[T; N]> {
+        let index = self.index()?;
+        self.check_remaining(index, N)?;
+        self.check_packet_size(size_of::<T>())?;
+        let mut arr = MaybeUninit::<[T; N]>::uninit();
+        let res = h5call!(H5PTget_next(
+            self.checked_id(),
+            N,
+            arr.as_mut_ptr() as *mut _
+        ))
+        .map_err(Error::from);
+        self.check_next_index(index, N, res)?;
+        // SAFETY: read succeeded.
+        Ok(unsafe { arr.assume_init() })
     }
 
     fn read_unsized_impl<T: ?Sized>(
@@ -203,6 +1130,7 @@ impl PacketTable {
         buffer: &mut FixedVec<T>,
         f: impl FnOnce(*mut ()) -> Result<()>,
     ) -> Result<()> {
+        self.check_metadata::<T>(buffer.as_ptr().to_raw_parts().1)?;
         let old_len = buffer.len();
         buffer.reserve(len);
         let (ptr, _) = unsafe { buffer.get_unchecked_mut(old_len) as *mut T }.to_raw_parts();
@@ -220,8 +1148,14 @@ impl PacketTable {
         len: usize,
         buffer: &mut FixedVec<T>,
     ) -> Result<()> {
+        Self::checked_end(start, len)?;
         self.read_unsized_impl(len, buffer, |ptr| {
-            h5try!(H5PTread_packets(self.id(), start, len, ptr as *mut _));
+            h5try!(H5PTread_packets(
+                self.checked_id(),
+                start,
+                len,
+                ptr as *mut _
+            ));
             Ok(())
         })
     }
@@ -232,51 +1166,267 @@ impl PacketTable {
         len: usize,
         buffer: &mut FixedVec<T>,
     ) -> Result<()> {
-        self.read_unsized_impl(len, buffer, |ptr| {
-            h5try!(H5PTget_next(self.id(), len, ptr as *mut _));
+        let index = self.index()?;
+        self.check_remaining(index, len)?;
+        let res = self.read_unsized_impl(len, buffer, |ptr| {
+            h5try!(H5PTget_next(self.checked_id(), len, ptr as *mut _));
             Ok(())
-        })
+        });
+        self.check_next_index(index, len, res)
+    }
+
+    /// Read one packet from current index into the buffer and update the index if the operation succeeds.
+    /// Returns `false` if the index has reached the end of the table.
+    pub fn read_next_one_unsized<T: ?Sized>(&mut self, buffer: &mut FixedVec<T>) -> Result<bool> {
+        if self.index()? >= self.num_packets()? {
+            return Ok(false);
+        }
+        self.read_next_unsized(1, buffer)?;
+        Ok(true)
     }
 
-    /// Create an iterator to read the packets one by one.
+    /// Create an iterator over the packets.
     /// It doesn't influence the index of the packet table.
-    #[allow(clippy::needless_lifetimes)]
-    pub fn iter<'a, T>(&'a self) -> impl Iterator<Item = Result<T>> + 'a {
-        let mut index = 0u64;
-        std::iter::from_fn::<Result<T>, _>(move || {
-            let mut read_one = || {
-                if index < self.num_packets()? {
-                    let mut val = MaybeUninit::uninit();
-                    h5try!(H5PTread_packets(
-                        self.id(),
-                        index,
-                        1,
-                        val.as_mut_ptr() as *mut _
-                    ));
-                    index += 1;
-                    Ok(Some(unsafe { val.assume_init() }))
-                } else {
-                    Ok(None)
-                }
-            };
-            read_one().transpose()
-        })
+    ///
+    /// The packets are read in blocks of about [`AUTO_CHUNK_BYTES`], not one by one,
+    /// see [`Self::iter_indexed_from`] for the memory cost.
+    pub fn iter<T>(&self) -> impl Iterator<Item = Result<T>> + use<'_, T> {
+        self.iter_from(0)
     }
-}
 
-impl Drop for PacketTable {
+    /// Create an iterator over the packets from the start index.
+    /// It doesn't influence the index of the packet table.
+    ///
+    /// The packets are read in blocks, see [`Self::iter_indexed_from`] for the memory cost.
+    pub fn iter_from<T>(&self, start: u64) -> impl Iterator<Item = Result<T>> + use<'_, T> {
+        self.iter_indexed_from(start)
+            .map(|res| res.map(|(_, val)| val))
+    }
+
+    /// Create an iterator over the packets, with their absolute indices.
+    /// It doesn't influence the index of the packet table.
+    ///
+    /// The packets are read in blocks, see [`Self::iter_indexed_from`] for the memory cost.
+    pub fn iter_indexed<T>(&self) -> impl Iterator<Item = Result<(u64, T)>> + use<'_, T> {
+        self.iter_indexed_from(0)
+    }
+
+    /// Create an iterator over the packets from the start index,
+    /// with their absolute indices.
+    /// It doesn't influence the index of the packet table.
+    ///
+    /// The packets are read by [`Self::read_chunks`] in blocks of [`auto_chunk`] packets,
+    /// which is about [`AUTO_CHUNK_BYTES`] but at least 16 packets, rather than one by one.
+    /// The iterator holds up to one block in memory, so the large packets cost more.
+    /// Use [`Self::read_chunks`] to choose the block size.
+    /// The iterator stops after the first error.
+    pub fn iter_indexed_from<T>(
+        &self,
+        start: u64,
+    ) -> impl Iterator<Item = Result<(u64, T)>> + use<'_, T> {
+        let mut index = start;
+        self.read_chunks_from(start, auto_chunk(size_of::<T>()))
+            .flat_map(move |res| {
+                let (vals, err) = match res {
+                    Ok(vals) => (vals, None),
+                    Err(e) => (vec![], Some(e)),
+                };
+                let first = index;
+                index += vals.len() as u64;
+                (first..).zip(vals).map(Ok).chain(err.map(Err))
+            })
+    }
+
+    /// Create an iterator to read the packets in blocks of up to `chunk` packets.
+    /// The last block may be shorter.
+    /// It doesn't influence the index of the packet table.
+    ///
+    /// Each block is read at once into a new [`Vec`], so that it could be sent to other threads.
+    /// The iterator stops after the first error.
+    pub fn read_chunks<T>(&self, chunk: usize) -> impl Iterator<Item = Result<Vec<T>>> + '_ {
+        self.read_chunks_from(0, chunk)
+    }
+
+    fn read_chunks_from<T>(
+        &self,
+        start: u64,
+        chunk: usize,
+    ) -> impl Iterator<Item = Result<Vec<T>>> + '_ {
+        let mut index = start;
+        let mut done = false;
+        std::iter::from_fn::<Result<Vec<T>>, _>(move || {
+            if done {
+                return None;
+            }
+            let mut read_chunk = || {
+                if chunk == 0 {
+                    return Err(Error::ZeroChunk);
+                }
+                let num = self.num_packets()?;
+                if index >= num {
+                    return Ok(None);
+                }
+                let len = (num - index).min(chunk as u64) as usize;
+                let vec = self.read(index, len)?;
+                index += len as u64;
+                Ok(Some(vec))
+            };
+            let res = read_chunk().transpose();
+            done = !matches!(res, Some(Ok(_)));
+            res
+        })
+    }
+
+    /// Visit all packets with a callback, reading `chunk` packets at a time
+    /// into a reused buffer. Stops at the first error returned by the callback.
+    /// It doesn't influence the index of the packet table.
+    pub fn for_each_packet<T, F: FnMut(&T) -> Result<()>>(
+        &self,
+        chunk: usize,
+        mut f: F,
+    ) -> Result<()> {
+        if chunk == 0 {
+            return Err(Error::ZeroChunk);
+        }
+        self.check_packet_size(size_of::<T>())?;
+        let len = self.num_packets()?;
+        let mut reporter = self.reporter("for_each_packet", Some(len));
+        let mut buffer = Vec::with_capacity(chunk);
+        let mut start = 0u64;
+        while start < len {
+            self.check_cancelled(start)?;
+            let count = (len - start).min(chunk as u64) as usize;
+            buffer.clear();
+            h5try!(H5PTread_packets(
+                self.checked_id(),
+                start,
+                count,
+                buffer.spare_capacity_mut().as_mut_ptr() as *mut _
+            ));
+            // SAFETY: read succeeded.
+            unsafe {
+                buffer.set_len(count);
+            }
+            for val in &buffer {
+                f(val)?;
+            }
+            start += count as u64;
+            reporter.update(start, start * self.packet_size as u64);
+        }
+        reporter.finish(len, len * self.packet_size as u64);
+        Ok(())
+    }
+
+    /// Visit all unsized packets with a callback, reading `chunk` packets at a time
+    /// into a reused buffer. Stops at the first error returned by the callback.
+    /// It doesn't influence the index of the packet table.
+    pub fn for_each_packet_unsized<T: ?Sized, F: FnMut(&T) -> Result<()>>(
+        &self,
+        metadata: <T as Pointee>::Metadata,
+        chunk: usize,
+        f: F,
+    ) -> Result<()> {
+        self.for_each_packet_unsized_impl(PooledVec::unpooled(metadata, chunk), chunk, f)
+    }
+
+    /// Visit all unsized packets with a callback like [`PacketTable::for_each_packet_unsized`].
+    /// The buffer is drawn from the pool.
+    pub fn for_each_packet_unsized_pooled<T: ?Sized, F: FnMut(&T) -> Result<()>>(
+        &self,
+        pool: &StagingPool<T>,
+        metadata: <T as Pointee>::Metadata,
+        chunk: usize,
+        f: F,
+    ) -> Result<()> {
+        self.for_each_packet_unsized_impl(pool.acquire(metadata, chunk), chunk, f)
+    }
+
+    fn for_each_packet_unsized_impl<T: ?Sized, F: FnMut(&T) -> Result<()>>(
+        &self,
+        mut buffer: PooledVec<'_, T>,
+        chunk: usize,
+        mut f: F,
+    ) -> Result<()> {
+        if chunk == 0 {
+            return Err(Error::ZeroChunk);
+        }
+        let len = self.num_packets()?;
+        let mut reporter = self.reporter("for_each_packet_unsized", Some(len));
+        let mut start = 0u64;
+        while start < len {
+            self.check_cancelled(start)?;
+            let count = (len - start).min(chunk as u64) as usize;
+            buffer.clear();
+            self.read_unsized(start, count, &mut buffer)?;
+            for i in 0..buffer.len() {
+                f(&buffer[i])?;
+            }
+            start += count as u64;
+            reporter.update(start, start * self.packet_size as u64);
+        }
+        reporter.finish(len, len * self.packet_size as u64);
+        Ok(())
+    }
+
+    /// Create a reader to read the unsized packets one by one.
+    /// It doesn't influence the index of the packet table.
+    pub fn iter_unsized<T: ?Sized>(
+        &self,
+        metadata: <T as Pointee>::Metadata,
+    ) -> PacketTableIterUnsized<'_, T> {
+        PacketTableIterUnsized::new(self, metadata)
+    }
+}
+
+impl Drop for PacketTable {
     fn drop(&mut self) {
-        h5call!(H5PTclose(self.id())).unwrap();
+        h5call!(H5PTclose(self.checked_id())).unwrap();
     }
 }
 
 /// The incomplete builder of [`PacketTable`].
 /// You need at least set the chunk or the plist with valid chunk.
-/// If both are set, the chunk value will override the plist chunk value.
+/// If both are set, the chunk value should equal the plist chunk value.
+///
+/// Cloning the builder copies the plist, instead of sharing it.
+#[derive(Clone)]
 pub struct PacketTableBuilder {
     loc: Group,
     chunk: Option<usize>,
     plist: Option<DatasetCreate>,
+    fill: Option<(TypeDescriptor, Box<[MaybeUninit<u8>]>)>,
+    filters: Vec<FilterRequest>,
+    shuffle: bool,
+    fletcher32: bool,
+    overwrite: bool,
+    max_chunk: u64,
+    chunk_auto: bool,
+    chunk_cache: Option<(usize, usize, f64)>,
+    create_intermediate: bool,
+    attrs: Vec<(String, AttrValue)>,
+    track_times: Option<bool>,
+    attr_order: Option<c_uint>,
+    transform: Option<TableTransform>,
+}
+
+/// The default cap of the chunk, in packets.
+/// HDF5 limits a chunk dimension to 32 bits.
+pub const DEFAULT_MAX_CHUNK: u64 = u32::MAX as u64;
+
+/// The target size of a chunk in bytes chosen by [`PacketTableBuilder::chunk_auto`].
+pub const AUTO_CHUNK_BYTES: usize = 1 << 20;
+
+/// The range of the chunk in packets chosen by [`PacketTableBuilder::chunk_auto`].
+pub const AUTO_CHUNK_RANGE: RangeInclusive<usize> = 16..=65536;
+
+/// Get the chunk in packets targeting [`AUTO_CHUNK_BYTES`] per chunk
+/// for packets of `packet_size` bytes, clamped to [`AUTO_CHUNK_RANGE`].
+///
+/// For example, it is 65536 for `i32`, 2048 for a 512-byte compound,
+/// and 16 for packets larger than 64 KiB.
+pub fn auto_chunk(packet_size: usize) -> usize {
+    (AUTO_CHUNK_BYTES / packet_size.max(1))
+        .clamp(*AUTO_CHUNK_RANGE.start(), *AUTO_CHUNK_RANGE.end())
 }
 
 impl PacketTableBuilder {
@@ -285,6 +1435,19 @@ impl PacketTableBuilder {
             loc: loc.clone(),
             chunk: None,
             plist: None,
+            fill: None,
+            filters: vec![],
+            shuffle: false,
+            fletcher32: false,
+            overwrite: false,
+            max_chunk: DEFAULT_MAX_CHUNK,
+            chunk_auto: false,
+            chunk_cache: None,
+            create_intermediate: false,
+            attrs: vec![],
+            track_times: None,
+            attr_order: None,
+            transform: None,
         }
     }
 
@@ -295,25 +1458,417 @@ impl PacketTableBuilder {
     }
 
     /// Set the chunk size of the packet table.
+    ///
+    /// Creating fails with [`Error::Builder`] if the chunk is zero,
+    /// exceeds the [max chunk](Self::max_chunk), or differs from the plist chunk.
     pub fn chunk(mut self, chunk: impl Dimension) -> Self {
         self.chunk = Some(chunk.size());
         self
     }
 
+    /// Set the cap of the chunk in packets. The default is [`DEFAULT_MAX_CHUNK`].
+    pub fn max_chunk(mut self, max_chunk: u64) -> Self {
+        self.max_chunk = max_chunk;
+        self
+    }
+
+    /// Choose the chunk from the datatype size when creating, see [`auto_chunk`].
+    ///
+    /// It applies only if neither the chunk nor the plist chunk is set.
+    pub fn chunk_auto(mut self) -> Self {
+        self.chunk_auto = true;
+        self
+    }
+
+    /// Set the chunk cache of the dataset: the number of hash slots,
+    /// the size in bytes, and the preemption policy between 0 and 1.
+    ///
+    /// `H5PTcreate` doesn't take a dataset access plist,
+    /// so the table is reopened with the cache after being created.
+    pub fn chunk_cache(mut self, nslots: usize, nbytes: usize, w0: f64) -> Self {
+        self.chunk_cache = Some((nslots, nbytes, w0));
+        self
+    }
+
+    /// Set whether to create the missing intermediate groups
+    /// when the table name is a path, e.g., `runs/2024/photons`.
+    /// The default is `false`, so that creating fails if the groups don't exist.
+    ///
+    /// It applies to all the create methods of [`PacketTableBuilderTyped`].
+    /// See [`PacketTableBuilderTyped::create_path`] for the handling of the path.
+    pub fn create_intermediate(mut self, create: bool) -> Self {
+        self.create_intermediate = create;
+        self
+    }
+
+    /// Add a scalar attribute written onto the dataset when creating,
+    /// e.g., a number, a small array or a fixed-length string.
+    /// The type shouldn't contain pointers, see [`Self::attr_str`] for strings.
+    ///
+    /// If writing any attribute fails, the created dataset is unlinked.
+    pub fn attr<T: H5Type>(mut self, name: impl Into<String>, value: T) -> Self {
+        self.attrs.push((name.into(), AttrValue::new(value)));
+        self
+    }
+
+    /// Add a variable length string attribute written onto the dataset when creating.
+    ///
+    /// See [`Self::attr`].
+    pub fn attr_str(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attrs.push((name.into(), AttrValue::Str(value.into())));
+        self
+    }
+
+    /// Wrap the created table, and write the attributes.
+    /// The dataset is unlinked if reopening it or writing an attribute fails.
+    fn finish_created(&self, table: hid_t, table_name: &str, name: &CStr) -> Result<PacketTable> {
+        let res = self.open_created(table, name).and_then(|table| {
+            let dset = table.dataset()?;
+            self.attrs
+                .iter()
+                .try_for_each(|(name, value)| value.write(&dset, name))?;
+            Ok(table)
+        });
+        if res.is_err() {
+            // The original error is more relevant than a failed cleanup.
+            self.loc.unlink(table_name).ok();
+        }
+        res
+    }
+
+    /// Wrap the created table, and reopen it with the chunk cache if set.
+    fn open_created(&self, table: hid_t, name: &CStr) -> Result<PacketTable> {
+        let table = PacketTable::from_id(table)?;
+        let Some((nslots, nbytes, w0)) = self.chunk_cache else {
+            return Ok(table);
+        };
+        drop(table);
+        let dapl = DatasetAccess::build()
+            .chunk_cache(nslots, nbytes, w0)
+            .finish()?;
+        // The cache is set by the first open, and shared by the later ones.
+        let dset = h5try!(H5Dopen2(self.loc.id(), name.as_ptr(), dapl.id()));
+        let _dset = unsafe { from_id::<Dataset>(dset) }?;
+        let table = h5try!(H5PTopen(self.loc.id(), name.as_ptr()));
+        PacketTable::from_id(table)
+    }
+
+    /// Get the chunk to create with, from the chunk, the plist,
+    /// or the packet size if [chunking automatically](Self::chunk_auto).
+    fn effective_chunk(&self, packet_size: usize) -> Result<usize> {
+        let plist_chunk = match self.plist.as_ref().and_then(|plist| plist.chunk()) {
+            Some(dims) if dims.len() != 1 => {
+                return Err(BuilderError::InvalidPlistChunk { ndim: dims.len() }.into())
+            }
+            Some(dims) => Some(dims[0]),
+            None => None,
+        };
+        let chunk = match (self.chunk, plist_chunk) {
+            (Some(chunk), Some(plist)) if chunk != plist => {
+                return Err(BuilderError::PlistChunkMismatch {
+                    chunk: chunk as u64,
+                    plist: plist as u64,
+                }
+                .into())
+            }
+            (Some(chunk), _) | (None, Some(chunk)) => chunk,
+            (None, None) if self.chunk_auto => auto_chunk(packet_size),
+            (None, None) => return Err(BuilderError::MissingChunk.into()),
+        };
+        if chunk == 0 {
+            return Err(BuilderError::ZeroChunk.into());
+        }
+        if chunk as u64 > self.max_chunk {
+            return Err(BuilderError::ChunkTooLarge {
+                chunk: chunk as u64,
+                max: self.max_chunk,
+            }
+            .into());
+        }
+        Ok(chunk)
+    }
+
+    /// Compress the packet table with the zstd filter plugin.
+    ///
+    /// Creating fails with [`Error::FilterUnavailable`] if the plugin is not available,
+    /// see [`zstd_filter_available`].
+    pub fn compression_zstd(mut self, level: u32) -> Self {
+        self.filters
+            .push(FilterRequest::new(ZSTD_FILTER_ID, [level]));
+        self
+    }
+
+    /// Compress the packet table with deflate.
+    ///
+    /// The filter is merged into the plist or the chunk when creating.
+    /// Creating fails if the level is greater than 9.
+    pub fn deflate(mut self, level: u8) -> Self {
+        self.filters
+            .push(FilterRequest::new(H5Z_FILTER_DEFLATE, [level as c_uint]));
+        self
+    }
+
+    /// Compress the packet table with the blosc filter bundled with `hdf5`.
+    ///
+    /// The filter is registered when creating. Creating fails if the level is greater than 9.
+    #[cfg(feature = "blosc")]
+    pub fn blosc(mut self, complib: Blosc, clevel: u8, shuffle: impl Into<BloscShuffle>) -> Self {
+        let shuffle: BloscShuffle = shuffle.into();
+        // The first 4 values are filled by the filter when creating.
+        let cd_values = [0, 0, 0, 0, clevel, shuffle as u8, complib as u8].map(c_uint::from);
+        self.filters
+            .push(FilterRequest::new(BLOSC_FILTER_ID, cd_values));
+        self
+    }
+
+    /// Compress the packet table with the lzf filter bundled with `hdf5`.
+    ///
+    /// The filter is registered when creating.
+    #[cfg(feature = "lzf")]
+    pub fn lzf(mut self) -> Self {
+        self.filters.push(FilterRequest::new(LZF_FILTER_ID, []));
+        self
+    }
+
+    /// Set whether to shuffle the bytes before the compression filters.
+    ///
+    /// With an explicit plist that already has filters, the pipeline is rebuilt
+    /// so that shuffle still comes first.
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Set whether to checksum the chunks with fletcher32, after the other filters.
+    pub fn fletcher32(mut self, fletcher32: bool) -> Self {
+        self.fletcher32 = fletcher32;
+        self
+    }
+
+    /// Set whether to replace the existing dataset of the same name when creating.
+    ///
+    /// If not set, creating fails with [`Error::AlreadyExists`].
+    /// The objects other than datasets, e.g., groups, are never replaced.
+    /// The new table is created under a temporary name and moved over the old one
+    /// once it is set up, while the old one is moved to a backup name, and restored
+    /// if the move fails. So the old one is kept if creating fails. A crash while
+    /// replacing may leave the links `{name}.~N` in the group.
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Set the fill value of the dataset, which the unwritten packets read as.
+    /// The type should match the datatype, and shouldn't contain pointers.
+    ///
+    /// The fill value replaces the one of the plist, if any.
+    pub fn fill_value<T: H5Type>(mut self, val: T) -> Self {
+        self.fill = Some(raw::value_bytes(&val));
+        self
+    }
+
+    /// Set the fill value of the dataset with the provided [`TypeDescriptor`] and bytes.
+    ///
+    /// Creating fails with [`Error::PacketSize`] if the length of the bytes
+    /// differs from the size of the descriptor.
+    /// See [`PacketTableBuilder::fill_value`].
+    pub fn fill_value_as(mut self, dtype: &TypeDescriptor, bytes: &[u8]) -> Self {
+        let bytes = bytes.iter().map(|&b| MaybeUninit::new(b)).collect();
+        self.fill = Some((dtype.clone(), bytes));
+        self
+    }
+
+    /// Set whether to record the access, modification, change and birth times of the dataset.
+    /// HDF5 records them by default, so the files are not byte-reproducible.
+    ///
+    /// The option replaces the one of the plist, if any.
+    pub fn track_times(mut self, track_times: bool) -> Self {
+        self.track_times = Some(track_times);
+        self
+    }
+
+    /// Set whether to track the creation order of the attributes,
+    /// and whether to index the attributes by it, which requires tracking.
+    ///
+    /// The option replaces the one of the plist, if any.
+    pub fn attr_creation_order(mut self, tracked: bool, indexed: bool) -> Self {
+        let mut flags = 0;
+        if tracked || indexed {
+            flags |= H5P_CRT_ORDER_TRACKED;
+        }
+        if indexed {
+            flags |= H5P_CRT_ORDER_INDEXED;
+        }
+        self.attr_order = Some(flags);
+        self
+    }
+
+    /// Whether any option needs to be merged into the plist.
+    fn patches_plist(&self, filters: &[FilterRequest]) -> bool {
+        self.fill.is_some()
+            || !filters.is_empty()
+            || self.track_times.is_some()
+            || self.attr_order.is_some()
+    }
+
+    /// Create the table by `create`.
+    ///
+    /// A new ASCII name is created directly. Otherwise, the table is created with
+    /// a [temporary name](Self::temp_name), and moved to the table name
+    /// by [`replace_link`] once it is set up.
+    /// `H5PTcreate` doesn't take a link creation plist, so the link of a non-ASCII name
+    /// is created by the move with [`LinkOptions`], in UTF-8.
+    /// If overwriting, the old table is kept if creating fails.
+    fn create_linked(
+        &self,
+        table_name: &str,
+        create: impl FnOnce(&CStr) -> Result<hid_t>,
+    ) -> Result<PacketTable> {
+        let replace = self.prepare_link(table_name)?;
+        // The default link creation plist already encodes an ASCII name as is.
+        let direct = !replace && table_name.is_ascii();
+        let create_name = if direct {
+            table_name.to_string()
+        } else {
+            self.temp_name(table_name)
+        };
+        let name = CString::new(create_name.as_str())?;
+        let table = create(&name)?;
+        let mut table = self.finish_created(table, &create_name, &name)?;
+        if !direct {
+            let backup = replace.then(|| self.temp_name(table_name));
+            replace_link(&self.loc, &create_name, table_name, backup.as_deref())?;
+        }
+        table.path = table.dataset()?.name();
+        table.transform = self.transform.clone();
+        Ok(table)
+    }
+
+    /// Check the existing link of the name, and get whether an old table is replaced.
+    fn prepare_link(&self, table_name: &str) -> Result<bool> {
+        if !self.loc.link_exists(table_name) {
+            return Ok(false);
+        }
+        if !self.overwrite || self.loc.loc_type_by_name(table_name)? != LocationType::Dataset {
+            return Err(Error::AlreadyExists {
+                name: table_name.to_string(),
+            });
+        }
+        Ok(true)
+    }
+
+    /// Get an unused name next to the table name, like `data.~0`.
+    fn temp_name(&self, table_name: &str) -> String {
+        (0..)
+            .map(|i| format!("{table_name}.~{i}"))
+            .find(|name| !self.loc.link_exists(name))
+            .unwrap()
+    }
+
+    /// Move into the parent group of the path, creating the missing groups,
+    /// and get the table name. See [`PacketTableBuilderTyped::create_path`].
+    fn enter_parent<'a>(&mut self, path: &'a str) -> Result<&'a str> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((parent, name)) => (parent, name),
+            None => ("", path),
+        };
+        if name.is_empty() {
+            return Err(Error::Other(format!(
+                "The path {path:?} has no table name."
+            )));
+        }
+        self.loc = self.loc.ensure_group(parent, &LinkOptions::new())?;
+        Ok(name)
+    }
+
+    /// Get the table name to create with, moving into the parent group of a path
+    /// if [creating the intermediate groups](Self::create_intermediate).
+    fn resolve_name<'a>(&mut self, table_name: &'a str) -> Result<&'a str> {
+        if self.create_intermediate && table_name.contains('/') {
+            self.enter_parent(table_name)
+        } else {
+            Ok(table_name)
+        }
+    }
+
+    fn filter_pipeline(&self) -> Vec<FilterRequest> {
+        let mut filters = vec![];
+        if self.shuffle {
+            filters.push(FilterRequest::new(H5Z_FILTER_SHUFFLE, []));
+        }
+        filters.extend(self.filters.iter().cloned());
+        if self.fletcher32 {
+            filters.push(FilterRequest::new(H5Z_FILTER_FLETCHER32, []));
+        }
+        filters
+    }
+
     /// Set the [`Datatype`] of the packet table.
     pub fn dtype<T: H5Type>(self) -> PacketTableBuilderTyped {
+        self.dtype_as(T::type_descriptor())
+    }
+
+    /// Set the [`Datatype`] of the packet table with provided [`TypeDescriptor`].
+    pub fn dtype_as(self, dtype: TypeDescriptor) -> PacketTableBuilderTyped {
         PacketTableBuilderTyped {
             builder: self,
-            dtype: T::type_descriptor(),
+            dtype: BuilderDtype::Descriptor(dtype),
+            commit: None,
         }
     }
 
-    /// Set the [`Datatype`] of the packet table with provided [`TypeDescriptor`].
-    pub fn dtype_as(self, dtype: TypeDescriptor) -> PacketTableBuilderTyped {
+    /// Set the [`Datatype`] of the packet table directly.
+    ///
+    /// The datatype is used as is, so that a committed datatype stays committed.
+    pub fn dtype_raw(self, dtype: Datatype) -> PacketTableBuilderTyped {
         PacketTableBuilderTyped {
             builder: self,
-            dtype,
+            dtype: BuilderDtype::Raw(dtype),
+            commit: None,
+        }
+    }
+
+    /// Set the [`Datatype`] of the packet table to exactly the one of `table`,
+    /// without a round trip through [`TypeDescriptor`], which may alter
+    /// details like the string padding.
+    ///
+    /// Unlike [`Self::dtype_like_table`], the other settings are not inherited.
+    pub fn dtype_like(self, table: &PacketTable) -> Result<PacketTableBuilderTyped> {
+        Ok(self.dtype_raw(table.dtype()?))
+    }
+
+    /// Set the [`Datatype`] of the packet table to the one of `src`,
+    /// and inherit the chunk, filters and fill value from its creation plist.
+    ///
+    /// The plist, chunk and filters set before calling this override the inherited ones:
+    /// an explicit plist is kept as is, an explicit chunk replaces the inherited chunk,
+    /// and any requested filter replaces all inherited filters.
+    pub fn dtype_like_table(mut self, src: &PacketTable) -> Result<PacketTableBuilderTyped> {
+        if self.plist.is_none() {
+            let plist = src.create_plist()?;
+            if let Some(chunk) = self.chunk {
+                let dims = [chunk as hsize_t];
+                h5try!(H5Pset_chunk(plist.id(), 1, dims.as_ptr()));
+            }
+            if !self.filter_pipeline().is_empty() {
+                h5try!(H5Premove_filter(plist.id(), H5Z_FILTER_ALL));
+            }
+            self.plist = Some(plist);
         }
+        Ok(self.dtype_raw(src.dtype()?))
+    }
+
+    /// Set the [`Datatype`] of the packet table to variable length arrays of `T`.
+    /// The packets are [`VarLenArray<T>`](hdf5::types::VarLenArray).
+    pub fn dtype_varlen<T: H5Type>(self) -> PacketTableBuilderTyped {
+        self.dtype_varlen_as(T::type_descriptor())
+    }
+
+    /// Set the [`Datatype`] of the packet table to variable length arrays
+    /// of the provided element [`TypeDescriptor`].
+    pub fn dtype_varlen_as(self, elem: TypeDescriptor) -> PacketTableBuilderTyped {
+        self.dtype_as(TypeDescriptor::VarLenArray(Box::new(elem)))
     }
 
     /// Set the [`Datatype`] of the packet table with raw pointee metadata.
@@ -332,41 +1887,112 @@ impl PacketTableBuilder {
         ptr: *const T,
     ) -> PacketTableBuilderTyped {
         let dtype = unsafe { (*ptr).type_descriptor() };
-        PacketTableBuilderTyped {
-            builder: self,
-            dtype,
-        }
+        self.dtype_as(dtype)
     }
 
-    pub(crate) fn create(self, table_name: &str, dtype: &Datatype) -> Result<PacketTable> {
-        if let Some(plist) = &self.plist {
-            if plist.chunk().is_none() {
-                return Err("Invalid chunk.".into());
+    pub(crate) fn create(mut self, table_name: &str, dtype: &Datatype) -> Result<PacketTable> {
+        let chunk = self.effective_chunk(dtype.size())?;
+        let filters = self.filter_pipeline();
+        let mut plist = self.plist.take();
+        if self.patches_plist(&filters) {
+            let patch_plist = match plist.take() {
+                Some(plist) => plist,
+                None => DatasetCreate::build().chunk(chunk).finish()?,
+            };
+            if let Some((fill_dtype, fill)) = &self.fill {
+                if raw::contains_pointers(fill_dtype) {
+                    return Err(Error::Other(
+                        "Fill values containing pointers are not supported.".to_string(),
+                    ));
+                }
+                if fill.len() != fill_dtype.size() {
+                    return Err(Error::PacketSize {
+                        expected: fill_dtype.size(),
+                        actual: fill.len(),
+                    });
+                }
+                if Datatype::from_descriptor(fill_dtype)? != *dtype {
+                    return Err(Error::FillValueMismatch {
+                        fill: Box::new(fill_dtype.clone()),
+                    });
+                }
+                h5try!(H5Pset_fill_value(
+                    patch_plist.id(),
+                    dtype.id(),
+                    fill.as_ptr() as *const _
+                ));
+            }
+            FilterRequest::extend_pipeline(patch_plist.id(), &filters)?;
+            if let Some(track_times) = self.track_times {
+                h5try!(H5Pset_obj_track_times(patch_plist.id(), track_times as _));
             }
-        } else if self.chunk.is_none() {
-            return Err("Either plist or chunk need to be set.".into());
+            if let Some(flags) = self.attr_order {
+                h5try!(H5Pset_attr_creation_order(patch_plist.id(), flags));
+            }
+            plist = Some(patch_plist);
         }
-        let table_name = CString::new(table_name).map_err(|e| e.to_string())?;
-        let plist = self
-            .plist
+        let plist = plist
             .as_ref()
             .map(|plist| plist.id())
             .unwrap_or(H5P_DEFAULT);
-        let table = h5try!(H5PTcreate(
-            self.loc.id(),
-            table_name.as_ptr(),
-            dtype.id(),
-            self.chunk.unwrap_or_default() as _,
-            plist
-        ));
-        Ok(PacketTable::from_id(table))
+        for (name, value) in &self.attrs {
+            value.check(name)?;
+        }
+        self.create_linked(table_name, |name| {
+            Ok(h5try!(H5PTcreate(
+                self.loc.id(),
+                name.as_ptr(),
+                dtype.id(),
+                chunk as _,
+                plist
+            )))
+        })
+    }
+
+    #[allow(deprecated)]
+    pub(crate) fn create_fl(
+        self,
+        table_name: &str,
+        dtype: &Datatype,
+        compression: i32,
+    ) -> Result<PacketTable> {
+        if self.plist.is_some() || self.patches_plist(&self.filter_pipeline()) {
+            return Err(
+                "The plist, fill value, filters and object options are not supported by H5PTcreate_fl."
+                    .into(),
+            );
+        }
+        let chunk = self.effective_chunk(dtype.size())?;
+        for (name, value) in &self.attrs {
+            value.check(name)?;
+        }
+        self.create_linked(table_name, |name| {
+            Ok(h5try!(hdf5_hl_sys::h5pt::H5PTcreate_fl(
+                self.loc.id(),
+                name.as_ptr(),
+                dtype.id(),
+                chunk as _,
+                compression
+            )))
+        })
     }
 }
 
 /// A complete builder of [`PacketTable`].
+///
+/// It could be cloned as a template of many tables.
+#[derive(Clone)]
 pub struct PacketTableBuilderTyped {
     builder: PacketTableBuilder,
-    dtype: TypeDescriptor,
+    dtype: BuilderDtype,
+    commit: Option<String>,
+}
+
+/// The datatype set on [`PacketTableBuilderTyped`].
+#[derive(Clone)]
+enum BuilderDtype {
+    Descriptor(TypeDescriptor),
+    Raw(Datatype),
 }
 
 impl PacketTableBuilderTyped {
@@ -382,67 +2008,396 @@ impl PacketTableBuilderTyped {
         self
     }
 
-    /// Create the [`PacketTable`].
-    pub fn create(self, table_name: impl AsRef<str>) -> Result<PacketTable> {
-        let dtype = Datatype::from_descriptor(&self.dtype)?;
-        self.builder.create(table_name.as_ref(), &dtype)
+    /// Choose the chunk from the datatype size when creating.
+    ///
+    /// See [`PacketTableBuilder::chunk_auto`].
+    pub fn chunk_auto(mut self) -> Self {
+        self.builder = self.builder.chunk_auto();
+        self
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::*;
-    use hdf5::{types::VarLenArray, H5Type};
-    use tempfile::NamedTempFile;
+    /// Add a scalar attribute written onto the dataset when creating.
+    ///
+    /// See [`PacketTableBuilder::attr`].
+    pub fn attr<T: H5Type>(mut self, name: impl Into<String>, value: T) -> Self {
+        self.builder = self.builder.attr(name, value);
+        self
+    }
 
-    #[test]
-    fn basic() {
-        let file = NamedTempFile::new().unwrap();
+    /// Add a variable length string attribute written onto the dataset when creating.
+    ///
+    /// See [`PacketTableBuilder::attr_str`].
+    pub fn attr_str(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.builder = self.builder.attr_str(name, value);
+        self
+    }
 
-        let vec = vec![1, 1, 4, 5, 1, 4];
+    /// Set the chunk cache of the dataset.
+    ///
+    /// See [`PacketTableBuilder::chunk_cache`].
+    pub fn chunk_cache(mut self, nslots: usize, nbytes: usize, w0: f64) -> Self {
+        self.builder = self.builder.chunk_cache(nslots, nbytes, w0);
+        self
+    }
 
-        let data = hdf5::File::create(file.path()).unwrap();
-        {
-            let mut table = PacketTable::builder(&data)
-                .chunk(16)
-                .dtype::<i32>()
-                .create("data")
-                .unwrap();
-            assert_eq!(
-                table.dtype().unwrap().to_descriptor().unwrap(),
-                i32::type_descriptor()
-            );
-            table.append(&vec).unwrap();
-        }
-        {
-            let mut table = PacketTable::open(&data, "data").unwrap();
-            {
-                let dataset = table.dataset().unwrap();
-                let read_data = dataset.read_1d::<i32>().unwrap();
-                assert_eq!(read_data.as_slice().unwrap(), &[1, 1, 4, 5, 1, 4]);
-            }
-            {
-                let read_data = table.read::<i32>(0, 6).unwrap();
-                assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
-            }
-            {
-                let read_data = table
-                    .iter::<i32>()
-                    .map(|item| item.unwrap())
-                    .collect::<Vec<_>>();
-                assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
-            }
-            {
-                table.reset_index().unwrap();
-                assert_eq!(table.index().unwrap(), 0);
-                assert_eq!(table.read_next::<i32>(6).unwrap(), &[1, 1, 4, 5, 1, 4]);
-                assert_eq!(table.index().unwrap(), 6);
-            }
-        }
+    /// Set the cap of the chunk in packets.
+    ///
+    /// See [`PacketTableBuilder::max_chunk`].
+    pub fn max_chunk(mut self, max_chunk: u64) -> Self {
+        self.builder = self.builder.max_chunk(max_chunk);
+        self
     }
 
-    #[test]
-    fn varlen() {
+    /// Compress the packet table with the zstd filter plugin.
+    ///
+    /// See [`PacketTableBuilder::compression_zstd`].
+    pub fn compression_zstd(mut self, level: u32) -> Self {
+        self.builder = self.builder.compression_zstd(level);
+        self
+    }
+
+    /// Compress the packet table with deflate.
+    ///
+    /// See [`PacketTableBuilder::deflate`].
+    pub fn deflate(mut self, level: u8) -> Self {
+        self.builder = self.builder.deflate(level);
+        self
+    }
+
+    /// Compress the packet table with blosc.
+    ///
+    /// See [`PacketTableBuilder::blosc`].
+    #[cfg(feature = "blosc")]
+    pub fn blosc(mut self, complib: Blosc, clevel: u8, shuffle: impl Into<BloscShuffle>) -> Self {
+        self.builder = self.builder.blosc(complib, clevel, shuffle);
+        self
+    }
+
+    /// Compress the packet table with lzf.
+    ///
+    /// See [`PacketTableBuilder::lzf`].
+    #[cfg(feature = "lzf")]
+    pub fn lzf(mut self) -> Self {
+        self.builder = self.builder.lzf();
+        self
+    }
+
+    /// Set whether to shuffle the bytes before the compression filters.
+    ///
+    /// See [`PacketTableBuilder::shuffle`].
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.builder = self.builder.shuffle(shuffle);
+        self
+    }
+
+    /// Set whether to checksum the chunks with fletcher32, after the other filters.
+    ///
+    /// See [`PacketTableBuilder::fletcher32`].
+    pub fn fletcher32(mut self, fletcher32: bool) -> Self {
+        self.builder = self.builder.fletcher32(fletcher32);
+        self
+    }
+
+    /// Set whether to replace the existing dataset of the same name when creating.
+    ///
+    /// See [`PacketTableBuilder::overwrite`].
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.builder = self.builder.overwrite(overwrite);
+        self
+    }
+
+    /// Pass every appended record through the [`RecordTransform`].
+    ///
+    /// The table is created with the output descriptor, and the records of the current
+    /// datatype are transformed before appending, including those from
+    /// [`PacketTableBufWriter`] and [`PacketTable::append_raw_from`].
+    /// The output descriptor is validated here.
+    pub fn with_transform(mut self, transform: Box<dyn RecordTransform>) -> Result<Self> {
+        let transform = TableTransform::new(transform.into(), self.descriptor()?)?;
+        self.dtype = BuilderDtype::Descriptor(transform.output.clone());
+        self.builder.transform = Some(transform);
+        Ok(self)
+    }
+
+    /// Set whether to record the times of the dataset.
+    ///
+    /// See [`PacketTableBuilder::track_times`].
+    pub fn track_times(mut self, track_times: bool) -> Self {
+        self.builder = self.builder.track_times(track_times);
+        self
+    }
+
+    /// Set whether to track and index the creation order of the attributes.
+    ///
+    /// See [`PacketTableBuilder::attr_creation_order`].
+    pub fn attr_creation_order(mut self, tracked: bool, indexed: bool) -> Self {
+        self.builder = self.builder.attr_creation_order(tracked, indexed);
+        self
+    }
+
+    /// Set the fill value of the dataset.
+    ///
+    /// See [`PacketTableBuilder::fill_value`].
+    pub fn fill_value<T: H5Type>(mut self, val: T) -> Self {
+        self.builder = self.builder.fill_value(val);
+        self
+    }
+
+    /// Set the fill value of the dataset with the provided [`TypeDescriptor`] and bytes.
+    ///
+    /// See [`PacketTableBuilder::fill_value_as`].
+    pub fn fill_value_as(mut self, dtype: &TypeDescriptor, bytes: &[u8]) -> Self {
+        self.builder = self.builder.fill_value_as(dtype, bytes);
+        self
+    }
+
+    /// Check the member names and create the [`Datatype`].
+    fn datatype(&self) -> Result<Datatype> {
+        match &self.dtype {
+            BuilderDtype::Descriptor(dtype) => {
+                hdf5_dst::check_descriptor(dtype)?;
+                Ok(Datatype::from_descriptor(dtype)?)
+            }
+            BuilderDtype::Raw(dtype) => Ok(dtype.clone()),
+        }
+    }
+
+    /// Get the [`TypeDescriptor`] of the datatype.
+    fn descriptor(&self) -> Result<TypeDescriptor> {
+        match &self.dtype {
+            BuilderDtype::Descriptor(dtype) => Ok(dtype.clone()),
+            BuilderDtype::Raw(dtype) => Ok(dtype.to_descriptor()?),
+        }
+    }
+
+    /// Commit the datatype under the name in the location group when creating,
+    /// and create the tables with the committed datatype.
+    ///
+    /// If the name exists, the committed datatype is shared,
+    /// and it should equal the datatype, otherwise [`Error::SchemaMismatch`] is returned.
+    pub fn commit_type(mut self, name: &str) -> Self {
+        self.commit = Some(name.to_string());
+        self
+    }
+
+    /// Get the datatype to create the tables with, committing it if requested.
+    fn create_datatype(&self) -> Result<Datatype> {
+        let dtype = self.datatype()?;
+        let Some(name) = &self.commit else {
+            return Ok(dtype);
+        };
+        let loc = &self.builder.loc;
+        let c_name = CString::new(name.as_str())?;
+        if loc.link_exists(name) {
+            let id = h5try!(H5Topen2(loc.id(), c_name.as_ptr(), H5P_DEFAULT));
+            let committed = unsafe { from_id::<Datatype>(id) }?;
+            if committed != dtype {
+                return Err(Error::SchemaMismatch {
+                    expected: Box::new(self.descriptor()?),
+                    actual: Box::new(committed.to_descriptor()?),
+                });
+            }
+            return Ok(committed);
+        }
+        let lcpl = LinkOptions::new().lcpl(name)?;
+        h5try!(H5Tcommit2(
+            loc.id(),
+            c_name.as_ptr(),
+            dtype.id(),
+            lcpl.id(),
+            H5P_DEFAULT,
+            H5P_DEFAULT
+        ));
+        Ok(dtype)
+    }
+
+    /// Set whether to create the missing intermediate groups.
+    ///
+    /// See [`PacketTableBuilder::create_intermediate`].
+    pub fn create_intermediate(mut self, create: bool) -> Self {
+        self.builder = self.builder.create_intermediate(create);
+        self
+    }
+
+    /// Create the [`PacketTable`].
+    ///
+    /// The link is created with the default [`LinkOptions`],
+    /// so a non-ASCII name is encoded in UTF-8.
+    pub fn create(mut self, table_name: impl AsRef<str>) -> Result<PacketTable> {
+        let table_name = self.builder.resolve_name(table_name.as_ref())?;
+        let dtype = self.create_datatype()?;
+        self.builder.create(table_name, &dtype)
+    }
+
+    /// Create the packet table at the path, creating the missing intermediate groups.
+    /// The existing groups are reused.
+    ///
+    /// A leading `/` starts from the root group of the file,
+    /// and a trailing `/` is an error, because the table name is empty.
+    pub fn create_path(mut self, path: &str) -> Result<PacketTable> {
+        let name = self.builder.enter_parent(path)?;
+        self.create(name)
+    }
+
+    /// Open the packet table if it exists, or create it otherwise.
+    ///
+    /// The datatype of the existing table should equal the configured one,
+    /// otherwise [`Error::SchemaMismatch`] is returned.
+    /// The other settings only apply when creating.
+    pub fn create_or_open(mut self, table_name: impl AsRef<str>) -> Result<PacketTable> {
+        let table_name = self.builder.resolve_name(table_name.as_ref())?;
+        if !self.builder.loc.link_exists(table_name) {
+            return self.create(table_name);
+        }
+        let dtype = self.datatype()?;
+        let mut table = PacketTable::open(&self.builder.loc, table_name)?;
+        let actual = table.dtype()?;
+        if actual != dtype {
+            return Err(Error::SchemaMismatch {
+                expected: Box::new(self.descriptor()?),
+                actual: Box::new(actual.to_descriptor()?),
+            });
+        }
+        table.transform = self.builder.transform;
+        Ok(table)
+    }
+
+    /// Create many [`PacketTable`]s with the same settings.
+    ///
+    /// If one fails, [`Error::PartialCreate`] reports the failed name. The created tables
+    /// are removed if `rollback` is set, otherwise they are kept in the file.
+    pub fn create_many(
+        self,
+        names: impl IntoIterator<Item = impl AsRef<str>>,
+        rollback: bool,
+    ) -> Result<Vec<PacketTable>> {
+        let dtype = self.create_datatype()?;
+        let mut tables = vec![];
+        let mut created = vec![];
+        for name in names {
+            let name = name.as_ref();
+            let mut builder = self.builder.clone();
+            let res = builder
+                .resolve_name(name)
+                .and_then(|table_name| builder.create(table_name, &dtype));
+            match res {
+                Ok(table) => {
+                    tables.push(table);
+                    created.push(name.to_string());
+                }
+                Err(error) => {
+                    drop(tables);
+                    let rolled_back = rollback
+                        && created
+                            .iter()
+                            .try_for_each(|name| self.builder.loc.unlink(name))
+                            .is_ok();
+                    return Err(Error::PartialCreate {
+                        name: name.to_string(),
+                        created: created.len(),
+                        rolled_back,
+                        error: Box::new(error),
+                    });
+                }
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Create the [`PacketTable`] with the legacy `H5PTcreate_fl`.
+    /// The chunk should be set, and the plist is not supported.
+    /// Like [`Self::create`], it needs the datatype, so it is not on [`PacketTableBuilder`].
+    ///
+    /// The `compression` is the deflate level from 0 to 9, or -1 for no compression.
+    #[deprecated(note = "H5PTcreate_fl is deprecated in HDF5 1.10.0, use create")]
+    pub fn create_fl(
+        mut self,
+        table_name: impl AsRef<str>,
+        compression: i32,
+    ) -> Result<PacketTable> {
+        let table_name = self.builder.resolve_name(table_name.as_ref())?;
+        let dtype = self.create_datatype()?;
+        self.builder.create_fl(table_name, &dtype, compression)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use dst_container::*;
+    use hdf5::{
+        filters::Filter,
+        h5call,
+        plist::DatasetCreate,
+        types::{FixedAscii, FloatSize, IntSize, TypeDescriptor, VarLenArray, VarLenUnicode},
+        Datatype, Group, H5Type,
+    };
+    use hdf5_sys::{
+        h5p::{H5Pset_filter, H5P_CRT_ORDER_INDEXED, H5P_CRT_ORDER_TRACKED, H5P_DEFAULT},
+        h5t::{H5Tcommit2, H5Tcommitted},
+        h5z::{
+            H5Z_class2_t, H5Z_filter_t, H5Zregister, H5Z_CLASS_T_VERS, H5Z_FLAG_MANDATORY,
+            H5Z_FLAG_REVERSE,
+        },
+    };
+    use std::{
+        ffi::{c_uint, c_void, CString},
+        mem::MaybeUninit,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn basic() {
+        let file = NamedTempFile::new().unwrap();
+
+        let vec = vec![1, 1, 4, 5, 1, 4];
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        {
+            let mut table = PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+            assert_eq!(
+                table.dtype().unwrap().to_descriptor().unwrap(),
+                i32::type_descriptor()
+            );
+            table.append(&vec).unwrap();
+        }
+        {
+            let mut table = PacketTable::open(&data, "data").unwrap();
+            {
+                let dataset = table.dataset().unwrap();
+                let read_data = dataset.read_1d::<i32>().unwrap();
+                assert_eq!(read_data.as_slice().unwrap(), &[1, 1, 4, 5, 1, 4]);
+            }
+            {
+                let read_data = table.read::<i32>(0, 6).unwrap();
+                assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
+            }
+            {
+                let read_data = table
+                    .iter::<i32>()
+                    .map(|item| item.unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
+            }
+            {
+                table.reset_index().unwrap();
+                assert_eq!(table.index().unwrap(), 0);
+                assert_eq!(table.read_next::<i32>(6).unwrap(), &[1, 1, 4, 5, 1, 4]);
+                assert_eq!(table.index().unwrap(), 6);
+            }
+        }
+    }
+
+    #[test]
+    fn varlen() {
         let file = NamedTempFile::new().unwrap();
 
         let arr1 = VarLenArray::from_slice(&[1, 1, 4]);
@@ -472,73 +2427,3273 @@ mod test {
             assert_eq!(iter.next().unwrap().unwrap(), arr4);
         }
     }
-}
 
-#[cfg(test)]
-#[generic_tests::define(attrs(bench))]
-mod bench_chunk {
-    use crate::*;
-    use tempfile::NamedTempFile;
-    use test::Bencher;
+    #[test]
+    fn read_next_one() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+
+        table.reset_index().unwrap();
+        let mut read_data = vec![];
+        while let Some(p) = table.read_next_one::<i32>().unwrap() {
+            read_data.push(p);
+        }
+        assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
+        assert_eq!(table.index().unwrap(), 6);
+        assert_eq!(table.read_next_one::<i32>().unwrap(), None);
+    }
 
-    #[bench]
-    fn chunk_buffer<const C: usize, const B: usize>(b: &mut Bencher) {
+    #[test]
+    fn read_next_one_unsized() {
         let file = NamedTempFile::new().unwrap();
-        let file = hdf5::File::create(file.path()).unwrap();
-        let mut table = PacketTable::builder(&file)
-            .chunk(C)
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<[i32]>(2)
+            .create("data")
+            .unwrap();
+        table.append(&[[1, 1], [4, 5], [1, 4]]).unwrap();
+
+        table.reset_index().unwrap();
+        let mut buffer = FixedVec::<[i32]>::new(2);
+        while table.read_next_one_unsized(&mut buffer).unwrap() {}
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(&buffer[0], &[1, 1]);
+        assert_eq!(&buffer[1], &[4, 5]);
+        assert_eq!(&buffer[2], &[1, 4]);
+    }
+
+    #[test]
+    fn iter_unsized() {
+        type Data = UnsizedSlice<u32, u64>;
+
+        let file = NamedTempFile::new().unwrap();
+
+        let mut vec: FixedVec<Data> = FixedVec::new(3);
+        for i in 0..4 {
+            unsafe {
+                vec.push_with(|slice| {
+                    slice.header.write(i);
+                    MaybeUninit::copy_from_slice(&mut slice.slice, &[i as u64, 1, 4]);
+                })
+            };
+        }
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<Data>(3)
+            .create("data")
+            .unwrap();
+        table.append_unsized(&vec).unwrap();
+
+        let mut iter = table.iter_unsized::<Data>(3);
+        let mut count = 0;
+        while let Some(item) = iter.next() {
+            let item = item.unwrap();
+            assert_eq!(item.header, count);
+            assert_eq!(&item.slice, &[count as u64, 1, 4]);
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn for_each_packet() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
             .dtype::<i32>()
             .create("data")
             .unwrap();
-        if B == 1 {
-            b.iter(|| {
-                for i in 0..65536 {
-                    table.push(&i).unwrap();
-                }
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+
+        let mut sum = 0;
+        table
+            .for_each_packet::<i32, _>(4, |p| {
+                sum += *p;
+                Ok(())
             })
-        } else {
-            b.iter(|| {
-                let mut writer = PacketTableBufWriter::<i32>::new(&mut table, B);
-                for i in 0..65536 {
-                    writer.push(i).unwrap();
-                }
+            .unwrap();
+        assert_eq!(sum, 16);
+
+        let mut visited = 0;
+        let res = table.for_each_packet::<i32, _>(4, |p| {
+            visited += 1;
+            if *p == 5 {
+                Err(Error::Other("found 5".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(res.is_err());
+        assert_eq!(visited, 4);
+
+        assert!(matches!(
+            table.for_each_packet::<i32, _>(0, |_| Ok(())),
+            Err(Error::ZeroChunk)
+        ));
+    }
+
+    #[test]
+    fn for_each_packet_unsized() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<[i32]>(2)
+            .create("data")
+            .unwrap();
+        table.append(&[[1, 1], [4, 5], [1, 4]]).unwrap();
+
+        let mut read_data = vec![];
+        table
+            .for_each_packet_unsized::<[i32], _>(2, 2, |p| {
+                read_data.extend_from_slice(p);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
+
+        let pool = StagingPool::<[i32]>::new(1);
+        for _ in 0..2 {
+            let mut read_data = vec![];
+            table
+                .for_each_packet_unsized_pooled(&pool, 2, 2, |p| {
+                    read_data.extend_from_slice(p);
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(read_data, &[1, 1, 4, 5, 1, 4]);
+            assert_eq!(pool.len(), 1);
+        }
+    }
+
+    #[test]
+    fn len() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        assert!(table.is_empty().unwrap());
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+        assert!(!table.is_empty().unwrap());
+        assert_eq!(table.len().unwrap(), 6);
+    }
+
+    #[test]
+    fn read_next_failed() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+
+        table.reset_index().unwrap();
+        assert_eq!(table.read_next::<i32>(2).unwrap(), &[1, 1]);
+        assert!(table.read_next::<i32>(10).is_err());
+        assert_eq!(table.index().unwrap(), 2);
+        assert_eq!(table.read_next::<i32>(4).unwrap(), &[4, 5, 1, 4]);
+        assert_eq!(table.index().unwrap(), 6);
+
+        table.set_index(2).unwrap();
+        let mut buffer = FixedVec::<i32>::new(());
+        assert!(table.read_next_unsized(10, &mut buffer).is_err());
+        assert_eq!(table.index().unwrap(), 2);
+        table.read_next_unsized(4, &mut buffer).unwrap();
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer[0], 4);
+
+        // The index is checked after a successful read.
+        assert!(matches!(
+            table.check_next_index(0, 1, Ok(())),
+            Err(Error::InconsistentIndex {
+                expected: 1,
+                actual: 6
             })
+        ));
+    }
+
+    #[test]
+    fn read_next_failed_in_hdf5() {
+        static FAIL_DECODE: AtomicBool = AtomicBool::new(false);
+
+        /// Pass the data through, and fail decoding if [`FAIL_DECODE`] is set.
+        unsafe extern "C" fn faulty_filter(
+            flags: c_uint,
+            _: usize,
+            _: *const c_uint,
+            nbytes: usize,
+            _: *mut usize,
+            _: *mut *mut c_void,
+        ) -> usize {
+            if flags & H5Z_FLAG_REVERSE != 0 && FAIL_DECODE.load(Ordering::SeqCst) {
+                0
+            } else {
+                nbytes
+            }
         }
+
+        // The filter ids from 256 to 511 are reserved for testing.
+        const FAULTY_FILTER_ID: H5Z_filter_t = 305;
+        let class = H5Z_class2_t {
+            version: H5Z_CLASS_T_VERS as _,
+            id: FAULTY_FILTER_ID,
+            encoder_present: 1,
+            decoder_present: 1,
+            name: c"faulty".as_ptr(),
+            can_apply: None,
+            set_local: None,
+            filter: Some(faulty_filter),
+        };
+        h5call!(H5Zregister(&class as *const H5Z_class2_t as *const _)).unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let plist = DatasetCreate::build().chunk(4).finish().unwrap();
+        h5call!(H5Pset_filter(
+            plist.id(),
+            FAULTY_FILTER_ID,
+            H5Z_FLAG_MANDATORY,
+            0,
+            std::ptr::null()
+        ))
+        .unwrap();
+        // Without the chunk cache, every read decodes the chunks.
+        let mut table = PacketTable::builder(&data)
+            .plist(plist)
+            .chunk_cache(0, 0, 0.0)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4, 1, 9]).unwrap();
+        table.set_index(2).unwrap();
+
+        // The reads fail inside HDF5, and the index is restored.
+        FAIL_DECODE.store(true, Ordering::SeqCst);
+        assert!(matches!(table.read_next::<i32>(4), Err(Error::Hdf5(_))));
+        assert_eq!(table.index().unwrap(), 2);
+        let mut buffer = FixedVec::<i32>::new(());
+        assert!(matches!(
+            table.read_next_unsized(4, &mut buffer),
+            Err(Error::Hdf5(_))
+        ));
+        assert!(buffer.is_empty());
+        assert_eq!(table.index().unwrap(), 2);
+        assert!(matches!(table.read_next_one::<i32>(), Err(Error::Hdf5(_))));
+        assert_eq!(table.index().unwrap(), 2);
+
+        // The next read resumes at the index.
+        FAIL_DECODE.store(false, Ordering::SeqCst);
+        assert_eq!(table.read_next::<i32>(4).unwrap(), [4, 5, 1, 4]);
+        assert_eq!(table.index().unwrap(), 6);
+        assert_eq!(table.read_next_one::<i32>().unwrap(), Some(1));
+        table.read_next_unsized(1, &mut buffer).unwrap();
+        assert_eq!(buffer[0], 9);
+        assert_eq!(table.index().unwrap(), 8);
     }
 
-    #[instantiate_tests(<16, 1>)]
-    mod append_16_1 {}
+    #[test]
+    fn read_array() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
 
-    #[instantiate_tests(<16, 16>)]
-    mod append_16_16 {}
+        assert_eq!(table.read_array::<i32, 4>(1).unwrap(), [1, 4, 5, 1]);
+        assert!(table.read_array::<i32, 4>(3).is_err());
 
-    #[instantiate_tests(<16, 1024>)]
-    mod append_16_1024 {}
+        table.reset_index().unwrap();
+        assert_eq!(table.read_next_array::<i32, 4>().unwrap(), [1, 1, 4, 5]);
+        assert_eq!(table.index().unwrap(), 4);
+        assert!(table.read_next_array::<i32, 4>().is_err());
+        assert_eq!(table.index().unwrap(), 4);
+        assert_eq!(table.read_next_array::<i32, 2>().unwrap(), [1, 4]);
+    }
 
-    #[instantiate_tests(<16, 65536>)]
-    mod append_16_65536 {}
+    #[test]
+    fn copy_raw_to() {
+        let file = NamedTempFile::new().unwrap();
 
-    #[instantiate_tests(<1024, 1>)]
-    mod append_1024_1 {}
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
 
-    #[instantiate_tests(<1024, 16>)]
-    mod append_1024_16 {}
+        let mut bytes = vec![];
+        assert_eq!(table.copy_raw_to(&mut bytes, 1..6).unwrap(), 20);
+        let expected = [1i32, 4, 5, 1, 4]
+            .iter()
+            .flat_map(|i| i.to_ne_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, expected);
+        assert!(table.copy_raw_to(&mut bytes, 4..8).is_err());
+        let mut small = [0u8; 4];
+        assert!(matches!(
+            table.copy_raw_to(&mut &mut small[..], ..),
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::WriteZero
+        ));
 
-    #[instantiate_tests(<1024, 1024>)]
-    mod append_1024_1024 {}
+        let mut table = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<VarLenArray<i32>>()
+            .create("varlen")
+            .unwrap();
+        table.push(&VarLenArray::from_slice(&[1, 1, 4])).unwrap();
+        assert!(table.copy_raw_to(&mut bytes, ..).is_err());
+    }
 
-    #[instantiate_tests(<1024, 65536>)]
-    mod append_1024_65536 {}
+    #[test]
+    fn append_raw_from() {
+        let file = NamedTempFile::new().unwrap();
 
-    #[instantiate_tests(<65536, 1>)]
-    mod append_65536_1 {}
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let vec = (0..100).collect::<Vec<i32>>();
+        table.append(&vec).unwrap();
 
-    #[instantiate_tests(<65536, 16>)]
-    mod append_65536_16 {}
+        let mut bytes = vec![];
+        table.copy_raw_to(&mut bytes, ..).unwrap();
 
-    #[instantiate_tests(<65536, 1024>)]
-    mod append_65536_1024 {}
+        let mut copied = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("copied")
+            .unwrap();
+        assert_eq!(copied.append_raw_from(&mut bytes.as_slice()).unwrap(), 100);
+        assert_eq!(copied.read::<i32>(0, 100).unwrap(), vec);
 
-    #[instantiate_tests(<65536, 65536>)]
-    mod append_65536_65536 {}
+        let mut truncated = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("truncated")
+            .unwrap();
+        assert!(matches!(
+            truncated.append_raw_from(&mut &bytes[..bytes.len() - 2]),
+            Err(Error::TruncatedPacket {
+                committed: 99,
+                trailing_bytes: 2
+            })
+        ));
+        assert_eq!(truncated.num_packets().unwrap(), 99);
+
+        struct Broken;
+
+        impl std::io::Read for Broken {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("broken"))
+            }
+        }
+
+        let mut broken = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("broken")
+            .unwrap();
+        // The staging buffer holds 16 packets.
+        let mut reader = std::io::Read::chain(&bytes[..80], Broken);
+        let res = broken.append_raw_from(&mut reader);
+        let Err(Error::PartialImport { committed, source }) = res else {
+            panic!("unexpected result: {res:?}");
+        };
+        assert_eq!(committed, 16);
+        assert!(matches!(*source, Error::Io(_)));
+        assert_eq!(broken.num_packets().unwrap(), 16);
+    }
+
+    #[test]
+    fn advance_index() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+
+        table.reset_index().unwrap();
+        table.advance_index(4).unwrap();
+        assert_eq!(table.index().unwrap(), 4);
+        table.advance_index(-3).unwrap();
+        assert_eq!(table.index().unwrap(), 1);
+        assert_eq!(table.read_next::<i32>(2).unwrap(), &[1, 4]);
+        assert!(table.advance_index(-4).is_err());
+        assert!(table.advance_index(4).is_err());
+        assert_eq!(table.index().unwrap(), 3);
+        table.advance_index(3).unwrap();
+        assert_eq!(table.index().unwrap(), 6);
+    }
+
+    #[test]
+    fn validation_profile() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.validation_profile(), crate::validation_profile());
+
+        for profile in [
+            ValidationProfile::Strict,
+            ValidationProfile::Standard,
+            ValidationProfile::Fast,
+        ] {
+            table.set_validation_profile(Some(profile));
+            assert_eq!(table.validation_profile(), profile);
+            // Memory-safety checks are never skipped.
+            assert!(table.append::<i64>(&[1, 1, 4]).is_err());
+            assert!(table.read::<i64>(0, 1).is_err());
+        }
+        for profile in [ValidationProfile::Strict, ValidationProfile::Standard] {
+            table.set_validation_profile(Some(profile));
+            assert!(matches!(
+                table.append::<u32>(&[5, 1, 4]),
+                Err(Error::SchemaMismatch { .. })
+            ));
+            assert!(table.push(&5u32).is_err());
+            table.append::<i32>(&[1, 1, 4]).unwrap();
+        }
+        // The strict check of `i32` is reused by the standard profile.
+        assert_eq!(
+            table.checked_types,
+            [(std::any::TypeId::of::<i32>(), ValidationProfile::Strict)]
+        );
+        // Same-size mismatch is memory-safe.
+        table.set_validation_profile(Some(ValidationProfile::Fast));
+        table.append::<u32>(&[5, 1, 4]).unwrap();
+        assert_eq!(table.read::<i32>(0, 6).unwrap(), &[1, 1, 4, 1, 1, 4]);
+        assert_eq!(table.read::<i32>(6, 3).unwrap(), &[5, 1, 4]);
+
+        // The global profile is not changed here, because the tests run in parallel.
+        table.set_validation_profile(None);
+        assert_eq!(table.validation_profile(), crate::validation_profile());
+    }
+
+    #[test]
+    fn typed() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("untyped")
+            .unwrap();
+        let mut typed = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("typed")
+            .unwrap()
+            .typed::<i32>()
+            .unwrap();
+        for i in 0..1000 {
+            table.push(&i).unwrap();
+            typed.push(&i).unwrap();
+        }
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+        typed.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+        assert_eq!(
+            typed.read(0, 1006).unwrap(),
+            table.read::<i32>(0, 1006).unwrap()
+        );
+
+        assert!(matches!(
+            PacketTable::open(&data, "typed").unwrap().typed::<u32>(),
+            Err(Error::SchemaMismatch { .. })
+        ));
+        let mut table = PacketTable::open(&data, "typed").unwrap();
+        table.set_validation_profile(Some(ValidationProfile::Fast));
+        let mut typed = table.typed::<u32>().unwrap();
+        typed.push(&114514).unwrap();
+        typed
+            .inner_mut()
+            .set_validation_profile(Some(ValidationProfile::Strict));
+        assert!(matches!(
+            typed.push(&1919810),
+            Err(Error::SchemaMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn typed_unsized() {
+        type Data = UnsizedSlice<u32, u64>;
+
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut typed = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<Data>(3)
+            .create("data")
+            .unwrap()
+            .typed_unsized::<Data>(3)
+            .unwrap();
+
+        let mut vec: FixedVec<Data> = FixedVec::new(3);
+        for i in 0..4 {
+            unsafe {
+                vec.push_with(|slice| {
+                    slice.header.write(i);
+                    MaybeUninit::copy_from_slice(&mut slice.slice, &[i as u64, 1, 4]);
+                })
+            };
+        }
+        typed.append_unsized(&vec).unwrap();
+        typed.push(&vec[0]).unwrap();
+
+        let mut buffer = FixedVec::<Data>::new(3);
+        typed.read_unsized(0, 5, &mut buffer).unwrap();
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer[3].header, 3);
+        assert_eq!(&buffer[4].slice, &[0, 1, 4]);
+
+        let mut wrong = FixedVec::<Data>::new(2);
+        assert!(matches!(
+            typed.read_unsized(0, 1, &mut wrong),
+            Err(Error::MetadataMismatch {
+                expected_size: 32,
+                actual_size: 24
+            })
+        ));
+    }
+
+    #[test]
+    fn create_typed() {
+        type Data = UnsizedSlice<u32, u64>;
+
+        let file = NamedTempFile::new().unwrap();
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut typed = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_for::<i32>()
+            .create_typed("sized")
+            .unwrap();
+        typed.push(&1).unwrap();
+        typed.append(&[2, 3]).unwrap();
+        assert_eq!(typed.read(0, 3).unwrap(), [1, 2, 3]);
+
+        let mut typed = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized_for::<Data>(2)
+            .create_typed_unsized("unsized")
+            .unwrap();
+        let mut vec = FixedVec::<Data>::new(2);
+        unsafe {
+            vec.push_with(|slice| {
+                slice.header.write(7);
+                MaybeUninit::copy_from_slice(&mut slice.slice, &[1, 2]);
+            })
+        };
+        typed.push(&vec[0]).unwrap();
+        let mut buffer = FixedVec::<Data>::new(2);
+        typed.read_unsized(0, 1, &mut buffer).unwrap();
+        assert_eq!(buffer[0].header, 7);
+        assert_eq!(&buffer[0].slice, &[1, 2]);
+    }
+
+    #[test]
+    fn debug_fmt() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let expected = "<HDF5: packet table \"/data\", packet size 4>";
+        assert_eq!(hdf5::sync::sync(|| format!("{table:?}")), expected);
+
+        struct FmtOnDrop<'a>(&'a PacketTable, &'a mut String);
+
+        impl Drop for FmtOnDrop<'_> {
+            fn drop(&mut self) {
+                *self.1 = hdf5::sync::sync(|| format!("{:?}", self.0));
+            }
+        }
+
+        let mut output = String::new();
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = FmtOnDrop(&table, &mut output);
+            panic!("unwinding");
+        }));
+        assert!(res.is_err());
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn copy_to() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut src = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("src")
+            .unwrap();
+        let vec = (0..100).collect::<Vec<i32>>();
+        src.append(&vec).unwrap();
+
+        let mut dst = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("dst")
+            .unwrap();
+        assert_eq!(src.copy_to(&mut dst, 10..).unwrap(), 90);
+        assert_eq!(dst.read::<i32>(0, 90).unwrap(), &vec[10..]);
+
+        let mut wrong = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<f64>()
+            .create("wrong")
+            .unwrap();
+        assert!(src.copy_to(&mut wrong, ..).is_err());
+
+        let arrs = (0..20)
+            .map(|i| VarLenArray::from_slice(&vec[..i]))
+            .collect::<Vec<_>>();
+        let mut src = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<VarLenArray<i32>>()
+            .create("src_varlen")
+            .unwrap();
+        src.append(&arrs).unwrap();
+        let mut dst = PacketTable::builder(&data)
+            .chunk(4)
+            .dtype::<VarLenArray<i32>>()
+            .create("dst_varlen")
+            .unwrap();
+        assert_eq!(src.copy_to(&mut dst, ..).unwrap(), 20);
+        let read_data = dst
+            .iter::<VarLenArray<i32>>()
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(read_data, arrs);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, H5Type)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        value: f64,
+    }
+
+    #[test]
+    fn field_default() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<Record>()
+            .create("data")
+            .unwrap();
+        assert!(table.field_defaults().unwrap().is_empty());
+        assert_eq!(table.field_default::<f64>("value").unwrap(), None);
+
+        table.set_field_default("value", 114.514f64).unwrap();
+        table.set_field_default("value", 1919.810f64).unwrap();
+        assert_eq!(table.field_defaults().unwrap(), &["value"]);
+        assert_eq!(table.field_default::<f64>("value").unwrap(), Some(1919.810));
+
+        assert!(table.set_field_default("value", 1i32).is_err());
+        // The default of a missing field is stored for the compat reads.
+        table.set_field_default("unknown", 1f64).unwrap();
+        assert_eq!(table.field_defaults().unwrap(), &["unknown", "value"]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, H5Type)]
+    #[repr(C)]
+    struct RecordV2 {
+        flag: u8,
+        id: u32,
+        value: f64,
+        scale: f32,
+    }
+
+    #[test]
+    fn read_compat() {
+        let file = NamedTempFile::new().unwrap();
+        {
+            let data = hdf5::File::create(file.path()).unwrap();
+            let mut table = PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<Record>()
+                .create("data")
+                .unwrap();
+            let records = (0..4)
+                .map(|id| Record {
+                    id,
+                    value: id as f64,
+                })
+                .collect::<Vec<_>>();
+            table.append(&records).unwrap();
+            // Neither an explicit nor a stored default.
+            assert!(table.read_compat::<RecordV2>(.., None).is_err());
+            table.set_field_default("flag", 1u8).unwrap();
+            table.set_field_default("scale", 0.5f32).unwrap();
+        }
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&data, "data").unwrap();
+        let expected = |flag, scale| {
+            (1..3)
+                .map(|id| RecordV2 {
+                    flag,
+                    id,
+                    value: id as f64,
+                    scale,
+                })
+                .collect::<Vec<_>>()
+        };
+        // The stored defaults fill the missing members.
+        assert_eq!(
+            table.read_compat::<RecordV2>(1..3, None).unwrap(),
+            expected(1, 0.5)
+        );
+        // The explicit default overrides the stored ones.
+        let explicit = RecordV2 {
+            flag: 7,
+            id: 0,
+            value: 0.0,
+            scale: 2.0,
+        };
+        assert_eq!(
+            table.read_compat(1..3, Some(&explicit)).unwrap(),
+            expected(7, 2.0)
+        );
+        assert!(table.read_compat::<RecordV2>(3..5, None).is_err());
+        drop(table);
+        drop(data);
+
+        // The stored default should have the member type.
+        let data = hdf5::File::open_rw(file.path()).unwrap();
+        let table = PacketTable::open(&data, "data").unwrap();
+        table.set_field_default("scale", 0.5f64).unwrap();
+        assert!(matches!(
+            table.read_compat::<RecordV2>(.., None),
+            Err(Error::SchemaMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn from_dataset() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let group = data.create_group("group").unwrap();
+        {
+            let mut table = PacketTable::builder(&group)
+                .chunk(16)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+            table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+        }
+        let dataset = group.dataset("data").unwrap();
+        let table = PacketTable::from_dataset(&dataset).unwrap();
+        assert_eq!(table.read::<i32>(0, 6).unwrap(), &[1, 1, 4, 5, 1, 4]);
+
+        let contiguous = data
+            .new_dataset::<i32>()
+            .shape(6)
+            .create("contiguous")
+            .unwrap();
+        assert!(matches!(
+            PacketTable::from_dataset(&contiguous),
+            Err(Error::NotChunked)
+        ));
+
+        let matrix = data
+            .new_dataset::<i32>()
+            .chunk((2, 2))
+            .shape((4, 4))
+            .create("matrix")
+            .unwrap();
+        let err = PacketTable::from_dataset(&matrix).unwrap_err();
+        assert!(matches!(err, Error::WrongRank { ndim: 2 }));
+        assert!(err.to_string().contains("rank is 2"));
+    }
+
+    #[test]
+    fn locations() {
+        fn create_in(loc: &impl AsGroup, name: &str) {
+            let mut table = PacketTable::builder(loc)
+                .chunk(16)
+                .dtype::<i32>()
+                .create(name)
+                .unwrap();
+            table.append(&[1, 1, 4]).unwrap();
+        }
+
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let nested = data.create_group("a").unwrap().create_group("b").unwrap();
+        create_in(&data, "root");
+        create_in(&nested, "nested");
+        create_in(&&nested, "nested_ref");
+
+        let table = PacketTable::open(&data, "root").unwrap();
+        assert_eq!(table.read::<i32>(0, 3).unwrap(), &[1, 1, 4]);
+        let table = PacketTable::open(&nested, "nested").unwrap();
+        assert_eq!(table.read::<i32>(0, 3).unwrap(), &[1, 1, 4]);
+        let table = PacketTable::open(&data, "a/b/nested_ref").unwrap();
+        assert_eq!(table.read::<i32>(0, 3).unwrap(), &[1, 1, 4]);
+    }
+
+    #[test]
+    fn packet_size_mismatch() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i64>()
+            .create("data")
+            .unwrap();
+        assert!(matches!(
+            table.append::<i32>(&[1, 1, 4]),
+            Err(Error::PacketSize {
+                expected: 8,
+                actual: 4
+            })
+        ));
+        assert!(table.push(&1i32).is_err());
+        let mut vec = FixedVec::<[i32]>::new(3);
+        vec.push_clone(&[1, 1, 4][..]);
+        assert!(table.append_unsized(&vec).is_err());
+        assert_eq!(table.num_packets().unwrap(), 0);
+
+        table.append::<i64>(&[1, 1, 4]).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 3);
+    }
+
+    #[test]
+    fn storage_size() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(1024)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.allocated_chunks().unwrap(), 0);
+        table.append(&vec![0; 4096]).unwrap();
+        assert_eq!(table.allocated_chunks().unwrap(), 4);
+        assert_eq!(table.storage_size().unwrap(), 4096 * 4);
+
+        let plist = DatasetCreate::build()
+            .chunk(1024)
+            .deflate(9)
+            .finish()
+            .unwrap();
+        let mut compressed = PacketTable::builder(&data)
+            .plist(plist)
+            .dtype::<i32>()
+            .create("compressed")
+            .unwrap();
+        compressed.append(&vec![0; 4096]).unwrap();
+        let stats = compressed.stats().unwrap();
+        assert_eq!(stats.num_packets, 4096);
+        assert_eq!(stats.packet_size, 4);
+        assert_eq!(stats.allocated_chunks, 4);
+        assert!(stats.storage_size < 4096 * 4 / 10);
+    }
+
+    #[test]
+    fn compression() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let table = PacketTable::builder(&data)
+            .chunk(1024)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        assert!(table.filters().unwrap().is_empty());
+        assert_eq!(table.compression().unwrap(), None);
+
+        let plist = DatasetCreate::build()
+            .chunk(1024)
+            .deflate(6)
+            .finish()
+            .unwrap();
+        PacketTable::builder(&data)
+            .plist(plist)
+            .dtype::<i32>()
+            .create("compressed")
+            .unwrap();
+        let table = PacketTable::open(&data, "compressed").unwrap();
+        assert_eq!(
+            table.filters().unwrap(),
+            vec![hdf5::filters::Filter::Deflate(6)]
+        );
+        assert_eq!(table.compression().unwrap(), Some(6));
+    }
+
+    #[test]
+    fn try_clone() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1, 2, 3]).unwrap();
+        let mut cloned = table.try_clone().unwrap();
+        assert_eq!(cloned.read_next_one::<i32>().unwrap(), Some(1));
+        assert_eq!(table.index().unwrap(), 0);
+        drop(table);
+        assert_eq!(cloned.read_all::<i32>().unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn cancel() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&(0..100).collect::<Vec<_>>()).unwrap();
+        let token = CancelToken::new();
+        let mut table = table.with_cancel(token.clone());
+
+        let mut visited = 0;
+        let res = table.for_each_packet::<i32, _>(10, |_| {
+            visited += 1;
+            if visited == 35 {
+                let token = token.clone();
+                std::thread::spawn(move || token.cancel()).join().unwrap();
+            }
+            Ok(())
+        });
+        assert!(matches!(
+            res,
+            Err(Error::Cancelled {
+                processed: 40,
+                rolled_back: false
+            })
+        ));
+        assert_eq!(visited, 40);
+
+        let mut dst = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("dst")
+            .unwrap();
+        assert!(matches!(
+            table.copy_to(&mut dst, ..),
+            Err(Error::Cancelled {
+                processed: 0,
+                rolled_back: false
+            })
+        ));
+        assert_eq!(dst.num_packets().unwrap(), 0);
+
+        table.set_cancel_token(None);
+        assert_eq!(table.copy_to(&mut dst, ..).unwrap(), 100);
+
+        struct Reader {
+            token: CancelToken,
+            reads: usize,
+        }
+
+        impl std::io::Read for Reader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let len = buf.len().min(64);
+                buf[..len].fill(0);
+                self.reads += 1;
+                if self.reads == 8 {
+                    let token = self.token.clone();
+                    std::thread::spawn(move || token.cancel()).join().unwrap();
+                }
+                Ok(len)
+            }
+        }
+
+        let token = CancelToken::new();
+        let mut dst = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("raw")
+            .unwrap()
+            .with_cancel(token.clone());
+        let mut reader = Reader { token, reads: 0 };
+        // The staging buffer holds 64 packets, and is appended every 4 reads.
+        assert!(matches!(
+            dst.append_raw_from(&mut reader),
+            Err(Error::Cancelled {
+                processed: 128,
+                rolled_back: false
+            })
+        ));
+        // The appended packets are kept.
+        assert_eq!(dst.num_packets().unwrap(), 128);
+    }
+
+    #[test]
+    fn cancel_concurrently() {
+        use std::sync::atomic::AtomicUsize;
+
+        // An endless stream of zeros, counting the bytes read.
+        struct Reader(Arc<AtomicUsize>);
+
+        impl std::io::Read for Reader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                buf.fill(0);
+                self.0.fetch_add(buf.len(), Ordering::Relaxed);
+                Ok(buf.len())
+            }
+        }
+
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let token = CancelToken::new();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap()
+            .with_cancel(token.clone());
+        let read = Arc::new(AtomicUsize::new(0));
+        let mut reader = Reader(read.clone());
+        // The stream only ends by the cancellation from the other thread.
+        let canceller = std::thread::spawn(move || {
+            while read.load(Ordering::Relaxed) < 4096 {
+                std::thread::yield_now();
+            }
+            token.cancel();
+        });
+        let res = table.append_raw_from(&mut reader);
+        canceller.join().unwrap();
+        let Err(Error::Cancelled {
+            processed,
+            rolled_back,
+        }) = res
+        else {
+            panic!("unexpected result: {res:?}");
+        };
+        // The staging buffer of 64 packets is appended after every read.
+        assert!(processed >= 1024);
+        assert_eq!(processed % 64, 0);
+        // The appended packets are kept.
+        assert!(!rolled_back);
+        assert_eq!(table.num_packets().unwrap(), processed);
+        assert!(table.read_all::<i32>().unwrap().iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn create_fl() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create_fl("data", 6)
+            .unwrap();
+        table.append(&[1, 1, 4, 5, 1, 4]).unwrap();
+        assert_eq!(table.read_all::<i32>().unwrap(), [1, 1, 4, 5, 1, 4]);
+        assert_eq!(table.compression().unwrap(), Some(6));
+
+        assert!(PacketTable::builder(&data)
+            .dtype::<i32>()
+            .create_fl("no_chunk", 6)
+            .is_err());
+    }
+
+    #[test]
+    fn append_many() {
+        let file = NamedTempFile::new().unwrap();
+
+        {
+            let data = hdf5::File::create(file.path()).unwrap();
+            let mut table = PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+            table
+                .append_many::<i32>(&[&[1, 1], &[], &[4, 5, 1], &[4]])
+                .unwrap();
+            assert_eq!(table.read_all::<i32>().unwrap(), [1, 1, 4, 5, 1, 4]);
+            table.append_many::<i64>(&[&[], &[]]).unwrap();
+            assert!(matches!(
+                table.append_many::<i64>(&[&[1]]),
+                Err(Error::PacketSize { .. })
+            ));
+        }
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let mut table = PacketTable::open(&data, "data").unwrap();
+        let err = table.append_many::<i32>(&[&[], &[1], &[2]]).unwrap_err();
+        assert!(matches!(err, Error::PartialAppend { batches: 1, .. }));
+        assert_eq!(table.num_packets().unwrap(), 6);
+    }
+
+    #[test]
+    fn is_varlen() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("fixed")
+            .unwrap();
+        assert_eq!(table.table_type().unwrap(), PacketTableType::Fixed);
+        assert!(table.is_fixed().unwrap());
+        assert!(!table.is_varlen().unwrap());
+
+        let table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<VarLenArray<i32>>()
+            .create("varlen")
+            .unwrap();
+        assert_eq!(table.table_type().unwrap(), PacketTableType::VarLen);
+        assert!(!table.is_fixed().unwrap());
+        assert!(table.is_varlen().unwrap());
+    }
+
+    #[test]
+    fn metadata_mismatch() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<[i32]>(6)
+            .create("data")
+            .unwrap();
+        let mut vec = FixedVec::<[i32]>::new(6);
+        vec.push_clone(&[1, 1, 4, 5, 1, 4][..]);
+        table.append_unsized(&vec).unwrap();
+
+        for metadata in [4, 8] {
+            let mut buffer = FixedVec::<[i32]>::new(metadata);
+            assert!(matches!(
+                table.read_unsized(0, 1, &mut buffer),
+                Err(Error::PacketSize {
+                    expected: 24,
+                    actual
+                }) if actual == metadata * 4
+            ));
+            assert!(buffer.is_empty());
+            assert!(table.read_next_unsized(1, &mut buffer).is_err());
+            assert_eq!(table.index().unwrap(), 0);
+
+            buffer.push_clone(&vec![0; metadata][..]);
+            assert!(matches!(
+                table.append_unsized(&buffer),
+                Err(Error::PacketSize { .. })
+            ));
+        }
+        assert_eq!(table.num_packets().unwrap(), 1);
+    }
+
+    #[derive(MaybeUninitProject, hdf5_dst::H5TypeUnsized)]
+    #[repr(C)]
+    struct Padded {
+        a: u64,
+        s: [u8],
+    }
+
+    #[test]
+    fn padded_metadata_mismatch() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype_unsized::<Padded>(1)
+            .create("data")
+            .unwrap();
+        let padded = |len: usize| {
+            let mut vec = FixedVec::<Padded>::new(len);
+            unsafe {
+                vec.push_with(|p| {
+                    p.a.write(1);
+                    for b in &mut p.s {
+                        b.write(4);
+                    }
+                })
+            };
+            vec
+        };
+        let (one, two) = (padded(1), padded(2));
+        // Both are padded to 16 bytes, so only the type check catches the mismatch.
+        assert_eq!(size_of_val(&one[0]), size_of_val(&two[0]));
+
+        for profile in [ValidationProfile::Strict, ValidationProfile::Standard] {
+            table.set_validation_profile(Some(profile));
+            table.push(&one[0]).unwrap();
+            table.append_unsized(&one).unwrap();
+            assert!(matches!(
+                table.push(&two[0]),
+                Err(Error::SchemaMismatch { .. })
+            ));
+            assert!(matches!(
+                table.append_unsized(&two),
+                Err(Error::SchemaMismatch { .. })
+            ));
+        }
+        // The unsized types are not cached.
+        assert!(table.checked_types.is_empty());
+        assert_eq!(table.num_packets().unwrap(), 4);
+    }
+
+    #[test]
+    fn progress() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&(0..160).collect::<Vec<_>>()).unwrap();
+        let collector = Arc::new(ProgressCollector::new());
+        let mut table = table.with_progress(collector.clone());
+
+        // 10 chunks.
+        let mut dst = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("dst")
+            .unwrap();
+        table.copy_to(&mut dst, ..).unwrap();
+        let events = collector.events();
+        assert_eq!(events.len(), 10);
+        assert!(events.windows(2).all(|w| w[0].done < w[1].done));
+        assert!(events.iter().all(|e| e.operation == "copy_to"));
+        assert!(events.iter().all(|e| e.total == Some(160)));
+        assert_eq!(events.last().unwrap().done, 160);
+        assert_eq!(events.last().unwrap().bytes, 640);
+
+        // Tiny batches.
+        collector.clear();
+        table.append(&(0..840).collect::<Vec<_>>()).unwrap();
+        table.for_each_packet::<i32, _>(1, |_| Ok(())).unwrap();
+        let events = collector.events();
+        assert!(events.len() <= 101);
+        assert!(events.windows(2).all(|w| w[0].done < w[1].done));
+        assert_eq!(events.last().unwrap().done, 1000);
+
+        collector.clear();
+        assert_eq!(table.read_all::<i32>().unwrap().len(), 1000);
+        let events = collector.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].done, 1000);
+
+        // Cancellation.
+        collector.clear();
+        let token = CancelToken::new();
+        table.set_cancel_token(Some(token.clone()));
+        let mut visited = 0;
+        let res = table.for_each_packet::<i32, _>(100, |_| {
+            visited += 1;
+            if visited == 350 {
+                token.cancel();
+            }
+            Ok(())
+        });
+        assert!(matches!(
+            res,
+            Err(Error::Cancelled {
+                processed: 400,
+                rolled_back: false
+            })
+        ));
+        let events = collector.events();
+        assert_eq!(events.last().unwrap().done, 400);
+        assert!(events.iter().all(|e| e.done <= 400));
+    }
+
+    #[test]
+    fn fill_value() {
+        let file = NamedTempFile::new().unwrap();
+
+        {
+            let data = hdf5::File::create(file.path()).unwrap();
+            let mut table = PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<i32>()
+                .fill_value(114514i32)
+                .create("data")
+                .unwrap();
+            table.append(&[1, 2, 3]).unwrap();
+            table.dataset().unwrap().resize(6).unwrap();
+
+            assert!(matches!(
+                PacketTable::builder(&data)
+                    .chunk(16)
+                    .dtype::<i32>()
+                    .fill_value(1i64)
+                    .create("mismatch"),
+                Err(Error::FillValueMismatch { .. })
+            ));
+            assert!(PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<VarLenArray<i32>>()
+                .fill_value(VarLenArray::from_slice(&[1]))
+                .create("varlen")
+                .is_err());
+
+            let plist = DatasetCreate::build().chunk(16).finish().unwrap();
+            PacketTable::builder(&data)
+                .plist(plist)
+                .dtype::<i32>()
+                .fill_value(-1i32)
+                .create("plist")
+                .unwrap();
+        }
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&data, "data").unwrap();
+        assert_eq!(
+            table.read_all::<i32>().unwrap(),
+            [1, 2, 3, 114514, 114514, 114514]
+        );
+        let dataset = data.dataset("plist").unwrap();
+        assert_eq!(dataset.dcpl().unwrap().fill_value_as::<i32>(), Some(-1));
+    }
+
+    #[test]
+    fn fill_value_as() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .fill_value_as(
+                &TypeDescriptor::Unsigned(IntSize::U2),
+                &0xBEEFu16.to_ne_bytes(),
+            )
+            .dtype::<u16>()
+            .create("data")
+            .unwrap();
+        table.append(&[1u16, 2, 3]).unwrap();
+        let dataset = table.dataset().unwrap();
+        dataset.resize(6).unwrap();
+        assert_eq!(
+            dataset.read_raw::<u16>().unwrap(),
+            [1, 2, 3, 0xBEEF, 0xBEEF, 0xBEEF]
+        );
+
+        let plist = DatasetCreate::build()
+            .chunk(4)
+            .fill_value(-1i32)
+            .finish()
+            .unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .fill_value(7i32)
+            .dtype::<i32>()
+            .create("plist")
+            .unwrap();
+        let dataset = table.dataset().unwrap();
+        dataset.resize(2).unwrap();
+        assert_eq!(dataset.read_raw::<i32>().unwrap(), [7, 7]);
+
+        assert!(matches!(
+            PacketTable::builder(&file)
+                .chunk(4)
+                .fill_value_as(&TypeDescriptor::Unsigned(IntSize::U2), &[0])
+                .dtype::<u16>()
+                .create("short"),
+            Err(Error::PacketSize {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        let res = PacketTable::builder(&file)
+            .chunk(4)
+            .fill_value_as(&TypeDescriptor::Unsigned(IntSize::U4), &[0; 4])
+            .dtype::<u16>()
+            .create("mismatch");
+        assert!(matches!(
+            res,
+            Err(Error::FillValueMismatch { fill })
+                if *fill == TypeDescriptor::Unsigned(IntSize::U4)
+        ));
+        assert!(!file.link_exists("mismatch"));
+    }
+
+    #[test]
+    fn track_times() {
+        fn create(path: &std::path::Path) {
+            let file = hdf5::File::create(path).unwrap();
+            let mut table = PacketTable::builder(&file)
+                .chunk(4)
+                .track_times(false)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+            table.append(&[1, 2, 3, 4, 5]).unwrap();
+        }
+
+        let first = NamedTempFile::new().unwrap();
+        let second = NamedTempFile::new().unwrap();
+        create(first.path());
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        create(second.path());
+        assert_eq!(
+            std::fs::read(first.path()).unwrap(),
+            std::fs::read(second.path()).unwrap()
+        );
+
+        let file = hdf5::File::open_rw(first.path()).unwrap();
+        let plist = DatasetCreate::build().chunk(4).finish().unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .track_times(false)
+            .attr_creation_order(false, true)
+            .dtype::<i32>()
+            .create("plist")
+            .unwrap();
+        let plist = table.create_plist().unwrap();
+        assert!(!plist.obj_track_times());
+        assert_eq!(
+            plist.attr_creation_order().bits(),
+            H5P_CRT_ORDER_TRACKED | H5P_CRT_ORDER_INDEXED
+        );
+    }
+
+    #[test]
+    fn iter_indexed() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table
+            .append(&[1, 1, 4, 5, 1, 4, 1, 9, 1, 9, 8, 1, 0])
+            .unwrap();
+
+        let even = table
+            .iter_indexed::<i32>()
+            .filter(|res| !matches!(res, Ok((_, v)) if v % 2 != 0))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(even, [(2, 4), (5, 4), (10, 8), (12, 0)]);
+        for (index, val) in even {
+            assert_eq!(table.read::<i32>(index, 1).unwrap(), [val]);
+        }
+
+        let indexed = table
+            .iter_indexed_from::<i32>(10)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(indexed, [(10, 8), (11, 1), (12, 0)]);
+        let vals = table
+            .iter_from::<i32>(10)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(vals, [8, 1, 0]);
+        assert_eq!(table.iter_from::<i32>(13).count(), 0);
+    }
+
+    #[test]
+    fn create_plist() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let plist = DatasetCreate::build()
+            .chunk(64)
+            .shuffle()
+            .deflate(6)
+            .finish()
+            .unwrap();
+        let src = PacketTable::builder(&data)
+            .plist(plist)
+            .dtype::<i32>()
+            .fill_value(-1i32)
+            .create("src")
+            .unwrap();
+
+        let dst = PacketTable::builder(&data)
+            .plist(src.create_plist().unwrap())
+            .dtype::<i32>()
+            .create("dst")
+            .unwrap();
+        assert_eq!(dst.filters().unwrap(), src.filters().unwrap());
+        assert_eq!(dst.compression().unwrap(), Some(6));
+        let plist = dst.create_plist().unwrap();
+        assert_eq!(plist.chunk(), Some(vec![64]));
+        assert_eq!(plist.fill_value_as::<i32>(), Some(-1));
+    }
+
+    #[test]
+    fn create_many() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let plist = DatasetCreate::build()
+            .chunk(16)
+            .deflate(3)
+            .finish()
+            .unwrap();
+        let template = PacketTable::builder(&data).plist(plist).dtype::<i32>();
+        let names = (0..50).map(|i| format!("channel{i}")).collect::<Vec<_>>();
+        let mut tables = template.clone().create_many(&names, false).unwrap();
+        assert_eq!(tables.len(), 50);
+        let dtype = tables[0].dtype().unwrap();
+        for table in &tables {
+            assert_eq!(table.dtype().unwrap(), dtype);
+            assert_eq!(table.compression().unwrap(), Some(3));
+        }
+        tables[7].append(&[1, 1, 4]).unwrap();
+        for (i, table) in tables.iter().enumerate() {
+            let expected = if i == 7 { 3 } else { 0 };
+            assert_eq!(table.num_packets().unwrap(), expected);
+        }
+
+        let err = template
+            .clone()
+            .create_many(["a", "b", "a", "c"], false)
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::PartialCreate { name, created: 2, rolled_back: false, .. } if name == "a"
+        ));
+        assert!(data.link_exists("a") && data.link_exists("b"));
+        assert!(!data.link_exists("c"));
+
+        let err = template.create_many(["d", "e", "d"], true).unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::PartialCreate { name, created: 2, rolled_back: true, .. } if name == "d"
+        ));
+        assert!(!data.link_exists("d") && !data.link_exists("e"));
+    }
+
+    #[test]
+    fn member_names() {
+        use hdf5::types::{CompoundField, CompoundType, TypeDescriptor};
+
+        fn compound(name: &str) -> TypeDescriptor {
+            TypeDescriptor::Compound(CompoundType {
+                fields: vec![CompoundField::new(name, f64::type_descriptor(), 0, 0)],
+                size: 8,
+            })
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+
+        let table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype_as(compound("温度"))
+            .create("data")
+            .unwrap();
+        assert_eq!(
+            table.dtype().unwrap().to_descriptor().unwrap(),
+            compound("温度")
+        );
+
+        for name in ["", "a\0b"] {
+            assert!(PacketTable::builder(&file)
+                .chunk(4)
+                .dtype_as(compound(name))
+                .create("bad")
+                .is_err());
+        }
+        assert!(!file.link_exists("bad"));
+    }
+
+    #[test]
+    fn utf8_name() {
+        use hdf5_sys::{
+            h5l::{H5L_info1_t, H5Lget_info1},
+            h5t::H5T_cset_t,
+        };
+
+        fn cset(group: &Group, name: &str) -> H5T_cset_t {
+            let c_name = CString::new(name).unwrap();
+            let mut info = H5L_info1_t::default();
+            #[allow(deprecated)]
+            h5call!(H5Lget_info1(
+                group.id(),
+                c_name.as_ptr(),
+                &mut info,
+                H5P_DEFAULT
+            ))
+            .unwrap();
+            info.cset
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+
+        for overwrite in [false, true] {
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<u32>()
+                .overwrite(overwrite)
+                .create("温度")
+                .unwrap()
+                .append(&[1u32, 2, 3])
+                .unwrap();
+            assert_eq!(cset(&file, "温度"), H5T_cset_t::H5T_CSET_UTF8);
+            assert_eq!(file.member_names().unwrap(), ["温度"]);
+        }
+        PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(cset(&file, "data"), H5T_cset_t::H5T_CSET_ASCII);
+        assert_eq!(file.member_names().unwrap(), ["data", "温度"]);
+        assert_eq!(PacketTable::open(&file, "温度").unwrap().len().unwrap(), 3);
+    }
+
+    #[test]
+    fn get() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<[u64; 2]>()
+            .create("data")
+            .unwrap();
+        let packets = (0..10u64).map(|i| [i, i * i]).collect::<Vec<_>>();
+        table.append(&packets).unwrap();
+
+        for index in [0, 3, 9] {
+            assert_eq!(
+                table.get::<[u64; 2]>(index).unwrap(),
+                packets[index as usize]
+            );
+        }
+        assert!(matches!(
+            table.get::<[u64; 2]>(10),
+            Err(Error::NotEnoughPackets { start: 10, .. })
+        ));
+        assert!(table.get::<[u64; 2]>(u64::MAX).is_err());
+        assert!(table.get::<u64>(0).is_err());
+        assert_eq!(table.index().unwrap(), 0);
+    }
+
+    #[test]
+    fn overwrite() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[0u32, 1, 2, 3, 4, 5]).unwrap();
+
+        table.overwrite(2, &[20u32, 30]).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 1, 20, 30, 4, 5]);
+
+        table.overwrite(5, &[50u32]).unwrap();
+        table.overwrite(6, &[] as &[u32]).unwrap();
+        assert!(matches!(
+            table.overwrite(5, &[0u32, 0]),
+            Err(Error::NotEnoughPackets {
+                start: 5,
+                len: 2,
+                ..
+            })
+        ));
+        assert!(table.overwrite(0, &[0u64]).is_err());
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 1, 20, 30, 4, 50]);
+
+        table.append(&[6u32]).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 7);
+    }
+
+    #[test]
+    fn adopt() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let dset = file
+            .new_dataset::<u32>()
+            .chunk(4)
+            .shape(0..)
+            .create("data")
+            .unwrap();
+        dset.resize(3).unwrap();
+        dset.write(&[1u32, 2, 3]).unwrap();
+
+        let mut table = PacketTable::adopt(&dset).unwrap();
+        table.append(&[4u32, 5]).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3, 4, 5]);
+
+        let fixed = file
+            .new_dataset::<u32>()
+            .chunk(4)
+            .shape(8)
+            .create("fixed")
+            .unwrap();
+        assert!(matches!(
+            PacketTable::adopt(&fixed),
+            Err(Error::NotUnlimited { max: 8 })
+        ));
+        let bounded = file
+            .new_dataset::<u32>()
+            .chunk(4)
+            .shape(0..=100)
+            .create("bounded")
+            .unwrap();
+        assert!(matches!(
+            PacketTable::adopt(&bounded),
+            Err(Error::NotUnlimited { max: 100 })
+        ));
+        // The layout is checked before the extent.
+        let contiguous = file
+            .new_dataset::<u32>()
+            .shape(8)
+            .create("contiguous")
+            .unwrap();
+        assert!(matches!(
+            PacketTable::adopt(&contiguous),
+            Err(Error::NotChunked)
+        ));
+        let matrix = file
+            .new_dataset::<u32>()
+            .chunk((2, 2))
+            .shape((0.., 2))
+            .create("matrix")
+            .unwrap();
+        assert!(matches!(
+            PacketTable::adopt(&matrix),
+            Err(Error::WrongRank { ndim: 2 })
+        ));
+    }
+
+    #[test]
+    fn invalid_name() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+
+        let check = |e: Error| {
+            assert!(matches!(e, Error::InvalidName { position: 3, .. }));
+            assert!(e.to_string().contains("NUL"));
+        };
+        check(PacketTable::open(&file, "bad\0name").unwrap_err());
+        check(
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<u32>()
+                .create("bad\0name")
+                .unwrap_err(),
+        );
+    }
+
+    #[test]
+    fn compression_zstd() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let res = PacketTable::builder(&file)
+            .chunk(1024)
+            .compression_zstd(3)
+            .dtype::<u32>()
+            .create("data");
+        if zstd_filter_available() {
+            let mut table = res.unwrap();
+            table.append(&[7u32; 4096]).unwrap();
+            assert_eq!(table.read_all::<u32>().unwrap(), [7u32; 4096]);
+            assert!(table.stats().unwrap().compression_ratio().unwrap() > 1.0);
+        } else {
+            assert!(matches!(
+                res,
+                Err(Error::FilterUnavailable { id: ZSTD_FILTER_ID })
+            ));
+            assert!(!file.link_exists("data"));
+        }
+    }
+
+    #[cfg(feature = "blosc")]
+    #[test]
+    fn blosc() {
+        use hdf5::filters::{Blosc, BloscShuffle};
+
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+        {
+            let file = hdf5::File::create(path).unwrap();
+            let mut table = PacketTable::builder(&file)
+                .chunk(1024)
+                .blosc(Blosc::LZ4, 5, BloscShuffle::Byte)
+                .dtype::<u32>()
+                .create("data")
+                .unwrap();
+            table.append(&(0..4096u32).collect::<Vec<_>>()).unwrap();
+            assert!(matches!(
+                table.filters().unwrap()[..],
+                [Filter::Blosc(Blosc::LZ4, 5, BloscShuffle::Byte)]
+            ));
+
+            assert!(PacketTable::builder(&file)
+                .chunk(1024)
+                .blosc(Blosc::LZ4, 10, true)
+                .dtype::<u32>()
+                .create("invalid")
+                .is_err());
+        }
+        let file = hdf5::File::open(path).unwrap();
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(
+            table.read_all::<u32>().unwrap(),
+            (0..4096).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "lzf")]
+    #[test]
+    fn lzf() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+        {
+            let file = hdf5::File::create(path).unwrap();
+            let mut table = PacketTable::builder(&file)
+                .chunk(1024)
+                .lzf()
+                .dtype::<u32>()
+                .create("data")
+                .unwrap();
+            table.append(&[7u32; 4096]).unwrap();
+            assert_eq!(table.filters().unwrap(), [Filter::LZF]);
+        }
+        let file = hdf5::File::open(path).unwrap();
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [7u32; 4096]);
+        assert!(table.stats().unwrap().compression_ratio().unwrap() > 1.0);
+    }
+
+    #[test]
+    fn deflate() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(1024)
+            .dtype::<u32>()
+            .deflate(6)
+            .create("data")
+            .unwrap();
+        table.append(&[7u32; 4096]).unwrap();
+        assert_eq!(table.compression().unwrap(), Some(6));
+        assert_eq!(table.read_all::<u32>().unwrap(), [7u32; 4096]);
+
+        let plist = DatasetCreate::build().chunk(16).finish().unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .deflate(2)
+            .dtype::<u32>()
+            .create("plist")
+            .unwrap();
+        assert_eq!(table.compression().unwrap(), Some(2));
+        assert_eq!(table.create_plist().unwrap().chunk(), Some(vec![16]));
+
+        assert!(PacketTable::builder(&file)
+            .chunk(16)
+            .deflate(10)
+            .dtype::<u32>()
+            .create("invalid")
+            .is_err());
+        assert!(!file.link_exists("invalid"));
+    }
+
+    #[test]
+    fn shuffle_fletcher32() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(64)
+            .deflate(4)
+            .fletcher32(true)
+            .shuffle(true)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(
+            table.filters().unwrap(),
+            [Filter::Shuffle, Filter::Deflate(4), Filter::Fletcher32]
+        );
+        table.append(&(0..256u32).collect::<Vec<_>>()).unwrap();
+        assert_eq!(
+            table.read_all::<u32>().unwrap(),
+            (0..256).collect::<Vec<_>>()
+        );
+
+        let plist = DatasetCreate::build().chunk(16).finish().unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .shuffle(true)
+            .dtype::<u32>()
+            .create("plist")
+            .unwrap();
+        assert_eq!(table.filters().unwrap(), [Filter::Shuffle]);
+
+        let plist = DatasetCreate::build()
+            .chunk(16)
+            .deflate(2)
+            .finish()
+            .unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .fletcher32(true)
+            .shuffle(true)
+            .dtype::<u32>()
+            .create("plist_deflate")
+            .unwrap();
+        assert_eq!(
+            table.filters().unwrap(),
+            [Filter::Shuffle, Filter::Deflate(2), Filter::Fletcher32]
+        );
+
+        let table = PacketTable::builder(&file)
+            .chunk(16)
+            .shuffle(true)
+            .shuffle(false)
+            .dtype::<u32>()
+            .create("none")
+            .unwrap();
+        assert!(table.filters().unwrap().is_empty());
+    }
+
+    #[test]
+    fn write_at() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[0u32, 1, 2, 3, 4]).unwrap();
+        table.write_at(2, &20u32).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 1, 20, 3, 4]);
+        assert!(table.write_at(5, &5u32).is_err());
+        assert!(table.write_at(0, &0u64).is_err());
+
+        type Data = UnsizedSlice<u32, u64>;
+        let mut vec: FixedVec<Data> = FixedVec::new(3);
+        for i in 0..4 {
+            unsafe {
+                vec.push_with(|slice| {
+                    slice.header.write(i);
+                    MaybeUninit::copy_from_slice(&mut slice.slice, &[i as u64; 3]);
+                })
+            };
+        }
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype_unsized::<Data>(3)
+            .create("unsized")
+            .unwrap();
+        table.append_unsized(&vec).unwrap();
+        let patch: Box<Data> = unsafe {
+            Box::<Data>::new_unsized_with(3, |slice| {
+                slice.header.write(100);
+                MaybeUninit::copy_from_slice(&mut slice.slice, &[7, 8, 9]);
+            })
+        };
+        table.write_at_unsized(1, patch.as_ref()).unwrap();
+        let mut read: FixedVec<Data> = FixedVec::new(3);
+        table.read_unsized(0, 4, &mut read).unwrap();
+        assert_eq!(read[1].header, 100);
+        assert_eq!(&read[1].slice, &[7, 8, 9]);
+        for i in [0, 2, 3] {
+            assert_eq!(read[i].header, i as u32);
+            assert_eq!(&read[i].slice, &[i as u64; 3]);
+        }
+        assert!(table.write_at_unsized(4, patch.as_ref()).is_err());
+    }
+
+    #[test]
+    fn truncate() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[0u32, 1, 2, 3, 4, 5]).unwrap();
+        table.set_index(5).unwrap();
+
+        table.truncate(3).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 3);
+        assert_eq!(table.index().unwrap(), 3);
+        table.append(&[30u32]).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 1, 2, 30]);
+        assert!(matches!(
+            table.truncate(5),
+            Err(Error::TruncateBeyondEnd {
+                len: 5,
+                num_packets: 4
+            })
+        ));
+        assert_eq!(
+            PacketTable::open(&file, "data")
+                .unwrap()
+                .num_packets()
+                .unwrap(),
+            4
+        );
+    }
+
+    #[test]
+    fn type_descriptor() {
+        #[derive(H5Type)]
+        #[repr(C)]
+        struct Record {
+            id: u32,
+            value: f64,
+            flags: [u8; 3],
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Record>()
+            .create("data")
+            .unwrap();
+        let table = PacketTable::open(&file, "data").unwrap();
+        let TypeDescriptor::Compound(ty) = table.type_descriptor().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(ty.size, size_of::<Record>());
+        let fields = ty
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.offset, f.ty.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                ("id", 0, u32::type_descriptor()),
+                ("value", 8, f64::type_descriptor()),
+                ("flags", 16, <[u8; 3]>::type_descriptor()),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_chunks() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.read_chunks::<u32>(3).count(), 0);
+
+        table.append(&(0..10u32).collect::<Vec<_>>()).unwrap();
+        let blocks = table
+            .read_chunks::<u32>(4)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(blocks, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+        assert_eq!(table.read_chunks::<u32>(5).count(), 2);
+
+        let mut iter = table.read_chunks::<u64>(4);
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        let mut iter = table.read_chunks::<u32>(0);
+        assert!(matches!(iter.next(), Some(Err(Error::ZeroChunk))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn create_path() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create_path("/x/y/z/data")
+            .unwrap();
+        table.append(&[1u32, 2, 3]).unwrap();
+        drop(table);
+
+        let table = PacketTable::open(&file, "/x/y/z/data").unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3]);
+
+        let group = file.group("x").unwrap();
+        PacketTable::builder(&group)
+            .chunk(4)
+            .dtype::<u32>()
+            .create_path("y/other")
+            .unwrap();
+        assert!(file.link_exists("/x/y/other"));
+        PacketTable::builder(&group)
+            .chunk(4)
+            .dtype::<u32>()
+            .create_path("/top")
+            .unwrap();
+        assert!(file.link_exists("/top"));
+        assert!(PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create_path("x/y/z/data/nested")
+            .is_err());
+        assert!(PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create_path("x/")
+            .is_err());
+    }
+
+    #[test]
+    fn capacity() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let dset = file
+            .new_dataset::<u32>()
+            .chunk(16)
+            .shape(0..=100)
+            .create("bounded")
+            .unwrap();
+        let mut table = PacketTable::from_dataset(&dset).unwrap();
+        assert_eq!(
+            format!("{table:?}"),
+            "<HDF5: packet table \"/bounded\", packet size 4, max packets 100>"
+        );
+        assert_eq!(table.capacity_remaining().unwrap(), Some(100));
+        table.append(&(0..60u32).collect::<Vec<_>>()).unwrap();
+        table.append(&(60..100u32).collect::<Vec<_>>()).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 100);
+        assert_eq!(table.capacity_remaining().unwrap(), Some(0));
+        assert!(matches!(
+            table.push(&100u32),
+            Err(Error::CapacityExceeded {
+                len: 1,
+                remaining: 0
+            })
+        ));
+        table.truncate(95).unwrap();
+        assert!(matches!(
+            table.append(&[0u32; 10]),
+            Err(Error::CapacityExceeded {
+                len: 10,
+                remaining: 5
+            })
+        ));
+        assert_eq!(table.num_packets().unwrap(), 95);
+
+        let table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("unlimited")
+            .unwrap();
+        assert_eq!(table.capacity_remaining().unwrap(), None);
+    }
+
+    #[test]
+    fn overflow() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[0u32, 1, 2, 3]).unwrap();
+
+        assert!(matches!(
+            table.read::<u32>(u64::MAX, 2),
+            Err(Error::RangeOverflow)
+        ));
+        assert!(matches!(
+            table.read_array::<u32, 2>(u64::MAX),
+            Err(Error::RangeOverflow)
+        ));
+        assert!(matches!(
+            table.overwrite(u64::MAX, &[0u32, 1]),
+            Err(Error::RangeOverflow)
+        ));
+        assert!(matches!(
+            table.copy_raw_to(&mut std::io::sink(), u64::MAX..=u64::MAX),
+            Err(Error::RangeOverflow)
+        ));
+
+        // The index beyond the end is kept, but no packet could be read from it.
+        table.set_index(u64::MAX).unwrap();
+        assert_eq!(table.index().unwrap(), u64::MAX);
+        assert_eq!(table.read_next_one::<u32>().unwrap(), None);
+        assert!(matches!(
+            table.read_next::<u32>(2),
+            Err(Error::RangeOverflow)
+        ));
+        assert!(table.advance_index(1).is_err());
+        assert_eq!(table.index().unwrap(), u64::MAX);
+        table.set_index(5).unwrap();
+        assert!(matches!(
+            table.read_next::<u32>(1),
+            Err(Error::NotEnoughPackets { .. })
+        ));
+        table.reset_index().unwrap();
+        assert_eq!(table.read_next::<u32>(4).unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn read_raw() {
+        let file = NamedTempFile::new().unwrap();
+        let manifest = fixtures::standard_file(file.path(), 114514).unwrap();
+        let entry = manifest.get(fixtures::I32_TABLE).unwrap();
+        let file = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&file, fixtures::I32_TABLE).unwrap();
+
+        let raw = table.read_raw(0, entry.count).unwrap();
+        assert_eq!(fixtures::checksum(&raw), entry.checksum);
+        let raw = table.read_raw(1, 4).unwrap();
+        let expected = table
+            .read::<i32>(1, 4)
+            .unwrap()
+            .into_iter()
+            .flat_map(i32::to_ne_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(raw, expected);
+        assert!(table.read_raw(0, 0).unwrap().is_empty());
+        assert!(table.read_raw(entry.count as u64 - 2, 3).is_err());
+    }
+
+    #[test]
+    fn dst_errors() {
+        use hdf5_dst::{ContainerBuilderExt, ContainerExt};
+
+        type Data = UnsizedSlice<u32, u64>;
+
+        // Both crates under a single `?`-based flow.
+        fn copy_dst(file: &hdf5::File, vec: &FixedVec<Data>) -> Result<u64> {
+            let dataset = file
+                .new_dataset_builder()
+                .with_data_unsized::<Data>(vec)
+                .create("dst")?;
+            let mut read = FixedVec::<Data>::new(2);
+            dataset.read_unsized(&mut read)?;
+            let mut table = PacketTable::builder(file)
+                .chunk(4)
+                .dtype_unsized_like(read.as_ptr())
+                .create("table")?;
+            table.append_unsized(&read)?;
+            table.num_packets()
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut vec = FixedVec::<Data>::new(2);
+        unsafe {
+            vec.push_with(|slice| {
+                slice.header.write(1);
+                MaybeUninit::copy_from_slice(&mut slice.slice, &[2, 3]);
+            })
+        };
+        assert_eq!(copy_dst(&file, &vec).unwrap(), 1);
+
+        // The mismatched member survives the conversion.
+        let dataset = file.dataset("dst").unwrap();
+        let mut wrong = FixedVec::<Data>::new(3);
+        let res = dataset.read_unsized(&mut wrong).map_err(Error::from);
+        let Err(Error::Dst(hdf5_dst::Error::DescriptorMismatch { expected, actual })) = res else {
+            panic!("{res:?}");
+        };
+        let (TypeDescriptor::Compound(expected), TypeDescriptor::Compound(actual)) =
+            (*expected, *actual)
+        else {
+            unreachable!()
+        };
+        assert_eq!(expected.fields[0], actual.fields[0]);
+        assert_eq!(actual.fields[1].name, "slice");
+        assert_eq!(
+            actual.fields[1].ty,
+            TypeDescriptor::FixedArray(Box::new(u64::type_descriptor()), 3)
+        );
+
+        // The metadata of the records can't be inferred from a plain dataset.
+        let plain = file.new_dataset::<u64>().shape(2).create("plain").unwrap();
+        let res = plain
+            .read_unsized_prefixed(&mut FixedVec::<UnsizedSlice<u16, u64>>::new(2))
+            .map_err(Error::from);
+        assert!(matches!(
+            res,
+            Err(Error::Dst(hdf5_dst::Error::MetadataInferenceFailed { reason }))
+                if reason.contains("compound")
+        ));
+
+        let name = "a".repeat(hdf5_dst::MAX_MEMBER_NAME_LEN + 1);
+        let err = Error::from(hdf5_dst::check_member_name(&name).unwrap_err());
+        assert!(matches!(
+            err,
+            Error::Dst(hdf5_dst::Error::InvalidMemberName { name: n, .. }) if n == name
+        ));
+        assert!(matches!(
+            Error::from(hdf5_dst::Error::Hdf5("e".into())),
+            Error::Hdf5(_)
+        ));
+    }
+
+    #[test]
+    fn dtype_varlen() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let arrays = [
+            VarLenArray::from_slice(&[1i32, 1, 4]),
+            VarLenArray::from_slice(&[]),
+            VarLenArray::from_slice(&[5, 1, 4]),
+        ];
+        for (name, builder) in [
+            (
+                "typed",
+                PacketTable::builder(&file).chunk(4).dtype_varlen::<i32>(),
+            ),
+            (
+                "runtime",
+                PacketTable::builder(&file)
+                    .chunk(4)
+                    .dtype_varlen_as(i32::type_descriptor()),
+            ),
+        ] {
+            let mut table = builder.create(name).unwrap();
+            assert_eq!(table.table_type().unwrap(), PacketTableType::VarLen);
+            table.push(&arrays[0]).unwrap();
+            table.append(&arrays[1..]).unwrap();
+            let read = table
+                .iter::<VarLenArray<i32>>()
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(read, arrays);
+        }
+    }
+
+    #[test]
+    fn create_or_open() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        for i in 0..2u32 {
+            let mut table = PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<u32>()
+                .create_or_open("data")
+                .unwrap();
+            table.push(&i).unwrap();
+        }
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 1]);
+
+        let res = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<f64>()
+            .create_or_open("data");
+        let Err(Error::SchemaMismatch { expected, actual }) = res else {
+            panic!("{res:?}");
+        };
+        assert_eq!(*expected, f64::type_descriptor());
+        assert_eq!(*actual, u32::type_descriptor());
+    }
+
+    #[test]
+    fn dtype_raw() {
+        #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+        #[repr(C)]
+        struct Pair {
+            id: u32,
+            value: f64,
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let dtype = Datatype::from_type::<Pair>().unwrap();
+        let name = CString::new("pair").unwrap();
+        h5call!(H5Tcommit2(
+            file.id(),
+            name.as_ptr(),
+            dtype.id(),
+            H5P_DEFAULT,
+            H5P_DEFAULT,
+            H5P_DEFAULT
+        ))
+        .unwrap();
+
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype_raw(dtype)
+            .create("data")
+            .unwrap();
+        table
+            .append(&[Pair { id: 1, value: 1.5 }, Pair { id: 2, value: 2.5 }])
+            .unwrap();
+        assert_eq!(
+            table.read_all::<Pair>().unwrap(),
+            [Pair { id: 1, value: 1.5 }, Pair { id: 2, value: 2.5 }]
+        );
+        let stored = table.dataset().unwrap().dtype().unwrap();
+        assert!(h5call!(H5Tcommitted(stored.id())).unwrap() > 0);
+    }
+
+    #[test]
+    fn dtype_like_table() {
+        #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+        #[repr(C)]
+        struct Pair {
+            id: u32,
+            value: f64,
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut src = PacketTable::builder(&file)
+            .chunk(16)
+            .shuffle(true)
+            .deflate(5)
+            .dtype::<Pair>()
+            .create("src")
+            .unwrap();
+        let packets = (0..40u32)
+            .map(|i| Pair {
+                id: i,
+                value: i as f64 / 2.0,
+            })
+            .collect::<Vec<_>>();
+        src.append(&packets).unwrap();
+
+        let mut dst = PacketTable::builder(&file)
+            .dtype_like_table(&src)
+            .unwrap()
+            .create("dst")
+            .unwrap();
+        assert_eq!(src.copy_to(&mut dst, ..).unwrap(), 40);
+        assert_eq!(dst.dtype().unwrap(), src.dtype().unwrap());
+        assert_eq!(dst.filters().unwrap(), src.filters().unwrap());
+        assert_eq!(dst.create_plist().unwrap().chunk(), Some(vec![16]));
+        assert_eq!(dst.read_all::<Pair>().unwrap(), packets);
+
+        let table = PacketTable::builder(&file)
+            .chunk(8)
+            .deflate(1)
+            .dtype_like_table(&src)
+            .unwrap()
+            .create("override")
+            .unwrap();
+        assert_eq!(table.dtype().unwrap(), src.dtype().unwrap());
+        assert_eq!(table.filters().unwrap(), [Filter::Deflate(1)]);
+        assert_eq!(table.create_plist().unwrap().chunk(), Some(vec![8]));
+    }
+
+    #[test]
+    fn dtype_like() {
+        #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+        #[repr(C)]
+        struct Record {
+            id: u32,
+            name: FixedAscii<8>,
+            value: f64,
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut src = PacketTable::builder(&file)
+            .chunk(16)
+            .deflate(3)
+            .dtype::<Record>()
+            .create("src")
+            .unwrap();
+        let records = (0..50u32)
+            .map(|i| Record {
+                id: i,
+                name: FixedAscii::from_ascii(format!("r{i}").as_bytes()).unwrap(),
+                value: i as f64 * 0.25,
+            })
+            .collect::<Vec<_>>();
+        src.append(&records).unwrap();
+
+        let mut dst = PacketTable::builder(&file)
+            .chunk(8)
+            .dtype_like(&src)
+            .unwrap()
+            .create("dst")
+            .unwrap();
+        assert_eq!(dst.dtype().unwrap(), src.dtype().unwrap());
+        assert!(dst.filters().unwrap().is_empty());
+        assert_eq!(src.copy_to(&mut dst, ..).unwrap(), 50);
+        assert_eq!(dst.read_all::<Record>().unwrap(), records);
+    }
+
+    #[test]
+    fn refresh() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1u32, 2]).unwrap();
+        table.set_index(1).unwrap();
+
+        // Another handle appends.
+        let mut other = table.try_clone().unwrap();
+        other.append(&[3u32, 4, 5]).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 2);
+        table.refresh().unwrap();
+        assert_eq!(table.num_packets().unwrap(), 5);
+        assert_eq!(table.index().unwrap(), 1);
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3, 4, 5]);
+
+        // The table is implicitly refreshed after truncating.
+        table.truncate(3).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 3);
+
+        // The link is replaced.
+        let mut replaced = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<f64>()
+            .create("replaced")
+            .unwrap();
+        replaced.append(&[0.5f64]).unwrap();
+        file.relink("data", "old").unwrap();
+        file.relink("replaced", "data").unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3]);
+        table.refresh().unwrap();
+        assert_eq!(
+            table.dtype().unwrap(),
+            Datatype::from_type::<f64>().unwrap()
+        );
+        assert_eq!(table.num_packets().unwrap(), 1);
+        assert_eq!(table.index().unwrap(), 1);
+        assert_eq!(table.read_all::<f64>().unwrap(), [0.5]);
+        assert!(table.read_all::<u32>().is_err());
+    }
+
+    #[test]
+    fn overwrite_existing() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1u32, 2]).unwrap();
+        drop(table);
+
+        assert!(matches!(
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<u32>()
+                .create("data"),
+            Err(Error::AlreadyExists { name }) if name == "data"
+        ));
+        assert_eq!(PacketTable::open(&file, "data").unwrap().len().unwrap(), 2);
+
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .overwrite(true)
+            .dtype::<f64>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.num_packets().unwrap(), 0);
+        table.push(&0.5f64).unwrap();
+        drop(table);
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(table.read_all::<f64>().unwrap(), [0.5]);
+
+        file.create_group("group").unwrap();
+        assert!(matches!(
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<u32>()
+                .overwrite(true)
+                .create("group"),
+            Err(Error::AlreadyExists { .. })
+        ));
+        assert!(file.group("group").is_ok());
+
+        // The old table is kept if creating the new one fails.
+        assert!(PacketTable::builder(&file)
+            .chunk(4)
+            .overwrite(true)
+            .attr("unit", 1u32)
+            .attr("unit", 2u32)
+            .dtype::<u32>()
+            .create("data")
+            .is_err());
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(table.read_all::<f64>().unwrap(), [0.5]);
+        assert_eq!(file.member_names().unwrap(), ["data", "group"]);
+
+        // The path of the replacing table is the table name.
+        let table = PacketTable::builder(&file)
+            .chunk(4)
+            .overwrite(true)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.dataset().unwrap().name(), "/data");
+        assert_eq!(file.member_names().unwrap(), ["data", "group"]);
+    }
+
+    /// Fails to move the link with the source name.
+    struct FaultyLinks<'a> {
+        group: &'a Group,
+        fail_src: &'a str,
+    }
+
+    impl super::LinkTarget for FaultyLinks<'_> {
+        fn move_link(&self, src: &str, dst: &str) -> Result<()> {
+            if src == self.fail_src {
+                return Err(Error::Other("Injected move failure.".to_string()));
+            }
+            super::LinkTarget::move_link(self.group, src, dst)
+        }
+
+        fn unlink(&self, name: &str) -> Result<()> {
+            super::LinkTarget::unlink(self.group, name)
+        }
+    }
+
+    #[test]
+    fn replace_link_failed() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[1u32, 2]).unwrap();
+        drop(table);
+
+        // The old table is kept if moving either table fails, and the new one is removed.
+        for fail_src in ["data.~0", "data"] {
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype::<f64>()
+                .create("data.~0")
+                .unwrap();
+            let faulty = FaultyLinks {
+                group: &file,
+                fail_src,
+            };
+            assert!(matches!(
+                super::replace_link(&faulty, "data.~0", "data", Some("data.~1")),
+                Err(Error::Other(_))
+            ));
+            let table = PacketTable::open(&file, "data").unwrap();
+            assert_eq!(table.read_all::<u32>().unwrap(), [1, 2]);
+            assert_eq!(file.member_names().unwrap(), ["data"]);
+        }
+
+        PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<f64>()
+            .create("data.~0")
+            .unwrap();
+        let group: &Group = &file;
+        super::replace_link(group, "data.~0", "data", Some("data.~1")).unwrap();
+        let table = PacketTable::open(&file, "data").unwrap();
+        assert_eq!(table.num_packets().unwrap(), 0);
+        assert_eq!(
+            table.dtype().unwrap(),
+            Datatype::from_type::<f64>().unwrap()
+        );
+        assert_eq!(file.member_names().unwrap(), ["data"]);
+    }
+
+    #[test]
+    fn commit_type() {
+        #[derive(H5Type)]
+        #[repr(C)]
+        struct Pair {
+            id: u32,
+            value: f64,
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let group = file.create_group("group").unwrap();
+        let builder = PacketTable::builder(&group)
+            .chunk(4)
+            .dtype::<Pair>()
+            .commit_type("pair");
+        let mut tables = builder.clone().create_many(["a", "b"], false).unwrap();
+        tables[0].push(&Pair { id: 1, value: 0.5 }).unwrap();
+        assert_eq!(
+            group.loc_type_by_name("pair").unwrap(),
+            hdf5::LocationType::NamedDatatype
+        );
+        for table in &tables {
+            let stored = table.dataset().unwrap().dtype().unwrap();
+            assert!(h5call!(H5Tcommitted(stored.id())).unwrap() > 0);
+        }
+        assert!(builder.create("a").is_err());
+
+        let res = PacketTable::builder(&group)
+            .chunk(4)
+            .dtype::<u32>()
+            .commit_type("pair")
+            .create("c");
+        assert!(matches!(res, Err(Error::SchemaMismatch { .. })));
+        assert!(!group.link_exists("c"));
+    }
+
+    #[test]
+    fn attrs() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let table = PacketTable::builder(&file)
+            .chunk(16)
+            .attr("version", 3u32)
+            .attr("scale", [0.5f64, 2.0, 4.0])
+            .dtype::<f64>()
+            .attr_str("units", "m/s")
+            .create("data")
+            .unwrap();
+        let dset = table.dataset().unwrap();
+        assert_eq!(
+            dset.attr("version").unwrap().read_scalar::<u32>().unwrap(),
+            3
+        );
+        assert_eq!(
+            dset.attr("scale")
+                .unwrap()
+                .read_scalar::<[f64; 3]>()
+                .unwrap(),
+            [0.5, 2.0, 4.0]
+        );
+        assert_eq!(
+            dset.attr("units")
+                .unwrap()
+                .read_scalar::<VarLenUnicode>()
+                .unwrap()
+                .as_str(),
+            "m/s"
+        );
+
+        let res = PacketTable::builder(&file)
+            .chunk(16)
+            .attr("version", 3u32)
+            .attr("bad\0name", 1u8)
+            .dtype::<f64>()
+            .create("invalid");
+        assert!(matches!(res, Err(Error::InvalidName { .. })));
+        assert!(!file.link_exists("invalid"));
+
+        let res = PacketTable::builder(&file)
+            .chunk(16)
+            .attr("producer", "x".parse::<VarLenUnicode>().unwrap())
+            .dtype::<f64>()
+            .create("pointers");
+        assert!(res.is_err());
+        assert!(!file.link_exists("pointers"));
+
+        // The dataset is unlinked if reopening it with the chunk cache fails.
+        let res = PacketTable::builder(&file)
+            .chunk(16)
+            .chunk_cache(521, 1 << 20, 2.0)
+            .dtype::<f64>()
+            .create("cache");
+        assert!(res.is_err());
+        assert!(!file.link_exists("cache"));
+    }
+
+    #[test]
+    fn create_intermediate() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        assert!(PacketTable::builder(&file)
+            .chunk(16)
+            .dtype::<u32>()
+            .create("runs/2024/photons")
+            .is_err());
+        assert!(!file.link_exists("runs"));
+
+        let builder = PacketTable::builder(&file)
+            .chunk(16)
+            .create_intermediate(true)
+            .dtype::<u32>();
+        let mut table = builder.clone().create("runs/2024/photons").unwrap();
+        table.push(&1u32).unwrap();
+        builder.clone().create("runs/2024/electrons").unwrap();
+        builder.clone().create("/runs/2025/photons").unwrap();
+        builder.clone().create("flat").unwrap();
+        assert!(builder.clone().create("runs/2026/").is_err());
+
+        let group = file.group("runs/2024").unwrap();
+        let mut names = group.member_names().unwrap();
+        names.sort();
+        assert_eq!(names, ["electrons", "photons"]);
+        assert!(PacketTable::exists(&file, "runs/2025/photons").unwrap());
+        assert!(PacketTable::exists(&file, "flat").unwrap());
+        assert_eq!(
+            PacketTable::open(&file, "runs/2024/photons")
+                .unwrap()
+                .read_all::<u32>()
+                .unwrap(),
+            [1]
+        );
+
+        let tables = builder
+            .clone()
+            .create_many(["many/a", "many/b"], false)
+            .unwrap();
+        assert_eq!(tables.len(), 2);
+        assert!(PacketTable::exists(&file, "many/a").unwrap());
+        assert!(PacketTable::exists(&file, "many/b").unwrap());
+        let table = builder.clone().create_or_open("runs/2024/photons").unwrap();
+        assert_eq!(table.num_packets().unwrap(), 1);
+        builder.clone().create_or_open("open/data").unwrap();
+        assert!(PacketTable::exists(&file, "open/data").unwrap());
+        #[allow(deprecated)]
+        builder.clone().create_fl("legacy/data", -1).unwrap();
+        assert!(PacketTable::exists(&file, "legacy/data").unwrap());
+    }
+
+    #[test]
+    fn chunk_cache() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(1024)
+            .deflate(4)
+            .chunk_cache(1009, 64 << 20, 0.5)
+            .dtype::<u64>()
+            .create("data")
+            .unwrap();
+        let packets = (0..100_000u64).collect::<Vec<_>>();
+        table.append(&packets).unwrap();
+        assert_eq!(table.read_all::<u64>().unwrap(), packets);
+
+        let cache = table
+            .dataset()
+            .unwrap()
+            .access_plist()
+            .unwrap()
+            .chunk_cache();
+        assert_eq!(cache.nslots, 1009);
+        assert_eq!(cache.nbytes, 64 << 20);
+        assert_eq!(cache.w0, 0.5);
+    }
+
+    #[test]
+    fn chunk_auto() {
+        #[derive(H5Type, Clone, Copy)]
+        #[repr(C)]
+        struct Record {
+            id: u64,
+            values: [f64; 63],
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let chunk = |table: &PacketTable| table.create_plist().unwrap().chunk().unwrap();
+
+        assert_eq!(auto_chunk(4), 65536);
+        assert_eq!(auto_chunk(512), 2048);
+        assert_eq!(auto_chunk(1 << 20), 16);
+        assert_eq!(auto_chunk(0), 65536);
+
+        let table = PacketTable::builder(&file)
+            .chunk_auto()
+            .dtype::<i32>()
+            .create("scalar")
+            .unwrap();
+        assert_eq!(chunk(&table), [65536]);
+
+        let table = PacketTable::builder(&file)
+            .chunk_auto()
+            .dtype::<Record>()
+            .create("compound")
+            .unwrap();
+        assert_eq!(chunk(&table), [2048]);
+
+        let table = PacketTable::builder(&file)
+            .chunk_auto()
+            .dtype_as(TypeDescriptor::FixedArray(
+                Box::new(TypeDescriptor::Float(FloatSize::U8)),
+                1 << 14,
+            ))
+            .create("large")
+            .unwrap();
+        assert_eq!(chunk(&table), [16]);
+
+        let table = PacketTable::builder(&file)
+            .chunk_auto()
+            .chunk(32)
+            .dtype::<i32>()
+            .create("explicit")
+            .unwrap();
+        assert_eq!(chunk(&table), [32]);
+    }
+
+    #[test]
+    fn invalid_chunk() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let create = |builder: PacketTableBuilder, name: &str| {
+            let res = builder.dtype::<u32>().create(name);
+            assert!(!file.link_exists(name));
+            match res {
+                Err(Error::Builder(e)) => e,
+                _ => panic!("the chunk should be rejected"),
+            }
+        };
+
+        assert_eq!(
+            create(PacketTable::builder(&file), "missing"),
+            BuilderError::MissingChunk
+        );
+        let e = PacketTable::builder(&file)
+            .dtype::<u32>()
+            .create("missing")
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Either plist or chunk need to be set, or use chunk_auto."
+        );
+        let source = std::error::Error::source(&e).unwrap();
+        assert_eq!(
+            source.downcast_ref::<BuilderError>(),
+            Some(&BuilderError::MissingChunk)
+        );
+        let plist = DatasetCreate::build().finish().unwrap();
+        assert_eq!(
+            create(PacketTable::builder(&file).plist(plist), "missing"),
+            BuilderError::MissingChunk
+        );
+        assert_eq!(
+            create(PacketTable::builder(&file).chunk(0), "zero"),
+            BuilderError::ZeroChunk
+        );
+        assert_eq!(
+            create(PacketTable::builder(&file).chunk(1usize << 33), "large"),
+            BuilderError::ChunkTooLarge {
+                chunk: 1 << 33,
+                max: DEFAULT_MAX_CHUNK,
+            }
+        );
+        assert_eq!(
+            create(PacketTable::builder(&file).chunk(1usize << 32), "large"),
+            BuilderError::ChunkTooLarge {
+                chunk: 1 << 32,
+                max: DEFAULT_MAX_CHUNK,
+            }
+        );
+        assert_eq!(
+            create(PacketTable::builder(&file).chunk(64).max_chunk(16), "large"),
+            BuilderError::ChunkTooLarge { chunk: 64, max: 16 }
+        );
+        let plist = DatasetCreate::build().chunk((2, 2)).finish().unwrap();
+        assert_eq!(
+            create(PacketTable::builder(&file).plist(plist), "rank"),
+            BuilderError::InvalidPlistChunk { ndim: 2 }
+        );
+        let plist = DatasetCreate::build().chunk(16).finish().unwrap();
+        assert_eq!(
+            create(
+                PacketTable::builder(&file).plist(plist).chunk(32),
+                "mismatch"
+            ),
+            BuilderError::PlistChunkMismatch {
+                chunk: 32,
+                plist: 16,
+            }
+        );
+        #[allow(deprecated)]
+        let res = PacketTable::builder(&file)
+            .chunk(0)
+            .dtype::<u32>()
+            .create_fl("zero_fl", 0);
+        assert!(matches!(
+            res,
+            Err(Error::Builder(BuilderError::ZeroChunk))
+        ));
+
+        let plist = DatasetCreate::build().chunk(16).finish().unwrap();
+        let table = PacketTable::builder(&file)
+            .plist(plist)
+            .chunk(16)
+            .max_chunk(16)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        assert_eq!(table.create_plist().unwrap().chunk(), Some(vec![16]));
+    }
+
+    /// Fails after a successful append, and optionally fails the rollback.
+    struct Faulty<'a> {
+        table: &'a mut PacketTable,
+        fail_rollback: bool,
+    }
+
+    impl super::AppendTarget for Faulty<'_> {
+        fn num_packets(&self) -> Result<u64> {
+            self.table.num_packets()
+        }
+
+        fn append<T: H5Type>(&mut self, slice: &[T]) -> Result<()> {
+            self.table.append(slice)?;
+            Err(Error::Other("Injected append failure.".to_string()))
+        }
+
+        fn truncate(&mut self, len: u64) -> Result<()> {
+            if self.fail_rollback {
+                return Err(Error::Other("Injected rollback failure.".to_string()));
+            }
+            self.table.truncate(len)
+        }
+    }
+
+    #[test]
+    fn append_checked() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append_checked(&[1u32, 2, 3]).unwrap();
+        table.append_checked::<u32>(&[]).unwrap();
+        assert!(matches!(
+            table.append_checked(&[4u64, 5]),
+            Err(Error::PacketSize {
+                expected: 4,
+                actual: 8
+            })
+        ));
+        assert_eq!(table.num_packets().unwrap(), 3);
+        table.append_checked(&[4u32]).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3, 4]);
+
+        let mut faulty = Faulty {
+            table: &mut table,
+            fail_rollback: false,
+        };
+        assert!(matches!(
+            super::append_rollback(&mut faulty, &[5u32, 6]),
+            Err(Error::Other(_))
+        ));
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3, 4]);
+
+        let mut faulty = Faulty {
+            table: &mut table,
+            fail_rollback: true,
+        };
+        let res = super::append_rollback(&mut faulty, &[5u32, 6]);
+        assert!(matches!(
+            res,
+            Err(Error::Rollback { len: 4, error, rollback_error })
+                if matches!(*error, Error::Other(_)) && matches!(*rollback_error, Error::Other(_))
+        ));
+        assert_eq!(table.read_all::<u32>().unwrap(), [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn read_into_uninit() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append(&(0..10).collect::<Vec<i32>>()).unwrap();
+
+        let mut buf = [MaybeUninit::<i32>::uninit(); 6];
+        table.read_into_uninit(2, &mut buf).unwrap();
+        // SAFETY: read succeeded.
+        let arr = unsafe { buf.map(|val| val.assume_init()) };
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7]);
+
+        table.read_into_uninit(4, &mut buf).unwrap();
+        // SAFETY: read succeeded.
+        let arr = unsafe { buf.map(|val| val.assume_init()) };
+        assert_eq!(arr, [4, 5, 6, 7, 8, 9]);
+
+        assert!(table.read_into_uninit(5, &mut buf).is_err());
+        let mut wide = [MaybeUninit::<i64>::uninit(); 2];
+        assert!(matches!(
+            table.read_into_uninit(0, &mut wide),
+            Err(Error::PacketSize { .. })
+        ));
+    }
+
+    #[test]
+    fn is_writable() {
+        let file = NamedTempFile::new().unwrap();
+        {
+            let data = hdf5::File::create(file.path()).unwrap();
+            let mut table = PacketTable::builder(&data)
+                .chunk(4)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+            assert!(table.is_writable().unwrap());
+            table.append(&[1, 2, 3]).unwrap();
+        }
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let mut table = PacketTable::open(&data, "data").unwrap();
+        assert!(!table.is_writable().unwrap());
+        assert!(table.push(&4).is_err());
+        table.set_validation_profile(Some(ValidationProfile::Strict));
+        let e = table.push(&4).unwrap_err();
+        assert!(matches!(&e, Error::ReadOnly { path } if path == "/data"));
+        assert_eq!(table.read_all::<i32>().unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn read_to_ptr() {
+        #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+        #[repr(C)]
+        struct Pair {
+            id: u16,
+            value: f32,
+        }
+
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Pair>()
+            .create("data")
+            .unwrap();
+        let packets = (0..10u16)
+            .map(|i| Pair {
+                id: i,
+                value: i as f32 * 0.5,
+            })
+            .collect::<Vec<_>>();
+        table.append(&packets).unwrap();
+
+        let mut boxed = Box::<[Pair]>::new_uninit_slice(7);
+        // SAFETY: the boxed slice is valid for 7 packets.
+        unsafe {
+            table
+                .read_to_ptr(3, boxed.len(), boxed.as_mut_ptr() as *mut Pair)
+                .unwrap();
+        }
+        // SAFETY: read succeeded.
+        let boxed = unsafe { boxed.assume_init() };
+        assert_eq!(&*boxed, &packets[3..]);
+
+        let mut small = Box::<[u8]>::new_uninit_slice(1);
+        // SAFETY: the packet size check fails before writing.
+        let res = unsafe { table.read_to_ptr(0, 1, small.as_mut_ptr() as *mut u8) };
+        assert!(matches!(res, Err(Error::PacketSize { .. })));
+    }
+
+    #[test]
+    fn iter_varlen() {
+        let file = NamedTempFile::new().unwrap();
+        let manifest = fixtures::standard_file(file.path(), 114514).unwrap();
+        let entry = manifest.get(fixtures::VARLEN_TABLE).unwrap();
+        let file = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&file, fixtures::VARLEN_TABLE).unwrap();
+
+        let records = table
+            .iter_varlen::<i32>()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(records.len(), entry.count);
+        let bytes = records
+            .iter()
+            .flatten()
+            .flat_map(|p| p.to_ne_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(fixtures::checksum(&bytes), entry.checksum);
+        for _ in 0..10 {
+            let read = table
+                .iter_varlen::<i32>()
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(read, records);
+        }
+        assert_eq!(
+            table
+                .iter_varlen::<i32>()
+                .unwrap()
+                .nth(18)
+                .unwrap()
+                .unwrap(),
+            records[18]
+        );
+        assert_eq!(
+            table.read::<VarLenArray<i32>>(0, 1).unwrap(),
+            [VarLenArray::<i32>::from_slice(&records[0])]
+        );
+
+        assert!(matches!(
+            table.iter_varlen::<i64>(),
+            Err(Error::SchemaMismatch { .. })
+        ));
+        let fixed = PacketTable::open(&file, fixtures::I32_TABLE).unwrap();
+        assert!(fixed.iter_varlen::<i32>().is_err());
+    }
+
+    #[test]
+    fn append_empty() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        table.append::<i32>(&[]).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 0);
+        table.append(&[1, 1, 4]).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 3);
+        assert_eq!(table.read::<i32>(0, 3).unwrap(), &[1, 1, 4]);
+    }
 }
@@ -0,0 +1,165 @@
+use super::fields::find_member;
+use crate::*;
+use hdf5::{
+    types::{CompoundField, CompoundType, TypeDescriptor},
+    Datatype, H5Type,
+};
+use std::ops::{Add, Range};
+
+/// The aggregate of a compound member over a range of packets,
+/// returned by [`PacketTable::aggregate_field`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAggregate<F> {
+    /// The examined range after clamping to the table.
+    pub range: Range<u64>,
+    /// Number of packets examined.
+    pub count: u64,
+    /// The sum of the values, or `None` if no packet is examined.
+    pub sum: Option<F>,
+    /// The minimum value, or `None` if no packet is examined.
+    pub min: Option<F>,
+    /// The maximum value, or `None` if no packet is examined.
+    pub max: Option<F>,
+}
+
+fn is_numeric(ty: &TypeDescriptor) -> bool {
+    matches!(
+        ty,
+        TypeDescriptor::Integer(_) | TypeDescriptor::Unsigned(_) | TypeDescriptor::Float(_)
+    )
+}
+
+impl PacketTable {
+    /// Aggregate a numeric compound member over the range of packets.
+    ///
+    /// Only the member is read, converted to `F` by HDF5, in chunk-sized batches,
+    /// so the memory usage is bounded. The range is clamped to the table.
+    /// The sum is computed with [`Add`] of `F`, and may overflow for integers.
+    pub fn aggregate_field<F: H5Type + PartialOrd + Copy + Add<Output = F>>(
+        &self,
+        field: &str,
+        range: Range<u64>,
+    ) -> Result<FieldAggregate<F>> {
+        let compound = self.compound_type()?;
+        let member = find_member(&compound, field)?;
+        if !is_numeric(&member.ty) || !is_numeric(&F::type_descriptor()) {
+            return Err(Error::Other(format!("The member {field} is not numeric.")));
+        }
+        let mem_type = Datatype::from_descriptor(&TypeDescriptor::Compound(CompoundType {
+            fields: vec![CompoundField::new(field, F::type_descriptor(), 0, 0)],
+            size: size_of::<F>(),
+        }))?;
+
+        let num = self.num_packets()?;
+        let end = range.end.min(num);
+        let start = range.start.min(end);
+        let mut aggregate = FieldAggregate {
+            range: start..end,
+            count: end - start,
+            sum: None,
+            min: None,
+            max: None,
+        };
+        let dset = self.dataset()?;
+        let batch = self.batch_len()?;
+        let mut buffer = Vec::<F>::with_capacity(batch);
+        let mut reporter = self.reporter("aggregate_field", Some(end - start));
+        let mut index = start;
+        while index < end {
+            self.check_cancelled(index - start)?;
+            let count = (end - index).min(batch as u64) as usize;
+            Self::read_converted(
+                &dset,
+                &mem_type,
+                index,
+                count,
+                buffer.as_mut_ptr() as *mut _,
+            )?;
+            // SAFETY: read succeeded, and F is Copy.
+            unsafe {
+                buffer.set_len(count);
+            }
+            for &v in &buffer {
+                aggregate.sum = Some(aggregate.sum.map_or(v, |sum| sum + v));
+                if aggregate.min.is_none_or(|min| v < min) {
+                    aggregate.min = Some(v);
+                }
+                if aggregate.max.is_none_or(|max| v > max) {
+                    aggregate.max = Some(v);
+                }
+            }
+            index += count as u64;
+            reporter.update(index - start, (index - start) * size_of::<F>() as u64);
+        }
+        reporter.finish(end - start, (end - start) * size_of::<F>() as u64);
+        Ok(aggregate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use hdf5::{types::FixedAscii, H5Type};
+    use tempfile::NamedTempFile;
+
+    #[derive(H5Type, Clone, Copy)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        value: f64,
+        name: FixedAscii<8>,
+    }
+
+    fn naive(records: &[Record]) -> (u64, f64, f64, f64) {
+        let values = records.iter().map(|r| r.value);
+        (
+            records.len() as u64,
+            values.clone().sum(),
+            values.clone().fold(f64::INFINITY, f64::min),
+            values.fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    #[test]
+    fn aggregate() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(8)
+            .dtype::<Record>()
+            .create("data")
+            .unwrap();
+        let records = (0..100u32)
+            .map(|id| Record {
+                id,
+                value: ((id * 37) % 101) as f64 - 50.0,
+                name: FixedAscii::from_ascii(b"r").unwrap(),
+            })
+            .collect::<Vec<_>>();
+        table.append(&records).unwrap();
+
+        for (range, expected) in [(0..100, 0..100), (10..35, 10..35), (90..200, 90..100)] {
+            let aggregate = table.aggregate_field::<f64>("value", range).unwrap();
+            assert_eq!(aggregate.range, expected);
+            let (count, sum, min, max) =
+                naive(&records[expected.start as usize..expected.end as usize]);
+            assert_eq!(aggregate.count, count);
+            assert_eq!(aggregate.sum, Some(sum));
+            assert_eq!(aggregate.min, Some(min));
+            assert_eq!(aggregate.max, Some(max));
+        }
+
+        let aggregate = table.aggregate_field::<u64>("id", 0..100).unwrap();
+        assert_eq!(aggregate.sum, Some(4950));
+        assert_eq!(aggregate.min, Some(0));
+        assert_eq!(aggregate.max, Some(99));
+
+        let empty = table.aggregate_field::<f64>("value", 200..300).unwrap();
+        assert_eq!(empty.range, 100..100);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.sum, None);
+
+        assert!(table.aggregate_field::<f64>("name", 0..100).is_err());
+        assert!(table.aggregate_field::<f64>("missing", 0..100).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::*;
+use hdf5::{
+    from_id, h5try,
+    types::{TypeDescriptor, VarLenUnicode},
+    Attribute, Dataset, Dataspace, Datatype, H5Type,
+};
+use hdf5_sys::{
+    h5a::{H5Acreate2, H5Awrite},
+    h5p::H5P_DEFAULT,
+};
+use std::{ffi::CString, mem::MaybeUninit};
+
+/// An attribute value collected by the builder, written when the table is created.
+#[derive(Clone)]
+pub(crate) enum AttrValue {
+    /// The bytes of a value without pointers.
+    Raw(TypeDescriptor, Box<[MaybeUninit<u8>]>),
+    /// A variable length string.
+    Str(String),
+}
+
+impl AttrValue {
+    pub fn new<T: H5Type>(val: T) -> Self {
+        let (desc, bytes) = super::raw::value_bytes(&val);
+        Self::Raw(desc, bytes)
+    }
+
+    /// Check the value before the dataset is created, rejecting the types containing pointers.
+    pub fn check(&self, name: &str) -> Result<()> {
+        match self {
+            Self::Raw(desc, _) if super::raw::contains_pointers(desc) => Err(Error::Other(
+                format!("The attribute {name} contains pointers, which are not supported."),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Create the scalar attribute on the dataset and write the value.
+    pub fn write(&self, dset: &Dataset, name: &str) -> Result<()> {
+        let c_name = CString::new(name)?;
+        let space = Dataspace::try_new(())?;
+        let write = |dtype: &Datatype, ptr: *const ()| -> Result<()> {
+            let attr = h5try!(H5Acreate2(
+                dset.id(),
+                c_name.as_ptr(),
+                dtype.id(),
+                space.id(),
+                H5P_DEFAULT,
+                H5P_DEFAULT
+            ));
+            let attr = unsafe { from_id::<Attribute>(attr) }?;
+            h5try!(H5Awrite(attr.id(), dtype.id(), ptr as *const _));
+            Ok(())
+        };
+        match self {
+            Self::Raw(desc, bytes) => {
+                self.check(name)?;
+                write(
+                    &Datatype::from_descriptor(desc)?,
+                    bytes.as_ptr() as *const _,
+                )
+            }
+            Self::Str(s) => {
+                let s = s
+                    .parse::<VarLenUnicode>()
+                    .map_err(|e| Error::Other(format!("Invalid attribute {name}: {e}")))?;
+                write(
+                    &Datatype::from_type::<VarLenUnicode>()?,
+                    &s as *const VarLenUnicode as *const _,
+                )
+            }
+        }
+    }
+}
@@ -1,17 +1,22 @@
 use crate::*;
 use dst_container::*;
-use hdf5::Result;
-use std::ptr::Pointee;
+use hdf5_dst::H5TypeUnsized;
+use std::{io, ptr::Pointee};
 
 /// A [`PacketTable`] writer with buffer.
-pub struct PacketTableBufWriter<'a, T: ?Sized> {
+///
+/// The buffered records are flushed when the writer is dropped,
+/// but the errors are ignored. Call [`PacketTableBufWriter::flush`] to handle them.
+pub struct PacketTableBufWriter<'a, T: ?Sized + H5TypeUnsized> {
     table: &'a mut PacketTable,
-    buffer: FixedVec<T>,
+    buffer: PooledVec<'a, T>,
     buf_len: usize,
+    on_flush: Option<Box<dyn FnMut(usize) + 'a>>,
 }
 
-impl<'a, T: ?Sized> PacketTableBufWriter<'a, T> {
+impl<'a, T: ?Sized + H5TypeUnsized> PacketTableBufWriter<'a, T> {
     /// Create a new [`PacketTableBufWriter`] with metadata and buffer length.
+    /// A buffer length of 0 writes through, flushing after every push or write.
     pub fn new_unsized(
         table: &'a mut PacketTable,
         metadata: <T as Pointee>::Metadata,
@@ -19,28 +24,66 @@ impl<'a, T: ?Sized> PacketTableBufWriter<'a, T> {
     ) -> Self {
         Self {
             table,
-            buffer: FixedVec::with_capacity(metadata, buf_len),
+            buffer: PooledVec::unpooled(metadata, buf_len),
             buf_len,
+            on_flush: None,
         }
     }
 
     /// Create a new [`PacketTableBufWriter`] with metadata and buffer length.
-    /// The metadata is obtained from the provided pointer.
-    pub fn new_unsized_like(table: &'a mut PacketTable, ptr: *const T, buf_len: usize) -> Self {
+    /// The buffer is drawn from the pool, and returned when the writer is dropped.
+    pub fn new_pooled(
+        table: &'a mut PacketTable,
+        pool: &'a StagingPool<T>,
+        metadata: <T as Pointee>::Metadata,
+        buf_len: usize,
+    ) -> Self {
         Self {
             table,
-            buffer: FixedVec::with_capacity_like(ptr, buf_len),
+            buffer: pool.acquire(metadata, buf_len),
             buf_len,
+            on_flush: None,
         }
     }
 
+    /// Create a new [`PacketTableBufWriter`] with metadata and buffer length.
+    /// The metadata is obtained from the provided pointer.
+    pub fn new_unsized_like(table: &'a mut PacketTable, ptr: *const T, buf_len: usize) -> Self {
+        Self::new_unsized(table, ptr.to_raw_parts().1, buf_len)
+    }
+
     /// Force flush the buffer.
-    pub fn flush(&mut self) -> Result<()> {
-        if !self.buffer.is_empty() {
+    /// Returns the number of records written.
+    pub fn flush(&mut self) -> Result<usize> {
+        let len = self.buffer.len();
+        if len > 0 {
             self.table.append_unsized(&self.buffer)?;
             self.buffer.clear();
+            if let Some(f) = &mut self.on_flush {
+                f(len);
+            }
         }
-        Ok(())
+        Ok(len)
+    }
+
+    /// Set the callback invoked with the number of records written
+    /// after each flush which writes records, including the one on drop.
+    /// The previous callback is replaced.
+    pub fn on_flush(&mut self, f: impl FnMut(usize) + 'a) {
+        self.on_flush = Some(Box::new(f));
+    }
+
+    /// Set the buffer length.
+    /// The buffer is flushed immediately if it reaches the new length.
+    ///
+    /// A buffer length of 0 is rejected with [`Error::ZeroBufLen`],
+    /// and the buffer length is not changed.
+    pub fn set_buf_len(&mut self, buf_len: usize) -> Result<()> {
+        if buf_len == 0 {
+            return Err(Error::ZeroBufLen);
+        }
+        self.buf_len = buf_len;
+        self.check_and_flush()
     }
 
     fn check_and_flush(&mut self) -> Result<()> {
@@ -73,7 +116,7 @@ impl<'a, T: ?Sized> PacketTableBufWriter<'a, T> {
     }
 }
 
-impl<'a, T> PacketTableBufWriter<'a, T> {
+impl<'a, T: H5TypeUnsized> PacketTableBufWriter<'a, T> {
     /// Create a new [`PacketTableBufWriter`] with buffer length.
     pub fn new(table: &'a mut PacketTable, buf_len: usize) -> Self {
         Self::new_unsized(table, (), buf_len)
@@ -90,8 +133,318 @@ impl<'a, T> PacketTableBufWriter<'a, T> {
     }
 }
 
-impl<T: ?Sized> Drop for PacketTableBufWriter<'_, T> {
+impl io::Write for PacketTableBufWriter<'_, u8> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buffer.len() >= self.buf_len {
+            io::Write::flush(self)?;
+        }
+        let len = match self.buf_len {
+            0 => buf.len(),
+            buf_len => buf.len().min(buf_len - self.buffer.len()),
+        };
+        for &b in &buf[..len] {
+            // SAFETY: we are sure the value is initialized.
+            unsafe {
+                self.buffer.push_with(|uninit| {
+                    uninit.write(b);
+                })
+            };
+        }
+        if self.buf_len == 0 {
+            io::Write::flush(self)?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        PacketTableBufWriter::flush(self)
+            .map(|_| ())
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+impl PacketTable {
+    /// Create a byte writer for packet tables of `u8`.
+    /// The bytes are appended when the buffer is full or flushed.
+    pub fn byte_writer(&mut self) -> PacketTableBufWriter<'_, u8> {
+        PacketTableBufWriter::new(self, 4096)
+    }
+}
+
+impl<T: ?Sized + H5TypeUnsized> Drop for PacketTableBufWriter<'_, T> {
+    /// The errors of flushing are ignored.
+    /// Call [`PacketTableBufWriter::flush`] to handle them.
     fn drop(&mut self) {
-        self.flush().unwrap();
+        self.flush().ok();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_alloc::ALLOCS, *};
+    use std::{
+        cell::{Cell, RefCell},
+        io::Write,
+    };
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn no_alloc() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let vec = (0..64).collect::<Vec<i32>>();
+        // Warm up the lazily initialized states.
+        table.append(&vec).unwrap();
+        table.push(&0).unwrap();
+
+        let allocs = ALLOCS.get();
+        table.append(&vec).unwrap();
+        table.push(&0).unwrap();
+        assert_eq!(ALLOCS.get(), allocs);
+
+        let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 16);
+        let allocs = ALLOCS.get();
+        for i in 0..1024 {
+            writer.push(i).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(ALLOCS.get(), allocs);
+
+        // The descriptor of a compound allocates, so it should be checked only once.
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<fixtures::FixtureRecord>()
+            .create("compound")
+            .unwrap();
+        let records = (0..64)
+            .map(|id| fixtures::FixtureRecord {
+                id,
+                value: id as f64,
+            })
+            .collect::<Vec<_>>();
+        table.append(&records).unwrap();
+        table.push(&records[0]).unwrap();
+
+        let allocs = ALLOCS.get();
+        table.append(&records).unwrap();
+        table.push(&records[0]).unwrap();
+        assert_eq!(ALLOCS.get(), allocs);
+
+        let mut writer = PacketTableBufWriter::<fixtures::FixtureRecord>::new(&mut table, 16);
+        let allocs = ALLOCS.get();
+        for record in records.iter().cycle().take(1024) {
+            writer.push(*record).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(ALLOCS.get(), allocs);
+    }
+
+    #[test]
+    fn flush_count() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let flushed = RefCell::new(vec![]);
+        {
+            let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 4);
+            writer.on_flush(|len| flushed.borrow_mut().push(len));
+            for i in 0..10 {
+                writer.push(i).unwrap();
+            }
+            // The automatic flushes at the buffer length.
+            assert_eq!(*flushed.borrow(), [4, 4]);
+            assert_eq!(writer.flush().unwrap(), 2);
+            assert_eq!(writer.flush().unwrap(), 0);
+        }
+        assert_eq!(*flushed.borrow(), [4, 4, 2]);
+        assert_eq!(flushed.borrow().iter().sum::<usize>(), 10);
+        assert_eq!(table.num_packets().unwrap(), 10);
+    }
+
+    #[test]
+    fn auto_flush() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let observer = PacketTable::open(&data, "data").unwrap();
+        {
+            let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 4);
+            let mut counts = vec![];
+            for i in 0..10 {
+                writer.push(i).unwrap();
+                counts.push(observer.num_packets().unwrap());
+            }
+            assert_eq!(counts, [0, 0, 0, 4, 4, 4, 4, 8, 8, 8]);
+        }
+        assert_eq!(observer.num_packets().unwrap(), 10);
+    }
+
+    #[test]
+    fn on_flush() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let total = Cell::new(0);
+        let replaced = Cell::new(0);
+        {
+            let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 4);
+            writer.on_flush(|len| replaced.set(replaced.get() + len));
+            writer.on_flush(|len| total.set(total.get() + len));
+            for i in 0..10 {
+                writer.push(i).unwrap();
+            }
+            assert_eq!(total.get(), 8);
+            writer.flush().unwrap();
+            assert_eq!(total.get(), 10);
+            assert_eq!(writer.flush().unwrap(), 0);
+            for i in 0..3 {
+                writer.push(i).unwrap();
+            }
+        }
+        assert_eq!(total.get(), 13);
+        assert_eq!(replaced.get(), 0);
+        assert_eq!(table.num_packets().unwrap(), 13);
+    }
+
+    #[test]
+    fn set_buf_len() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 16);
+        for i in 0..6 {
+            writer.push(i).unwrap();
+        }
+        writer.set_buf_len(8).unwrap();
+        assert_eq!(writer.flush().unwrap(), 6);
+        for i in 0..6 {
+            writer.push(i).unwrap();
+        }
+        writer.set_buf_len(4).unwrap();
+        assert_eq!(writer.flush().unwrap(), 0);
+        writer.push(6).unwrap();
+        assert!(matches!(writer.set_buf_len(0), Err(Error::ZeroBufLen)));
+        // The buffer length is not changed.
+        for i in 7..10 {
+            writer.push(i).unwrap();
+        }
+        assert_eq!(writer.flush().unwrap(), 0);
+        drop(writer);
+        assert_eq!(table.num_packets().unwrap(), 16);
+    }
+
+    #[test]
+    fn drop_error() {
+        let file = NamedTempFile::new().unwrap();
+        {
+            let data = hdf5::File::create(file.path()).unwrap();
+            PacketTable::builder(&data)
+                .chunk(16)
+                .dtype::<i32>()
+                .create("data")
+                .unwrap();
+        }
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let mut table = PacketTable::open(&data, "data").unwrap();
+        let mut writer = PacketTableBufWriter::<i32>::new(&mut table, 16);
+        writer.push(1).unwrap();
+        assert!(writer.flush().is_err());
+        // The failed flush on drop doesn't panic.
+        drop(writer);
+        assert_eq!(table.num_packets().unwrap(), 0);
+    }
+
+    #[test]
+    fn write_through() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<u8>()
+            .create("data")
+            .unwrap();
+        let mut writer = PacketTableBufWriter::<u8>::new(&mut table, 0);
+        writer.write_all(b"hello").unwrap();
+        assert_eq!(writer.flush().unwrap(), 0);
+        writer.push(b'!').unwrap();
+        assert_eq!(writer.flush().unwrap(), 0);
+        drop(writer);
+        assert_eq!(table.read_all::<u8>().unwrap(), b"hello!");
+    }
+
+    #[test]
+    fn pooled() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<i32>()
+            .create("data")
+            .unwrap();
+        let pool = StagingPool::<i32>::new(1);
+        for _ in 0..4 {
+            let mut writer = PacketTableBufWriter::new_pooled(&mut table, &pool, (), 16);
+            for i in 0..10 {
+                writer.push(i).unwrap();
+            }
+        }
+        assert_eq!(pool.len(), 1);
+        assert_eq!(table.num_packets().unwrap(), 40);
+
+        let allocs = ALLOCS.get();
+        let mut writer = PacketTableBufWriter::new_pooled(&mut table, &pool, (), 16);
+        for i in 0..10 {
+            writer.push(i).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(ALLOCS.get(), allocs);
+    }
+
+    #[test]
+    fn byte_writer() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(16)
+            .dtype::<u8>()
+            .create("data")
+            .unwrap();
+        let bytes = (0..10000).map(|i| i as u8).collect::<Vec<_>>();
+        {
+            let mut writer = table.byte_writer();
+            writer.write_all(&bytes).unwrap();
+            Write::flush(&mut writer).unwrap();
+        }
+        assert_eq!(table.read_all::<u8>().unwrap(), bytes);
     }
 }
@@ -0,0 +1,188 @@
+use crate::*;
+use hdf5::{
+    h5try,
+    types::{CompoundField, CompoundType, TypeDescriptor},
+    Datatype, H5Type,
+};
+use hdf5_sys::h5a::{H5Adelete, H5Aread};
+use std::{ffi::CString, mem::MaybeUninit, ops::RangeBounds};
+
+const DEFAULT_PREFIX: &str = "ext:default:";
+
+impl PacketTable {
+    /// Get the type of the compound field, or `None` if the field doesn't exist.
+    fn field_type(&self, field: &str) -> Result<Option<TypeDescriptor>> {
+        match self.type_descriptor()? {
+            TypeDescriptor::Compound(ty) => Ok(ty
+                .fields
+                .into_iter()
+                .find(|f| f.name == field)
+                .map(|f| f.ty)),
+            _ => Err(Error::Other(
+                "The packet type is not a compound.".to_string(),
+            )),
+        }
+    }
+
+    /// Store the default value of a compound field as an attribute `ext:default:<field>`.
+    ///
+    /// The field may be missing in the stored type, e.g., a member added after
+    /// the table was written, see [`Self::read_compat`]. If it exists,
+    /// the type of the value should match the field type.
+    pub fn set_field_default<F: H5Type>(&self, field: &str, value: F) -> Result<()> {
+        let value_ty = <F as H5Type>::type_descriptor();
+        if let Some(field_ty) = self.field_type(field)?
+            && field_ty != value_ty
+        {
+            return Err(Error::Other(format!(
+                "Type mismatch for field {field}: expected {field_ty:?}, got {value_ty:?}."
+            )));
+        }
+        let dataset = self.dataset()?;
+        let name = format!("{DEFAULT_PREFIX}{field}");
+        if dataset.attr_names()?.contains(&name) {
+            let c_name = CString::new(name.as_str())?;
+            h5try!(H5Adelete(dataset.id(), c_name.as_ptr()));
+        }
+        dataset
+            .new_attr::<F>()
+            .create(name.as_str())?
+            .write_scalar(&value)?;
+        Ok(())
+    }
+
+    /// Read the stored default value of a compound field.
+    pub fn field_default<F: H5Type>(&self, field: &str) -> Result<Option<F>> {
+        let dataset = self.dataset()?;
+        let name = format!("{DEFAULT_PREFIX}{field}");
+        if dataset.attr_names()?.contains(&name) {
+            Ok(Some(dataset.attr(&name)?.read_scalar()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// List the compound fields with stored default values.
+    pub fn field_defaults(&self) -> Result<Vec<String>> {
+        Ok(self
+            .dataset()?
+            .attr_names()?
+            .into_iter()
+            .filter_map(|name| name.strip_prefix(DEFAULT_PREFIX).map(|s| s.to_string()))
+            .collect())
+    }
+
+    /// Read the bytes of the stored default value of a member.
+    /// The stored type should equal the member type.
+    fn stored_default(&self, field: &CompoundField) -> Result<Box<[MaybeUninit<u8>]>> {
+        let dataset = self.dataset()?;
+        let name = format!("{DEFAULT_PREFIX}{}", field.name);
+        if !dataset.attr_names()?.contains(&name) {
+            return Err(Error::Other(format!(
+                "The member {} is missing, and has no default.",
+                field.name
+            )));
+        }
+        let attr = dataset.attr(&name)?;
+        let mem_type = Datatype::from_descriptor(&field.ty)?;
+        let stored = attr.dtype()?;
+        if stored != mem_type {
+            return Err(Error::SchemaMismatch {
+                expected: Box::new(field.ty.clone()),
+                actual: Box::new(stored.to_descriptor()?),
+            });
+        }
+        let mut bytes = vec![MaybeUninit::<u8>::uninit(); field.ty.size()].into_boxed_slice();
+        h5try!(H5Aread(
+            attr.id(),
+            mem_type.id(),
+            bytes.as_mut_ptr() as *mut _
+        ));
+        Ok(bytes)
+    }
+
+    /// Read the packets in the range as a compound `T`, whose members
+    /// may be missing in the stored type, e.g., a table written by an older schema.
+    ///
+    /// The stored members are read like [`Self::read_fields`]. A missing member
+    /// takes its value from `defaults` if provided, otherwise from the default stored by
+    /// [`Self::set_field_default`]. It fails if a missing member has neither,
+    /// or contains pointers.
+    pub fn read_compat<T: H5Type>(
+        &self,
+        range: impl RangeBounds<u64>,
+        defaults: Option<&T>,
+    ) -> Result<Vec<T>> {
+        let compound = self.compound_type()?;
+        let TypeDescriptor::Compound(target) = <T as H5Type>::type_descriptor() else {
+            return Err(Error::Other(
+                "The compat type should be a compound.".to_string(),
+            ));
+        };
+        let (stored, missing): (Vec<_>, Vec<_>) = target
+            .fields
+            .into_iter()
+            .partition(|f| compound.fields.iter().any(|s| s.name == f.name));
+        let mut patches = Vec::with_capacity(missing.len());
+        for field in &missing {
+            if super::raw::contains_pointers(&field.ty) {
+                return Err(Error::Other(format!(
+                    "The default of {} contains pointers, which is not supported.",
+                    field.name
+                )));
+            }
+            let bytes = match defaults {
+                Some(val) => {
+                    // SAFETY: the member is in the bounds of the value,
+                    // and the bytes are copied as uninitialized, because of the padding.
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(
+                            (val as *const T as *const MaybeUninit<u8>).add(field.offset),
+                            field.ty.size(),
+                        )
+                    };
+                    bytes.into()
+                }
+                None => self.stored_default(field)?,
+            };
+            patches.push((field.offset, bytes));
+        }
+        let (start, end) = self.resolve_range(range)?;
+        let len = (end - start) as usize;
+        let mut vec = Vec::<T>::with_capacity(len);
+        if len > 0 {
+            if !stored.is_empty() {
+                let mem_type =
+                    Datatype::from_descriptor(&TypeDescriptor::Compound(CompoundType {
+                        fields: stored,
+                        size: size_of::<T>(),
+                    }))?;
+                Self::read_converted(
+                    &self.dataset()?,
+                    &mem_type,
+                    start,
+                    len,
+                    vec.as_mut_ptr() as *mut _,
+                )?;
+            }
+            let base = vec.as_mut_ptr() as *mut MaybeUninit<u8>;
+            for i in 0..len {
+                for (offset, bytes) in &patches {
+                    // SAFETY: the member is in the bounds of the packet.
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(
+                            bytes.as_ptr(),
+                            base.add(i * size_of::<T>() + offset),
+                            bytes.len(),
+                        )
+                    };
+                }
+            }
+            // SAFETY: all the members are read or copied.
+            unsafe {
+                vec.set_len(len);
+            }
+        }
+        Ok(vec)
+    }
+}
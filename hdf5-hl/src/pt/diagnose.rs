@@ -0,0 +1,128 @@
+use crate::*;
+use hdf5::types::TypeDescriptor;
+
+/// The suggested remedy of an [`OpenDiagnosis`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum OpenRemedy {
+    /// The packet table opens successfully, nothing to do.
+    Openable,
+    /// The link doesn't exist.
+    NotFound,
+    /// The object is not a dataset.
+    NotDataset,
+    /// The dataset is not chunked. Copy the packets into a new packet table.
+    NotChunked,
+    /// The dataset is not 1-dimensional.
+    WrongRank(usize),
+    /// The dataset looks fine, but HDF5 refuses to open it.
+    Unknown(Error),
+}
+
+/// The result of [`PacketTable::diagnose_open_failure`].
+#[derive(Debug)]
+pub struct OpenDiagnosis {
+    /// Whether the link exists.
+    pub exists: bool,
+    /// Whether the dataset is chunked, if it is a dataset.
+    pub chunked: Option<bool>,
+    /// The rank of the dataset, if it is a dataset.
+    pub rank: Option<usize>,
+    /// The datatype of the dataset, if it could be described.
+    pub dtype: Option<TypeDescriptor>,
+    /// The suggested remedy.
+    pub remedy: OpenRemedy,
+}
+
+impl PacketTable {
+    /// Inspect why [`PacketTable::open`] fails on the specified object.
+    ///
+    /// The object is inspected as a plain dataset. If it looks like a packet table,
+    /// the error of [`PacketTable::open`] is reported in [`OpenRemedy::Unknown`].
+    pub fn diagnose_open_failure(
+        loc: &impl AsGroup,
+        name: impl AsRef<str>,
+    ) -> Result<OpenDiagnosis> {
+        let group = loc.as_group();
+        let name = name.as_ref();
+        let mut diagnosis = OpenDiagnosis {
+            exists: group.link_exists(name),
+            chunked: None,
+            rank: None,
+            dtype: None,
+            remedy: OpenRemedy::NotFound,
+        };
+        if !diagnosis.exists {
+            return Ok(diagnosis);
+        }
+        let Ok(dset) = group.dataset(name) else {
+            diagnosis.remedy = OpenRemedy::NotDataset;
+            return Ok(diagnosis);
+        };
+        let chunked = dset.is_chunked();
+        let rank = dset.ndim();
+        diagnosis.chunked = Some(chunked);
+        diagnosis.rank = Some(rank);
+        diagnosis.dtype = dset.dtype().and_then(|ty| ty.to_descriptor()).ok();
+        diagnosis.remedy = if !chunked {
+            OpenRemedy::NotChunked
+        } else if rank != 1 {
+            OpenRemedy::WrongRank(rank)
+        } else {
+            match Self::open(group, name) {
+                Ok(_) => OpenRemedy::Openable,
+                Err(e) => OpenRemedy::Unknown(e),
+            }
+        };
+        Ok(diagnosis)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use hdf5::H5Type;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn diagnose() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        data.create_group("group").unwrap();
+        data.new_dataset::<i32>()
+            .chunk((2, 2))
+            .shape((4, 4))
+            .create("matrix")
+            .unwrap();
+        data.new_dataset::<i32>()
+            .shape(4)
+            .create("contiguous")
+            .unwrap();
+        data.new_packet_table()
+            .chunk(16)
+            .dtype::<i32>()
+            .create("table")
+            .unwrap();
+
+        let diagnosis = PacketTable::diagnose_open_failure(&data, "missing").unwrap();
+        assert!(!diagnosis.exists);
+        assert!(matches!(diagnosis.remedy, OpenRemedy::NotFound));
+
+        let diagnosis = PacketTable::diagnose_open_failure(&data, "group").unwrap();
+        assert!(diagnosis.exists);
+        assert!(matches!(diagnosis.remedy, OpenRemedy::NotDataset));
+
+        let diagnosis = PacketTable::diagnose_open_failure(&data, "matrix").unwrap();
+        assert_eq!(diagnosis.rank, Some(2));
+        assert!(matches!(diagnosis.remedy, OpenRemedy::WrongRank(2)));
+
+        let diagnosis = PacketTable::diagnose_open_failure(&data, "contiguous").unwrap();
+        assert_eq!(diagnosis.chunked, Some(false));
+        assert_eq!(diagnosis.dtype, Some(i32::type_descriptor()));
+        assert!(matches!(diagnosis.remedy, OpenRemedy::NotChunked));
+
+        let diagnosis = PacketTable::diagnose_open_failure(&data, "table").unwrap();
+        assert!(matches!(diagnosis.remedy, OpenRemedy::Openable));
+    }
+}
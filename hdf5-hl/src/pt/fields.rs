@@ -0,0 +1,166 @@
+use crate::*;
+use hdf5::{
+    h5try,
+    types::{CompoundField, CompoundType, TypeDescriptor},
+    Dataset, Dataspace, Datatype, H5Type,
+};
+use hdf5_sys::{h5d::H5Dread, h5p::H5P_DEFAULT};
+use std::{ffi::c_void, ops::RangeBounds};
+
+/// Find the member by name, or list the available ones in the error.
+pub(crate) fn find_member<'a>(compound: &'a CompoundType, name: &str) -> Result<&'a CompoundField> {
+    compound
+        .fields
+        .iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| {
+            let available = compound
+                .fields
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Error::Other(format!(
+                "No member named {name}. Available members: {available}."
+            ))
+        })
+}
+
+impl PacketTable {
+    /// Get the stored compound type.
+    pub(crate) fn compound_type(&self) -> Result<CompoundType> {
+        match self.type_descriptor()? {
+            TypeDescriptor::Compound(compound) => Ok(compound),
+            _ => Err(Error::Other(
+                "Only compound packets have fields.".to_string(),
+            )),
+        }
+    }
+
+    /// Read `len` packets from `start` of the dataset into `ptr`, converted to `mem_type`.
+    ///
+    /// HDF5 converts the compound members by name, so `mem_type` may select a subset.
+    pub(crate) fn read_converted(
+        dset: &Dataset,
+        mem_type: &Datatype,
+        start: u64,
+        len: usize,
+        ptr: *mut c_void,
+    ) -> Result<()> {
+        let file_space = dset.space()?.select(start as usize..start as usize + len)?;
+        let mem_space = Dataspace::try_new(len)?;
+        h5try!(H5Dread(
+            dset.id(),
+            mem_type.id(),
+            mem_space.id(),
+            file_space.id(),
+            H5P_DEFAULT,
+            ptr
+        ));
+        Ok(())
+    }
+
+    /// Read the packets in the range, projected onto a compound `T`
+    /// whose members are a subset of the stored ones.
+    ///
+    /// Only the members of `T` are read, matched by name and converted by HDF5.
+    pub fn read_fields<T: H5Type>(&self, range: impl RangeBounds<u64>) -> Result<Vec<T>> {
+        let compound = self.compound_type()?;
+        let TypeDescriptor::Compound(projected) = T::type_descriptor() else {
+            return Err(Error::Other(
+                "The projected type should be a compound.".to_string(),
+            ));
+        };
+        for field in &projected.fields {
+            find_member(&compound, &field.name)?;
+        }
+        let (start, end) = self.resolve_range(range)?;
+        let len = (end - start) as usize;
+        let mut vec = Vec::<T>::with_capacity(len);
+        if len > 0 {
+            let mem_type = Datatype::from_descriptor(&TypeDescriptor::Compound(projected))?;
+            Self::read_converted(
+                &self.dataset()?,
+                &mem_type,
+                start,
+                len,
+                vec.as_mut_ptr() as *mut _,
+            )?;
+            // SAFETY: read succeeded.
+            unsafe {
+                vec.set_len(len);
+            }
+        }
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use hdf5::H5Type;
+    use tempfile::NamedTempFile;
+
+    #[derive(H5Type, Clone, Copy)]
+    #[repr(C)]
+    struct Wide {
+        a: u8,
+        b: f64,
+        c: u32,
+        d: i16,
+        e: f32,
+    }
+
+    #[derive(H5Type, Debug, PartialEq)]
+    #[repr(C)]
+    struct Projected {
+        d: i16,
+        b: f64,
+    }
+
+    #[derive(H5Type, Debug)]
+    #[repr(C)]
+    struct Unknown {
+        a: u8,
+        z: u8,
+    }
+
+    #[test]
+    fn read_fields() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Wide>()
+            .create("data")
+            .unwrap();
+        let packets = (0..10)
+            .map(|i| Wide {
+                a: i as u8,
+                b: i as f64 * 1.5,
+                c: i * 100,
+                d: -(i as i16),
+                e: i as f32,
+            })
+            .collect::<Vec<_>>();
+        table.append(&packets).unwrap();
+
+        let projected = table.read_fields::<Projected>(3..7).unwrap();
+        assert_eq!(
+            projected,
+            (3..7)
+                .map(|i| Projected {
+                    d: -i,
+                    b: i as f64 * 1.5,
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(table.read_fields::<Projected>(..).unwrap().len(), 10);
+        assert!(table.read_fields::<Projected>(8..12).is_err());
+
+        let e = table.read_fields::<Unknown>(..).unwrap_err().to_string();
+        assert!(e.contains("z"));
+        assert!(e.contains("a, b, c, d, e"));
+        assert!(table.read_fields::<u8>(..).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::*;
+use hdf5::H5Type;
+
+/// A row of values, one for each table of a [`TableGroupWriter`].
+///
+/// It is implemented for tuples of up to 6 elements.
+pub trait Row {
+    /// The buffers of the columns.
+    type Buffers: Default;
+
+    /// Number of the tables.
+    const TABLES: usize;
+
+    /// Push the values into the buffers.
+    fn push_into(self, buffers: &mut Self::Buffers);
+
+    /// Append the buffer of one column into the table.
+    fn append_column(buffers: &Self::Buffers, column: usize, table: &mut PacketTable)
+        -> Result<()>;
+
+    /// Clear the buffers.
+    fn clear(buffers: &mut Self::Buffers);
+}
+
+macro_rules! impl_row {
+    ($n:literal; $($t:ident $i:tt),+) => {
+        impl<$($t: H5Type),+> Row for ($($t,)+) {
+            type Buffers = ($(Vec<$t>,)+);
+
+            const TABLES: usize = $n;
+
+            fn push_into(self, buffers: &mut Self::Buffers) {
+                $(buffers.$i.push(self.$i);)+
+            }
+
+            fn append_column(
+                buffers: &Self::Buffers,
+                column: usize,
+                table: &mut PacketTable,
+            ) -> Result<()> {
+                match column {
+                    $($i => table.append(&buffers.$i),)+
+                    _ => unreachable!(),
+                }
+            }
+
+            fn clear(buffers: &mut Self::Buffers) {
+                $(buffers.$i.clear();)+
+            }
+        }
+    };
+}
+
+impl_row!(1; A 0);
+impl_row!(2; A 0, B 1);
+impl_row!(3; A 0, B 1, C 2);
+impl_row!(4; A 0, B 1, C 2, D 3);
+impl_row!(5; A 0, B 1, C 2, D 3, E 4);
+impl_row!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// A writer keeping several [`PacketTable`]s row-aligned.
+///
+/// The rows are buffered, and appended to all tables together.
+/// If one table fails, the tables are truncated back to the common length.
+/// The buffered rows are flushed when the writer is dropped.
+pub struct TableGroupWriter<R: Row> {
+    tables: Vec<PacketTable>,
+    buffers: R::Buffers,
+    len: usize,
+    buf_len: usize,
+}
+
+impl<R: Row> TableGroupWriter<R> {
+    /// Create a new [`TableGroupWriter`] with buffer length.
+    /// The tables should have the same number of packets.
+    ///
+    /// Use [`TableGroupWriter::new_recovered`] for tables which may differ, e.g., after a crash.
+    pub fn new(tables: Vec<PacketTable>, buf_len: usize) -> Result<Self> {
+        let this = Self::new_unaligned(tables, buf_len)?;
+        this.common_len()?;
+        Ok(this)
+    }
+
+    /// Create a new [`TableGroupWriter`] with buffer length,
+    /// truncating the tables to the shortest one, see [`TableGroupWriter::recover`].
+    pub fn new_recovered(tables: Vec<PacketTable>, buf_len: usize) -> Result<Self> {
+        let mut this = Self::new_unaligned(tables, buf_len)?;
+        this.recover()?;
+        Ok(this)
+    }
+
+    fn new_unaligned(tables: Vec<PacketTable>, buf_len: usize) -> Result<Self> {
+        if tables.len() != R::TABLES {
+            return Err(Error::Other(format!(
+                "Expected {} tables, got {}.",
+                R::TABLES,
+                tables.len()
+            )));
+        }
+        if buf_len == 0 {
+            return Err(Error::ZeroBufLen);
+        }
+        Ok(Self {
+            tables,
+            buffers: R::Buffers::default(),
+            len: 0,
+            buf_len,
+        })
+    }
+
+    /// Get the tables.
+    pub fn tables(&self) -> &[PacketTable] {
+        &self.tables
+    }
+
+    /// Get the numbers of packets of the tables.
+    pub fn counts(&self) -> Result<Vec<u64>> {
+        self.tables.iter().map(|t| t.num_packets()).collect()
+    }
+
+    /// Get the number of packets, returning [`Error::RowMismatch`] if they differ.
+    pub fn common_len(&self) -> Result<u64> {
+        let counts = self.counts()?;
+        if counts.windows(2).any(|w| w[0] != w[1]) {
+            return Err(Error::RowMismatch { counts });
+        }
+        Ok(counts[0])
+    }
+
+    /// Truncate the tables to the shortest one, e.g., after a crash.
+    /// Returns the common length.
+    pub fn recover(&mut self) -> Result<u64> {
+        let len = self.counts()?.into_iter().min().unwrap_or_default();
+        self.truncate(len)?;
+        Ok(len)
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<()> {
+        for table in &mut self.tables {
+            if table.num_packets()? > len {
+                table.truncate(len)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Push a row into the buffers.
+    pub fn push_row(&mut self, row: R) -> Result<()> {
+        row.push_into(&mut self.buffers);
+        self.len += 1;
+        if self.len >= self.buf_len {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Append the buffered rows to all tables.
+    /// Returns the number of rows written.
+    ///
+    /// If one table fails, the tables are truncated back to the previous length,
+    /// and the rows are kept in the buffers. If truncating fails too,
+    /// [`Error::Rollback`] reports both errors.
+    pub fn flush(&mut self) -> Result<usize> {
+        if self.len == 0 {
+            return Ok(0);
+        }
+        let base = self.common_len()?;
+        for column in 0..R::TABLES {
+            if let Err(error) = R::append_column(&self.buffers, column, &mut self.tables[column]) {
+                return Err(match self.truncate(base) {
+                    Ok(()) => error,
+                    Err(rollback_error) => Error::Rollback {
+                        len: base,
+                        error: Box::new(error),
+                        rollback_error: Box::new(rollback_error),
+                    },
+                });
+            }
+        }
+        let len = self.common_len()?;
+        debug_assert_eq!(len, base + self.len as u64);
+        R::clear(&mut self.buffers);
+        Ok(std::mem::take(&mut self.len))
+    }
+
+    /// Flush and get the tables.
+    pub fn into_tables(mut self) -> Result<Vec<PacketTable>> {
+        self.flush()?;
+        Ok(std::mem::take(&mut self.tables))
+    }
+}
+
+impl<R: Row> Drop for TableGroupWriter<R> {
+    /// The errors of flushing are ignored.
+    /// Call [`TableGroupWriter::flush`] or [`TableGroupWriter::into_tables`] to handle them.
+    fn drop(&mut self) {
+        if !self.tables.is_empty() {
+            self.flush().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use tempfile::NamedTempFile;
+
+    fn create(file: &hdf5::File, names: &[&str]) -> Vec<PacketTable> {
+        names
+            .iter()
+            .map(|name| {
+                PacketTable::builder(file)
+                    .chunk(4)
+                    .dtype::<u32>()
+                    .create(name)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn row_aligned() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut tables = create(&file, &["a", "b", "c"]);
+        // The type of "c" doesn't match the row.
+        tables[2] = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u64>()
+            .create("d")
+            .unwrap();
+
+        let mut writer = TableGroupWriter::<(u32, u32, u32)>::new(tables, 2).unwrap();
+        writer.push_row((1, 2, 3)).unwrap();
+        assert!(writer.push_row((4, 5, 6)).is_err());
+        assert_eq!(writer.counts().unwrap(), [0, 0, 0]);
+        drop(writer.into_tables().unwrap_err());
+
+        let tables = create(&file, &["x", "y"]);
+        let mut writer = TableGroupWriter::<(u32, u32)>::new(tables, 3).unwrap();
+        for i in 0..7 {
+            writer.push_row((i, i * 10)).unwrap();
+        }
+        assert_eq!(writer.counts().unwrap(), [6, 6]);
+        let tables = writer.into_tables().unwrap();
+        assert_eq!(
+            tables[0].read_all::<u32>().unwrap(),
+            (0..7).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            tables[1].read_all::<u32>().unwrap(),
+            (0..7).map(|i| i * 10).collect::<Vec<_>>()
+        );
+
+        let tables = create(&file, &["e", "f"]);
+        assert!(matches!(
+            TableGroupWriter::<(u32, u32)>::new(tables, 0),
+            Err(Error::ZeroBufLen)
+        ));
+    }
+
+    #[test]
+    fn recover() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut tables = create(&file, &["a", "b"]);
+        tables[0].append(&[1u32, 2, 3]).unwrap();
+        tables[1].append(&[1u32, 2]).unwrap();
+
+        let reopen = |tables: &[PacketTable]| {
+            tables
+                .iter()
+                .map(|t| t.try_clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert!(matches!(
+            TableGroupWriter::<(u32, u32)>::new(reopen(&tables), 4),
+            Err(Error::RowMismatch { counts }) if counts == [3, 2]
+        ));
+        // The writer could be created from the tables after a crash.
+        let mut writer = TableGroupWriter::<(u32, u32)>::new_recovered(tables, 4).unwrap();
+        assert_eq!(writer.common_len().unwrap(), 2);
+        writer.push_row((3, 3)).unwrap();
+        let tables = writer.into_tables().unwrap();
+        assert_eq!(tables[0].read_all::<u32>().unwrap(), [1, 2, 3]);
+        assert_eq!(tables[1].read_all::<u32>().unwrap(), [1, 2, 3]);
+
+        let tables = create(&file, &["c", "d"]);
+        let mut writer = TableGroupWriter::<(u32, u32)>::new(tables, 4).unwrap();
+        writer.push_row((1, 1)).unwrap();
+        writer.flush().unwrap();
+        // Simulate a crash between the appends.
+        writer.tables[0].append(&[2u32]).unwrap();
+        assert!(matches!(
+            writer.common_len(),
+            Err(Error::RowMismatch { .. })
+        ));
+        assert_eq!(writer.recover().unwrap(), 1);
+        assert_eq!(writer.common_len().unwrap(), 1);
+    }
+}
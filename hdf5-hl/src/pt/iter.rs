@@ -0,0 +1,144 @@
+use crate::*;
+use dst_container::*;
+use hdf5::{h5try, types::TypeDescriptor, H5Type};
+use hdf5_hl_sys::h5pt::{H5PTfree_vlen_buff, H5PTread_packets};
+use hdf5_sys::h5t::hvl_t;
+use std::{mem::MaybeUninit, ptr::Pointee};
+
+/// An unsized packet reader of [`PacketTable`] with an internal buffer.
+///
+/// It is not an [`Iterator`], because the returned reference borrows the
+/// internal buffer, and is invalidated by the next call of [`Self::next`].
+pub struct PacketTableIterUnsized<'a, T: ?Sized> {
+    table: &'a PacketTable,
+    index: u64,
+    buffer: FixedVec<T>,
+}
+
+impl<'a, T: ?Sized> PacketTableIterUnsized<'a, T> {
+    pub(crate) fn new(table: &'a PacketTable, metadata: <T as Pointee>::Metadata) -> Self {
+        Self {
+            table,
+            index: 0,
+            buffer: FixedVec::with_capacity(metadata, 1),
+        }
+    }
+
+    fn read_one(&mut self) -> Result<bool> {
+        if self.index < self.table.num_packets()? {
+            self.buffer.clear();
+            self.table.read_unsized(self.index, 1, &mut self.buffer)?;
+            self.index += 1;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Read the next packet into the internal buffer.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<&T>> {
+        match self.read_one() {
+            Ok(true) => Some(Ok(&self.buffer[0])),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl PacketTable {
+    /// Create an iterator to read the variable length packets one by one as owned vectors.
+    /// It doesn't influence the index of the packet table.
+    ///
+    /// The buffer allocated by HDF5 for each packet is freed right after being copied,
+    /// even if the iteration stops early or cloning an element panics.
+    /// The iterator stops after the first error.
+    /// It is an alternative to [`PacketTable::iter`] with
+    /// [`VarLenArray<T>`](hdf5::types::VarLenArray), which owns the buffer instead.
+    ///
+    /// Fails if the table is not variable length, or the element type is not `T`.
+    pub fn iter_varlen<T: H5Type + Clone>(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Vec<T>>> + '_> {
+        if !self.is_varlen()? {
+            return Err(Error::Other(
+                "The packet table is not variable length.".to_string(),
+            ));
+        }
+        let expected = TypeDescriptor::VarLenArray(Box::new(T::type_descriptor()));
+        let actual = self.type_descriptor()?;
+        if actual != expected {
+            return Err(Error::SchemaMismatch {
+                expected: Box::new(expected),
+                actual: Box::new(actual),
+            });
+        }
+        let mut index = 0;
+        let mut done = false;
+        Ok(std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let mut read_one = || {
+                if index >= self.num_packets()? {
+                    return Ok(None);
+                }
+                let mut buf = MaybeUninit::<hvl_t>::uninit();
+                h5try!(H5PTread_packets(
+                    self.checked_id(),
+                    index,
+                    1,
+                    buf.as_mut_ptr() as *mut _
+                ));
+                // SAFETY: read succeeded.
+                let mut buf = VarLenBuffer {
+                    table: self,
+                    buf: Some(unsafe { buf.assume_init() }),
+                };
+                let vec = buf.to_vec::<T>();
+                buf.free()?;
+                index += 1;
+                Ok(Some(vec))
+            };
+            let res = read_one().transpose();
+            done = !matches!(res, Some(Ok(_)));
+            res
+        }))
+    }
+}
+
+/// A variable length packet allocated by HDF5, freed when dropped,
+/// so that it doesn't leak if cloning the elements panics.
+struct VarLenBuffer<'a> {
+    table: &'a PacketTable,
+    buf: Option<hvl_t>,
+}
+
+impl VarLenBuffer<'_> {
+    fn to_vec<T: Clone>(&self) -> Vec<T> {
+        match &self.buf {
+            Some(buf) if buf.len > 0 => {
+                // SAFETY: HDF5 allocates `len` elements of `T`.
+                unsafe { std::slice::from_raw_parts(buf.p as *const T, buf.len) }.to_vec()
+            }
+            _ => vec![],
+        }
+    }
+
+    fn free(&mut self) -> Result<()> {
+        if let Some(mut buf) = self.buf.take() {
+            h5try!(H5PTfree_vlen_buff(
+                self.table.checked_id(),
+                1,
+                &mut buf as *mut hvl_t as *mut _
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VarLenBuffer<'_> {
+    fn drop(&mut self) {
+        self.free().ok();
+    }
+}
@@ -0,0 +1,352 @@
+use crate::*;
+use hdf5::H5Type;
+use std::ops::Range;
+
+/// The operation recorded by a [`JournalEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, H5Type)]
+#[repr(u8)]
+pub enum JournalOp {
+    /// Packets are appended.
+    Append = 0,
+    /// Existing packets are overwritten.
+    Overwrite = 1,
+    /// The table is truncated.
+    Truncate = 2,
+}
+
+/// One operation in the write journal of a [`PacketTable`].
+///
+/// The journal is a packet table of entries, see [`PacketTable::set_journal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, H5Type)]
+#[repr(C)]
+pub struct JournalEntry {
+    /// The sequence number, which is the index of the entry in the journal.
+    pub seq: u64,
+    /// The operation.
+    pub op: JournalOp,
+    /// The first packet written, or the new length if truncated.
+    pub start: u64,
+    /// Number of the packets written, or removed if truncated.
+    pub len: u64,
+}
+
+impl JournalEntry {
+    /// Get the range of the packets written or removed.
+    pub fn range(&self) -> Range<u64> {
+        self.start..self.start + self.len
+    }
+
+    /// Determine if this later entry changes the packets written by `entry`.
+    fn clobbers(&self, entry: &JournalEntry) -> bool {
+        match self.op {
+            JournalOp::Append => false,
+            JournalOp::Overwrite => {
+                self.start < entry.range().end && entry.start < self.range().end
+            }
+            JournalOp::Truncate => self.start < entry.range().end,
+        }
+    }
+}
+
+impl PacketTable {
+    /// Set the write journal, which records every append, overwrite and truncate.
+    /// The packets copied in by [`PacketTable::copy_to`] are recorded as appends.
+    ///
+    /// The journal is a packet table of [`JournalEntry`]. An operation is recorded
+    /// after it succeeds; if the recording fails, the error is returned,
+    /// but the operation is not undone. Set `None` to stop recording.
+    pub fn set_journal(&mut self, journal: Option<PacketTable>) -> Result<()> {
+        self.journal = journal
+            .map(|j| j.typed::<JournalEntry>().map(Box::new))
+            .transpose()?;
+        Ok(())
+    }
+
+    /// Get the write journal.
+    pub fn journal(&self) -> Option<&PacketTable> {
+        self.journal.as_ref().map(|j| j.inner())
+    }
+
+    /// Record an operation in the journal if set.
+    pub(crate) fn log(&mut self, op: JournalOp, start: u64, len: u64) -> Result<()> {
+        if let Some(journal) = &mut self.journal {
+            let seq = journal.inner().num_packets()?;
+            journal.push(&JournalEntry {
+                seq,
+                op,
+                start,
+                len,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Reconstruct the table of all operations in the journal.
+///
+/// See [`replay_until`].
+pub fn replay_journal(
+    journal: &PacketTable,
+    original: &PacketTable,
+    source: impl FnMut(Range<u64>) -> Result<Vec<u8>>,
+    builder: PacketTableBuilderTyped,
+    table_name: impl AsRef<str>,
+) -> Result<PacketTable> {
+    replay_until(journal, original, u64::MAX, source, builder, table_name)
+}
+
+/// Reconstruct the table before the operation `seq` in the journal,
+/// by reapplying the previous operations against a new table created by `builder`.
+///
+/// The written packets are read from `original`, the journaled table, if no later
+/// operation in the journal has overwritten or truncated them. Otherwise, the raw bytes
+/// of the whole range of the entry are pulled from `source`, in the layout of the stored datatype.
+/// Variable length tables are rejected, because their raw bytes contain pointers.
+pub fn replay_until(
+    journal: &PacketTable,
+    original: &PacketTable,
+    seq: u64,
+    mut source: impl FnMut(Range<u64>) -> Result<Vec<u8>>,
+    builder: PacketTableBuilderTyped,
+    table_name: impl AsRef<str>,
+) -> Result<PacketTable> {
+    let entries = journal.read_all::<JournalEntry>()?;
+    let mut table = builder.create(table_name)?;
+    let size = table.raw_packet_size()?;
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.seq >= seq {
+            break;
+        }
+        let len = entry.len as usize;
+        if entry.op == JournalOp::Truncate {
+            table.truncate(entry.start)?;
+            continue;
+        }
+        // `original` holds the state after the whole journal, so the entries
+        // after `seq` are also scanned: they may have clobbered this one.
+        let bytes = if entries[i + 1..].iter().any(|later| later.clobbers(entry)) {
+            source(entry.range())?
+        } else {
+            original.read_raw(entry.start, len)?
+        };
+        if bytes.len() != len * size {
+            return Err(Error::JournalMismatch {
+                seq: entry.seq,
+                expected: len * size,
+                actual: bytes.len(),
+            });
+        }
+        let ptr = bytes.as_ptr() as *const ();
+        match entry.op {
+            JournalOp::Append => {
+                let num = table.num_packets()?;
+                if num != entry.start {
+                    return Err(Error::JournalAppendMismatch {
+                        seq: entry.seq,
+                        start: entry.start,
+                        num_packets: num,
+                    });
+                }
+                table.append_stored(len, ptr)?;
+            }
+            JournalOp::Overwrite => {
+                table.check_remaining(entry.start, len)?;
+                table.overwrite_raw(entry.start, len, ptr)?;
+            }
+            JournalOp::Truncate => unreachable!(),
+        }
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use std::{cell::RefCell, ops::Range};
+    use tempfile::NamedTempFile;
+
+    fn bytes(packets: &[u32]) -> Vec<u8> {
+        packets.iter().flat_map(|p| p.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn replay() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        let journal = PacketTable::builder(&file)
+            .chunk(16)
+            .dtype::<JournalEntry>()
+            .create("journal")
+            .unwrap();
+        table.set_journal(Some(journal)).unwrap();
+
+        table.append(&[0u32, 1, 2, 3]).unwrap();
+        table.append(&[4u32, 5]).unwrap();
+        table.write_at(1, &10u32).unwrap();
+        table.truncate(3).unwrap();
+        table.append(&[6u32, 7]).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [0, 10, 2, 6, 7]);
+
+        let journal = table.journal().unwrap();
+        assert_eq!(
+            journal
+                .read_all::<JournalEntry>()
+                .unwrap()
+                .iter()
+                .map(|e| (e.seq, e.op, e.range()))
+                .collect::<Vec<_>>(),
+            [
+                (0, JournalOp::Append, 0..4),
+                (1, JournalOp::Append, 4..6),
+                (2, JournalOp::Overwrite, 1..2),
+                (3, JournalOp::Truncate, 3..6),
+                (4, JournalOp::Append, 3..5),
+            ]
+        );
+
+        // The first two appends are clobbered later.
+        let pulled = RefCell::new(vec![]);
+        let source = |range: Range<u64>| {
+            pulled.borrow_mut().push(range.clone());
+            match range {
+                Range { start: 0, end: 4 } => Ok(bytes(&[0, 1, 2, 3])),
+                Range { start: 4, end: 6 } => Ok(bytes(&[4, 5])),
+                _ => unreachable!(),
+            }
+        };
+        let builder = || {
+            PacketTable::builder(&file)
+                .chunk(4)
+                .dtype_like(&table)
+                .unwrap()
+        };
+        for (seq, expected) in [
+            (2, vec![0u32, 1, 2, 3, 4, 5]),
+            (3, vec![0, 10, 2, 3, 4, 5]),
+            (4, vec![0, 10, 2]),
+        ] {
+            let replayed = replay_until(
+                journal,
+                &table,
+                seq,
+                source,
+                builder(),
+                format!("replay_{seq}"),
+            )
+            .unwrap();
+            assert_eq!(replayed.read_all::<u32>().unwrap(), expected);
+        }
+        let replayed = replay_journal(journal, &table, source, builder(), "replay").unwrap();
+        assert_eq!(replayed.read_all::<u32>().unwrap(), [0, 10, 2, 6, 7]);
+        assert_eq!(
+            *pulled.borrow(),
+            [0..4, 4..6, 0..4, 4..6, 0..4, 4..6, 0..4, 4..6]
+        );
+
+        // The source should return whole entries.
+        assert!(matches!(
+            replay_journal(journal, &table, |_| Ok(vec![0]), builder(), "bad"),
+            Err(Error::JournalMismatch {
+                seq: 0,
+                expected: 16,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn replay_gap() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        table.append(&[0u32, 1, 2]).unwrap();
+        let mut journal = PacketTable::builder(&file)
+            .chunk(16)
+            .dtype::<JournalEntry>()
+            .create("journal")
+            .unwrap();
+        // The first two packets are not journaled.
+        journal
+            .push(&JournalEntry {
+                seq: 0,
+                op: JournalOp::Append,
+                start: 2,
+                len: 1,
+            })
+            .unwrap();
+
+        let builder = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype_like(&table)
+            .unwrap();
+        assert!(matches!(
+            replay_journal(&journal, &table, |_| unreachable!(), builder, "replay"),
+            Err(Error::JournalAppendMismatch {
+                seq: 0,
+                start: 2,
+                num_packets: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn replay_copy() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut src = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("src")
+            .unwrap();
+        src.append(&[0u32, 1, 2, 3, 4, 5]).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+        let journal = PacketTable::builder(&file)
+            .chunk(16)
+            .dtype::<JournalEntry>()
+            .create("journal")
+            .unwrap();
+        table.set_journal(Some(journal)).unwrap();
+
+        table.push(&9u32).unwrap();
+        assert_eq!(src.copy_to(&mut table, 2..5).unwrap(), 3);
+        table.write_at(2, &10u32).unwrap();
+        assert_eq!(table.read_all::<u32>().unwrap(), [9, 2, 10, 4]);
+
+        let journal = table.journal().unwrap();
+        assert_eq!(
+            journal
+                .read_all::<JournalEntry>()
+                .unwrap()
+                .iter()
+                .map(|e| (e.op, e.range()))
+                .collect::<Vec<_>>(),
+            [
+                (JournalOp::Append, 0..1),
+                (JournalOp::Append, 1..4),
+                (JournalOp::Overwrite, 2..3),
+            ]
+        );
+
+        // The copied range is clobbered by the overwrite, so it comes from `src`.
+        let source = |range: Range<u64>| src.read_raw(range.start + 1, 3);
+        let builder = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype_like(&table)
+            .unwrap();
+        let replayed = replay_until(journal, &table, 2, source, builder, "replay").unwrap();
+        assert_eq!(replayed.read_all::<u32>().unwrap(), [9, 2, 3, 4]);
+    }
+}
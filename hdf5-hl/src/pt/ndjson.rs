@@ -0,0 +1,313 @@
+use super::{fields::find_member, raw::contains_pointers};
+use crate::*;
+use hdf5::{
+    h5call, h5try,
+    types::{CompoundField, TypeDescriptor},
+};
+use hdf5_hl_sys::h5pt::{H5PTfree_vlen_buff, H5PTread_packets};
+use serde_json::{Map, Number, Value};
+use std::{
+    ffi::{c_char, c_void, CStr},
+    io::Write,
+    ops::RangeBounds,
+};
+
+/// Read a value of `T` from the start of the bytes.
+fn read_ne<T: Copy>(bytes: &[u8]) -> T {
+    assert!(bytes.len() >= size_of::<T>());
+    // SAFETY: the length is checked.
+    unsafe { (bytes.as_ptr() as *const T).read_unaligned() }
+}
+
+fn decode_int(bytes: &[u8], signed: bool) -> Value {
+    match (bytes.len(), signed) {
+        (1, true) => read_ne::<i8>(bytes).into(),
+        (2, true) => read_ne::<i16>(bytes).into(),
+        (4, true) => read_ne::<i32>(bytes).into(),
+        (8, true) => read_ne::<i64>(bytes).into(),
+        (1, false) => read_ne::<u8>(bytes).into(),
+        (2, false) => read_ne::<u16>(bytes).into(),
+        (4, false) => read_ne::<u32>(bytes).into(),
+        (8, false) => read_ne::<u64>(bytes).into(),
+        _ => unsupported("integer"),
+    }
+}
+
+fn decode_string(bytes: &[u8]) -> Value {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned().into()
+}
+
+fn unsupported(name: &str) -> Value {
+    let mut map = Map::new();
+    map.insert("$unsupported".to_string(), name.into());
+    Value::Object(map)
+}
+
+/// Decode the bytes of one value in memory.
+///
+/// # Safety
+///
+/// The pointers in the bytes should be valid.
+unsafe fn decode(ty: &TypeDescriptor, bytes: &[u8]) -> Value {
+    match ty {
+        TypeDescriptor::Integer(_) => decode_int(bytes, true),
+        TypeDescriptor::Unsigned(_) => decode_int(bytes, false),
+        TypeDescriptor::Float(_) => match bytes.len() {
+            4 => Number::from_f64(read_ne::<f32>(bytes) as f64).map_or(Value::Null, Value::Number),
+            8 => Number::from_f64(read_ne::<f64>(bytes)).map_or(Value::Null, Value::Number),
+            _ => unsupported("float"),
+        },
+        TypeDescriptor::Boolean => Value::Bool(bytes[0] != 0),
+        TypeDescriptor::Enum(ty) => {
+            let value = decode_int(bytes, ty.signed);
+            let raw = value.as_i64().map(|v| v as u64).or_else(|| value.as_u64());
+            ty.members
+                .iter()
+                .find(|m| Some(m.value) == raw)
+                .map_or(value, |m| m.name.clone().into())
+        }
+        TypeDescriptor::Compound(ty) => Value::Object(unsafe { decode_fields(&ty.fields, bytes) }),
+        TypeDescriptor::FixedArray(ty, len) => {
+            let size = ty.size();
+            Value::Array(
+                (0..*len)
+                    .map(|i| unsafe { decode(ty, &bytes[i * size..(i + 1) * size]) })
+                    .collect(),
+            )
+        }
+        TypeDescriptor::FixedAscii(_) | TypeDescriptor::FixedUnicode(_) => decode_string(bytes),
+        TypeDescriptor::VarLenArray(ty) => {
+            let len = read_ne::<usize>(bytes);
+            let ptr = read_ne::<*const c_void>(&bytes[size_of::<usize>()..]);
+            if len == 0 || ptr.is_null() {
+                return Value::Array(vec![]);
+            }
+            let size = ty.size();
+            // SAFETY: the buffer is allocated by HDF5.
+            let data = unsafe { std::slice::from_raw_parts(ptr as *const u8, len * size) };
+            Value::Array(
+                (0..len)
+                    .map(|i| unsafe { decode(ty, &data[i * size..(i + 1) * size]) })
+                    .collect(),
+            )
+        }
+        TypeDescriptor::VarLenAscii | TypeDescriptor::VarLenUnicode => {
+            let ptr = read_ne::<*const c_char>(bytes);
+            if ptr.is_null() {
+                return Value::String(String::new());
+            }
+            // SAFETY: the string is allocated by HDF5.
+            let s = unsafe { CStr::from_ptr(ptr) };
+            s.to_string_lossy().into_owned().into()
+        }
+        _ => unsupported(&format!("{ty:?}")),
+    }
+}
+
+/// Decode the compound members in the bytes.
+///
+/// # Safety
+///
+/// The pointers in the bytes should be valid.
+unsafe fn decode_fields<'a>(
+    fields: impl IntoIterator<Item = &'a CompoundField>,
+    bytes: &[u8],
+) -> Map<String, Value> {
+    fields
+        .into_iter()
+        .map(|f| {
+            let value = unsafe { decode(&f.ty, &bytes[f.offset..f.offset + f.ty.size()]) };
+            (f.name.clone(), value)
+        })
+        .collect()
+}
+
+impl PacketTable {
+    /// Export all packets as newline-delimited JSON.
+    /// Returns the number of packets written.
+    ///
+    /// See [`PacketTable::export_ndjson`].
+    pub fn to_ndjson<W: Write>(&self, w: &mut W) -> Result<u64> {
+        self.export_ndjson(w, .., None)
+    }
+
+    /// Export the packets in the range as newline-delimited JSON, one packet per line.
+    /// Returns the number of packets written.
+    ///
+    /// Compound packets are written as objects, and `fields` selects the members
+    /// to write. Arrays, both fixed and variable length, are written as arrays, and
+    /// strings are decoded lossily. Unsupported values are written as
+    /// `{"$unsupported": "<type>"}`.
+    ///
+    /// The packets are read in chunk-sized batches, so the memory usage is bounded.
+    pub fn export_ndjson<W: Write>(
+        &self,
+        w: &mut W,
+        range: impl RangeBounds<u64>,
+        fields: Option<&[&str]>,
+    ) -> Result<u64> {
+        let (start, end) = self.resolve_range(range)?;
+        let dtype = self.dtype()?;
+        let ty = dtype.to_descriptor()?;
+        let selected = match (&ty, fields) {
+            (_, None) => None,
+            (TypeDescriptor::Compound(compound), Some(fields)) => Some(
+                fields
+                    .iter()
+                    .map(|name| find_member(compound, name))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            (_, Some(_)) => {
+                return Err(Error::Other(
+                    "Only compound packets have fields.".to_string(),
+                ))
+            }
+        };
+        let varlen = contains_pointers(&ty);
+        let size = dtype.size();
+        let batch = self.batch_len()?;
+        let mut buffer = vec![0u8; batch * size];
+        let mut reporter = self.reporter("export_ndjson", Some(end - start));
+        let mut index = start;
+        while index < end {
+            self.check_cancelled(index - start)?;
+            let count = (end - index).min(batch as u64) as usize;
+            let bytes = &mut buffer[..count * size];
+            h5try!(H5PTread_packets(
+                self.checked_id(),
+                index,
+                count,
+                bytes.as_mut_ptr() as *mut _
+            ));
+            let res = bytes.chunks(size).try_for_each(|packet| {
+                // SAFETY: the packets are read by HDF5, and not freed yet.
+                let value = unsafe {
+                    match &selected {
+                        Some(fields) => {
+                            Value::Object(decode_fields(fields.iter().copied(), packet))
+                        }
+                        None => decode(&ty, packet),
+                    }
+                };
+                serde_json::to_writer(&mut *w, &value).map_err(std::io::Error::from)?;
+                w.write_all(b"\n")?;
+                Ok::<_, Error>(())
+            });
+            if varlen {
+                h5call!(H5PTfree_vlen_buff(
+                    self.checked_id(),
+                    count as _,
+                    bytes.as_mut_ptr() as *mut _
+                ))?;
+            }
+            res?;
+            index += count as u64;
+            reporter.update(index - start, (index - start) * size as u64);
+        }
+        reporter.finish(end - start, (end - start) * size as u64);
+        Ok(end - start)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_alloc, *};
+    use hdf5::{
+        types::{FixedAscii, VarLenArray},
+        H5Type,
+    };
+    use serde_json::{json, Value};
+    use tempfile::NamedTempFile;
+
+    #[derive(H5Type)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        name: FixedAscii<8>,
+        values: VarLenArray<f64>,
+    }
+
+    fn record(id: u32) -> Record {
+        Record {
+            id,
+            name: FixedAscii::from_ascii(format!("r{id}").as_bytes()).unwrap(),
+            values: VarLenArray::from_slice(&vec![id as f64 / 2.0; id as usize]),
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Vec<Value> {
+        std::str::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn export() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(2)
+            .dtype::<Record>()
+            .create("data")
+            .unwrap();
+        table.append(&[record(0), record(1), record(2)]).unwrap();
+
+        let mut out = vec![];
+        assert_eq!(table.to_ndjson(&mut out).unwrap(), 3);
+        assert_eq!(
+            parse(&out),
+            [
+                json!({"id": 0, "name": "r0", "values": []}),
+                json!({"id": 1, "name": "r1", "values": [0.5]}),
+                json!({"id": 2, "name": "r2", "values": [1.0, 1.0]}),
+            ]
+        );
+
+        let mut out = vec![];
+        assert_eq!(
+            table
+                .export_ndjson(&mut out, 1.., Some(&["values", "id"]))
+                .unwrap(),
+            2
+        );
+        let values = parse(&out);
+        assert_eq!(
+            values,
+            [
+                json!({"id": 1, "values": [0.5]}),
+                json!({"id": 2, "values": [1.0, 1.0]}),
+            ]
+        );
+        assert!(values
+            .iter()
+            .all(|v| v.as_object().unwrap().keys().len() == 2));
+
+        assert!(table
+            .export_ndjson(&mut vec![], .., Some(&["missing"]))
+            .is_err());
+    }
+
+    #[test]
+    fn bounded_memory() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&data)
+            .chunk(1024)
+            .dtype::<[u64; 2]>()
+            .create("data")
+            .unwrap();
+        let packets = (0..100_000u64).map(|i| [i, i * 2]).collect::<Vec<_>>();
+        table.append(&packets).unwrap();
+        drop(packets);
+
+        let base = test_alloc::reset_peak();
+        assert_eq!(table.to_ndjson(&mut std::io::sink()).unwrap(), 100_000);
+        // The whole table takes 1.6MB in memory.
+        assert!(test_alloc::peak_since(base) < 256 * 1024);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::*;
+use rayon::prelude::*;
+
+impl PacketTable {
+    /// Read all packets in parallel, `chunk` packets per task.
+    ///
+    /// Every task opens its own handle of the table by [`Self::try_clone`].
+    /// The HDF5 calls, including the type conversion while reading, are serialized
+    /// by the global lock, so reading is not faster than [`Self::read_all`].
+    /// It is useful to process the chunks in parallel in the later stages.
+    /// The [`CancelToken`] is checked before each task, and the cancelled tasks
+    /// report their start index as the progress.
+    pub fn par_read_chunks<T: Send>(
+        &self,
+        chunk: usize,
+    ) -> Result<impl IndexedParallelIterator<Item = Result<Vec<T>>>> {
+        if chunk == 0 {
+            return Err(Error::ZeroChunk);
+        }
+        self.check_packet_size(size_of::<T>())?;
+        let num_packets = self.num_packets()?;
+        let table = self.try_clone()?;
+        let cancel = self.cancel.clone();
+        let ranges = (0..num_packets)
+            .step_by(chunk)
+            .map(|start| (start, (num_packets - start).min(chunk as u64) as usize))
+            .collect::<Vec<_>>();
+        Ok(ranges.into_par_iter().map(move |(start, len)| {
+            if cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+                return Err(Error::Cancelled {
+                    processed: start,
+                    rolled_back: false,
+                });
+            }
+            table.try_clone()?.read::<T>(start, len)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use rayon::prelude::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn par_read_chunks() {
+        let file = NamedTempFile::new().unwrap();
+        let manifest = fixtures::standard_file(file.path(), 114514).unwrap();
+        let entry = manifest.get(fixtures::I32_TABLE).unwrap();
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&data, fixtures::I32_TABLE).unwrap();
+        let serial = table.read_all::<i32>().unwrap();
+        assert_eq!(serial.len(), entry.count);
+        let bytes = serial
+            .iter()
+            .flat_map(|p| p.to_ne_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(fixtures::checksum(&bytes), entry.checksum);
+
+        let chunks = table
+            .par_read_chunks::<i32>(16)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), entry.count.div_ceil(16));
+        assert_eq!(chunks.concat(), serial);
+
+        let chunks = table
+            .par_read_chunks::<i32>(20)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), entry.count.div_ceil(20));
+        assert_eq!(chunks.concat(), serial);
+
+        assert!(matches!(
+            table.par_read_chunks::<i32>(0),
+            Err(Error::ZeroChunk)
+        ));
+        assert!(table.par_read_chunks::<i64>(100).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+use hdf5::{h5lock, LocationType};
+use hdf5_sys::h5t::{H5T_class_t, H5Tget_class};
+
+/// The facts of a dataset gathered by [`PacketTable::probe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableProbe {
+    /// Whether the dataset is chunked and 1-dimensional,
+    /// i.e., could be opened as a packet table.
+    pub chunked_1d: bool,
+    /// The class of the datatype.
+    pub class: H5T_class_t,
+    /// The size of one record in bytes.
+    pub record_size: usize,
+    /// Number of the elements, which is the number of packets if 1-dimensional.
+    pub num_packets: u64,
+}
+
+impl PacketTable {
+    /// Determine if the link exists and points to a dataset, without opening it.
+    pub fn exists(loc: &impl AsGroup, name: impl AsRef<str>) -> Result<bool> {
+        let group = loc.as_group();
+        let name = name.as_ref();
+        Ok(group.link_exists(name) && group.loc_type_by_name(name)? == LocationType::Dataset)
+    }
+
+    /// Gather the facts of the dataset without creating a packet table handle.
+    /// Returns `None` if the link doesn't exist or doesn't point to a dataset.
+    pub fn probe(loc: &impl AsGroup, name: impl AsRef<str>) -> Result<Option<TableProbe>> {
+        let name = name.as_ref();
+        if !Self::exists(loc, name)? {
+            return Ok(None);
+        }
+        let dset = loc.as_group().dataset(name)?;
+        let dtype = dset.dtype()?;
+        let class = h5lock!(H5Tget_class(dtype.id()));
+        if class == H5T_class_t::H5T_NO_CLASS {
+            return Err(Error::Other("Invalid datatype class.".to_string()));
+        }
+        Ok(Some(TableProbe {
+            chunked_1d: dset.is_chunked() && dset.ndim() == 1,
+            class,
+            record_size: dtype.size(),
+            num_packets: dset.size() as u64,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_util::open_ids, *};
+    use hdf5_sys::h5t::H5T_class_t;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn probe() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        data.create_group("group").unwrap();
+        data.new_dataset::<i32>()
+            .shape(4)
+            .create("contiguous")
+            .unwrap();
+        let mut table = data
+            .new_packet_table()
+            .chunk(16)
+            .dtype::<f64>()
+            .create("table")
+            .unwrap();
+        table.append(&[1.0, 2.0, 3.0]).unwrap();
+        drop(table);
+
+        let ids = open_ids(&data);
+        assert!(PacketTable::exists(&data, "table").unwrap());
+        assert!(!PacketTable::exists(&data, "missing").unwrap());
+        assert!(!PacketTable::exists(&data, "group").unwrap());
+        assert!(PacketTable::exists(&data, "contiguous").unwrap());
+
+        assert_eq!(
+            PacketTable::probe(&data, "table").unwrap(),
+            Some(TableProbe {
+                chunked_1d: true,
+                class: H5T_class_t::H5T_FLOAT,
+                record_size: 8,
+                num_packets: 3,
+            })
+        );
+        assert_eq!(PacketTable::probe(&data, "missing").unwrap(), None);
+        assert_eq!(PacketTable::probe(&data, "group").unwrap(), None);
+        assert_eq!(
+            PacketTable::probe(&data, "contiguous").unwrap(),
+            Some(TableProbe {
+                chunked_1d: false,
+                class: H5T_class_t::H5T_INTEGER,
+                record_size: 4,
+                num_packets: 4,
+            })
+        );
+        assert_eq!(open_ids(&data), ids);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::*;
+use hdf5::{h5try, types::TypeDescriptor, H5Type};
+use hdf5_hl_sys::h5pt::{H5PTfree_vlen_buff, H5PTread_packets};
+use std::{
+    io::{ErrorKind, Read, Write},
+    mem::MaybeUninit,
+    ops::RangeBounds,
+};
+
+/// Determine if the type contains pointers in memory.
+pub(crate) fn contains_pointers(ty: &TypeDescriptor) -> bool {
+    match ty {
+        TypeDescriptor::VarLenArray(_)
+        | TypeDescriptor::VarLenAscii
+        | TypeDescriptor::VarLenUnicode => true,
+        TypeDescriptor::FixedArray(ty, _) => contains_pointers(ty),
+        TypeDescriptor::Compound(ty) => ty.fields.iter().any(|f| contains_pointers(&f.ty)),
+        _ => false,
+    }
+}
+
+/// Copy the bytes of a value with its descriptor, to be written when creating.
+///
+/// The bytes are empty if the type contains pointers, which would dangle
+/// once the value is dropped.
+pub(crate) fn value_bytes<T: H5Type>(val: &T) -> (TypeDescriptor, Box<[MaybeUninit<u8>]>) {
+    let desc = <T as H5Type>::type_descriptor();
+    if contains_pointers(&desc) {
+        return (desc, Box::new([]));
+    }
+    // SAFETY: the bytes are copied as uninitialized, because of the padding.
+    let bytes = unsafe {
+        std::slice::from_raw_parts(val as *const T as *const MaybeUninit<u8>, size_of::<T>())
+    };
+    (desc, bytes.into())
+}
+
+impl PacketTable {
+    /// Get the packet size in bytes, rejecting the types containing pointers.
+    pub(crate) fn raw_packet_size(&self) -> Result<usize> {
+        let dtype = self.dtype()?;
+        if contains_pointers(&dtype.to_descriptor()?) {
+            return Err(Error::Other(
+                "Raw bytes of variable length packets are not supported.".to_string(),
+            ));
+        }
+        Ok(dtype.size())
+    }
+
+    /// Get the number of packets in one batch of bulk operations.
+    pub(crate) fn batch_len(&self) -> Result<usize> {
+        let chunk = self.dataset()?.chunk();
+        Ok(chunk
+            .and_then(|c| c.first().copied())
+            .unwrap_or(1024)
+            .max(1))
+    }
+
+    /// Read the raw bytes of `count` packets from a specified packet index.
+    ///
+    /// The bytes are in the layout of the stored datatype, without conversion.
+    /// Variable length tables are rejected, because their raw bytes contain pointers.
+    pub fn read_raw(&self, start: u64, count: usize) -> Result<Vec<u8>> {
+        self.check_remaining(start, count)?;
+        let size = self.raw_packet_size()?;
+        let mut buffer = vec![0u8; count * size];
+        h5try!(H5PTread_packets(
+            self.checked_id(),
+            start,
+            count,
+            buffer.as_mut_ptr() as *mut _
+        ));
+        Ok(buffer)
+    }
+
+    /// Write the raw bytes of the packets in the range to the writer.
+    /// Returns the number of bytes written.
+    ///
+    /// Variable length tables are rejected, because their raw bytes contain pointers.
+    pub fn copy_raw_to<W: Write>(&self, w: &mut W, range: impl RangeBounds<u64>) -> Result<u64> {
+        let (start, end) = self.resolve_range(range)?;
+        let size = self.raw_packet_size()?;
+        let batch = self.batch_len()?;
+        let mut buffer = vec![0u8; batch * size];
+        let mut reporter = self.reporter("copy_raw_to", Some(end - start));
+        let mut written = 0u64;
+        let mut index = start;
+        while index < end {
+            self.check_cancelled(index - start)?;
+            let count = (end - index).min(batch as u64) as usize;
+            let bytes = &mut buffer[..count * size];
+            h5try!(H5PTread_packets(
+                self.checked_id(),
+                index,
+                count,
+                bytes.as_mut_ptr() as *mut _
+            ));
+            w.write_all(bytes)?;
+            written += bytes.len() as u64;
+            index += count as u64;
+            reporter.update(index - start, written);
+        }
+        reporter.finish(end - start, written);
+        Ok(written)
+    }
+
+    /// Append the raw bytes read from the reader until the end of the stream.
+    /// Returns the number of packets appended.
+    ///
+    /// If a [`RecordTransform`] is set, the stream contains the input records.
+    /// The stream should contain whole packets. If it ends in the middle of a packet,
+    /// [`Error::TruncatedPacket`] reports how many packets have been appended,
+    /// and if reading fails, [`Error::PartialImport`] does.
+    /// If cancelled, the appended packets are kept.
+    pub fn append_raw_from<R: Read>(&mut self, r: &mut R) -> Result<u64> {
+        let size = match &self.transform {
+            Some(transform) => {
+                if contains_pointers(&transform.input) {
+                    return Err(Error::Other(
+                        "Raw bytes of variable length packets are not supported.".to_string(),
+                    ));
+                }
+                transform.input.size()
+            }
+            None => self.raw_packet_size()?,
+        };
+        let batch = self.batch_len()? * 4;
+        let mut buffer = vec![0u8; batch * size];
+        let mut filled = 0;
+        let mut committed = 0u64;
+        // The reporter borrows its own handle, because appending borrows the table mutably.
+        let progress = self.progress.clone();
+        let mut reporter = ProgressReporter::new(progress.as_deref(), "append_raw_from", None);
+        loop {
+            self.check_cancelled(committed)?;
+            let n = match r.read(&mut buffer[filled..]) {
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    return Err(Error::PartialImport {
+                        committed,
+                        source: Box::new(Error::Io(e)),
+                    });
+                }
+            };
+            filled += n;
+            if n == 0 || filled == buffer.len() {
+                let count = filled / size;
+                if count > 0 {
+                    self.append_ptr(count, buffer.as_ptr() as *const _)?;
+                    committed += count as u64;
+                    reporter.update(committed, committed * size as u64);
+                }
+                let rest = filled - count * size;
+                if n == 0 {
+                    if rest != 0 {
+                        return Err(Error::TruncatedPacket {
+                            committed,
+                            trailing_bytes: rest,
+                        });
+                    }
+                    reporter.finish(committed, committed * size as u64);
+                    return Ok(committed);
+                }
+                buffer.copy_within(count * size..filled, 0);
+                filled = rest;
+            }
+        }
+    }
+
+    /// Copy the packets in the range to another packet table with the same datatype.
+    /// Returns the number of packets copied.
+    ///
+    /// Variable length packets are supported, and the buffers allocated by HDF5 are freed.
+    pub fn copy_to(&self, dst: &mut PacketTable, range: impl RangeBounds<u64>) -> Result<u64> {
+        let (start, end) = self.resolve_range(range)?;
+        let dtype = self.dtype()?;
+        if dtype != dst.dtype()? {
+            return Err(Error::Other(
+                "Datatype mismatch between the packet tables.".to_string(),
+            ));
+        }
+        let varlen = contains_pointers(&dtype.to_descriptor()?);
+        let size = dtype.size();
+        let batch = self.batch_len()?;
+        let mut buffer = vec![0u8; batch * size];
+        let mut reporter = self.reporter("copy_to", Some(end - start));
+        let mut index = start;
+        while index < end {
+            self.check_cancelled(index - start)?;
+            let count = (end - index).min(batch as u64) as usize;
+            let bytes = &mut buffer[..count * size];
+            h5try!(H5PTread_packets(
+                self.checked_id(),
+                index,
+                count,
+                bytes.as_mut_ptr() as *mut _
+            ));
+            let res = dst.append_stored(count, bytes.as_ptr() as *const _);
+            if varlen {
+                h5try!(H5PTfree_vlen_buff(
+                    self.checked_id(),
+                    count as _,
+                    bytes.as_mut_ptr() as *mut _
+                ));
+            }
+            res?;
+            index += count as u64;
+            reporter.update(index - start, (index - start) * size as u64);
+        }
+        reporter.finish(end - start, (end - start) * size as u64);
+        Ok(end - start)
+    }
+}
@@ -0,0 +1,137 @@
+use crate::*;
+use dst_container::*;
+
+/// A packet reader of [`PacketTable`] with its own position.
+///
+/// The position is kept in Rust, so the readers don't touch the index of the
+/// packet table, and several readers over the same table could interleave freely.
+#[derive(Debug, Clone)]
+pub struct PacketTableReader<'a> {
+    table: &'a PacketTable,
+    position: u64,
+}
+
+impl<'a> PacketTableReader<'a> {
+    pub(crate) fn new(table: &'a PacketTable) -> Self {
+        Self { table, position: 0 }
+    }
+
+    /// Get the packet table.
+    pub fn table(&self) -> &'a PacketTable {
+        self.table
+    }
+
+    /// Get the current position.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Move to the position. It could be at most the number of packets.
+    pub fn seek(&mut self, position: u64) -> Result<()> {
+        let num_packets = self.table.num_packets()?;
+        if position > num_packets {
+            return Err(Error::InvalidIndex {
+                index: self.position,
+                delta: position.wrapping_sub(self.position) as i64,
+                num_packets,
+            });
+        }
+        self.position = position;
+        Ok(())
+    }
+
+    /// Read `len` packets from the current position, and advance the position.
+    pub fn read_next<T>(&mut self, len: usize) -> Result<Vec<T>> {
+        self.table.check_remaining(self.position, len)?;
+        let vec = self.table.read(self.position, len)?;
+        self.position += len as u64;
+        Ok(vec)
+    }
+
+    /// Read one packet from the current position, and advance the position.
+    /// Returns `None` if the position has reached the end of the table.
+    pub fn read_next_one<T>(&mut self) -> Result<Option<T>> {
+        if self.position >= self.table.num_packets()? {
+            return Ok(None);
+        }
+        let mut vec = self.table.read(self.position, 1)?;
+        self.position += 1;
+        Ok(vec.pop())
+    }
+
+    /// Read `len` unsized packets from the current position into the buffer,
+    /// and advance the position.
+    pub fn read_next_unsized<T: ?Sized>(
+        &mut self,
+        len: usize,
+        buffer: &mut FixedVec<T>,
+    ) -> Result<()> {
+        self.table.check_remaining(self.position, len)?;
+        self.table.read_unsized(self.position, len, buffer)?;
+        self.position += len as u64;
+        Ok(())
+    }
+}
+
+impl PacketTable {
+    /// Create a reader with its own position, starting from the first packet.
+    /// It doesn't influence the index of the packet table.
+    pub fn reader(&self) -> PacketTableReader<'_> {
+        PacketTableReader::new(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn interleave() {
+        let file = NamedTempFile::new().unwrap();
+        let manifest = fixtures::standard_file(file.path(), 114514).unwrap();
+        let entry = manifest.get(fixtures::I32_TABLE).unwrap();
+
+        let data = hdf5::File::open(file.path()).unwrap();
+        let table = PacketTable::open(&data, fixtures::I32_TABLE).unwrap();
+        let count = entry.count as u64;
+        let packets = table.read_all::<i32>().unwrap();
+        assert_eq!(
+            fixtures::checksum(&table.read_raw(0, entry.count).unwrap()),
+            entry.checksum
+        );
+
+        let mut fast = table.reader();
+        let mut slow = table.reader();
+        let (mut fast_read, mut slow_read) = (vec![], vec![]);
+        loop {
+            let len = 7.min(table.num_packets().unwrap() - fast.position()) as usize;
+            if len > 0 {
+                fast_read.extend(fast.read_next::<i32>(len).unwrap());
+            }
+            match slow.read_next_one::<i32>().unwrap() {
+                Some(val) => slow_read.push(val),
+                None => break,
+            }
+        }
+        assert_eq!(fast_read, packets);
+        assert_eq!(slow_read, packets);
+        assert_eq!(table.index().unwrap(), 0);
+
+        assert!(matches!(
+            fast.read_next::<i32>(1),
+            Err(Error::NotEnoughPackets { start, .. }) if start == count
+        ));
+        assert!(fast.seek(count + 1).is_err());
+        fast.seek(count - 2).unwrap();
+        assert_eq!(
+            fast.read_next::<i32>(2).unwrap(),
+            packets[entry.count - 2..]
+        );
+
+        let mut cloned = slow.clone();
+        cloned.seek(0).unwrap();
+        assert_eq!(cloned.read_next_one::<i32>().unwrap(), Some(packets[0]));
+        assert_eq!(slow.position(), count);
+    }
+}
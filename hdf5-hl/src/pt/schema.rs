@@ -0,0 +1,131 @@
+use crate::*;
+use hdf5::Datatype;
+use hdf5_dst::H5TypeUnsized;
+use std::ptr::Pointee;
+
+impl PacketTable {
+    /// Open an existing packet table, and check the stored datatype against `T`.
+    ///
+    /// Returns [`Error::SchemaMismatch`] if the datatypes are not equal.
+    pub fn open_as<T: ?Sized + H5TypeUnsized>(
+        loc: &impl AsGroup,
+        dset_name: impl AsRef<str>,
+        metadata: <T as Pointee>::Metadata,
+    ) -> Result<Self> {
+        let table = Self::open(loc, dset_name)?;
+        let ptr: *const T = std::ptr::from_raw_parts(std::ptr::null::<()>(), metadata);
+        // SAFETY: the type descriptor only depends on the metadata.
+        let expected = unsafe { (*ptr).type_descriptor() };
+        let actual = table.dtype()?;
+        if Datatype::from_descriptor(&expected)? != actual {
+            return Err(Error::SchemaMismatch {
+                expected: Box::new(expected),
+                actual: Box::new(actual.to_descriptor()?),
+            });
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use dst_container::*;
+    use hdf5_dst::H5TypeUnsized;
+    use std::mem::MaybeUninit;
+    use tempfile::NamedTempFile;
+
+    #[derive(H5TypeUnsized)]
+    #[h5(table)]
+    #[repr(C)]
+    struct Point {
+        x: f64,
+        y: f64,
+    }
+
+    #[derive(H5TypeUnsized)]
+    #[h5(table)]
+    #[repr(C)]
+    struct Event {
+        id: u32,
+        time: f64,
+    }
+
+    #[derive(MaybeUninitProject, H5TypeUnsized)]
+    #[h5(table)]
+    #[repr(C)]
+    struct Trace {
+        id: u32,
+        samples: [i32],
+    }
+
+    #[derive(H5TypeUnsized)]
+    #[h5(table)]
+    #[repr(C)]
+    struct Run {
+        run: u32,
+        #[h5(unsized)]
+        trace: Trace,
+    }
+
+    #[test]
+    fn sized() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let table = Point::create_table(&data, "points", 16).unwrap();
+        assert_eq!(table.dtype().unwrap().size(), size_of::<Point>());
+        drop(table);
+        Point::open_table(&data, "points").unwrap();
+
+        let err = Event::open_table(&data, "points").unwrap_err();
+        let Error::SchemaMismatch { expected, actual } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_ne!(expected, actual);
+        let msg = err.to_string();
+        assert!(msg.contains("- x"), "{msg}");
+        assert!(msg.contains("+ id"), "{msg}");
+    }
+
+    #[test]
+    fn dst() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        let mut table = Trace::create_table(&data, "traces", 16, 4).unwrap();
+        let mut vec = FixedVec::<Trace>::new(4);
+        unsafe {
+            vec.push_with(|t| {
+                t.id.write(1);
+                MaybeUninit::copy_from_slice(&mut t.samples, &[1, 1, 4, 5]);
+            })
+        };
+        table.append_unsized(&vec).unwrap();
+        drop(table);
+
+        let table = Trace::open_table(&data, "traces", 4).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 1);
+        assert!(matches!(
+            Trace::open_table(&data, "traces", 3),
+            Err(Error::SchemaMismatch { .. })
+        ));
+        assert!(matches!(
+            Point::open_table(&data, "traces"),
+            Err(Error::SchemaMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn nested_dst() {
+        let file = NamedTempFile::new().unwrap();
+
+        let data = hdf5::File::create(file.path()).unwrap();
+        Run::create_table(&data, "runs", 16, 4).unwrap();
+        Run::open_table(&data, "runs", 4).unwrap();
+        assert!(matches!(
+            Run::open_table(&data, "runs", 3),
+            Err(Error::SchemaMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::*;
+use hdf5::{filters::Filter, plist::DatasetCreate};
+
+/// Storage statistics of a [`PacketTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketTableStats {
+    /// Number of packets.
+    pub num_packets: u64,
+    /// Size of one packet in memory.
+    pub packet_size: usize,
+    /// Allocated storage size in bytes.
+    pub storage_size: u64,
+    /// Number of allocated chunks.
+    pub allocated_chunks: u64,
+}
+
+impl PacketTableStats {
+    /// Get the ratio of the packet size in memory to the allocated storage size,
+    /// or `None` if no storage is allocated.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        if self.storage_size == 0 {
+            None
+        } else {
+            Some((self.num_packets * self.packet_size as u64) as f64 / self.storage_size as f64)
+        }
+    }
+}
+
+impl PacketTable {
+    /// Get the allocated storage size in bytes.
+    pub fn storage_size(&self) -> Result<u64> {
+        Ok(self.dataset()?.storage_size())
+    }
+
+    /// Get the number of allocated chunks.
+    pub fn allocated_chunks(&self) -> Result<u64> {
+        Ok(self.dataset()?.num_chunks().unwrap_or_default() as u64)
+    }
+
+    /// Get the [`DatasetCreate`] property list of the underlying dataset.
+    ///
+    /// It could be passed to [`PacketTableBuilder::plist`] to create a table
+    /// with the same chunk, filters and fill value.
+    pub fn create_plist(&self) -> Result<DatasetCreate> {
+        Ok(self.dataset()?.dcpl()?)
+    }
+
+    /// Get the filters in the pipeline.
+    ///
+    /// The filters reflect the settings when the table was created,
+    /// and some chunks may be stored unfiltered if a filter failed.
+    pub fn filters(&self) -> Result<Vec<Filter>> {
+        Ok(self.create_plist()?.filters())
+    }
+
+    /// Check that every filter in the pipeline is available,
+    /// failing with [`Error::FilterUnavailable`] for the first missing one.
+    ///
+    /// The packets cannot be read or appended without the filters.
+    pub fn check_filters(&self) -> Result<()> {
+        for filter in self.filters()? {
+            let id = filter.id();
+            if !filter_available(id) {
+                return Err(Error::FilterUnavailable { id });
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the deflate level, if the table is compressed with deflate.
+    ///
+    /// See [`PacketTable::filters`] for the limitations.
+    pub fn compression(&self) -> Result<Option<u8>> {
+        Ok(self.filters()?.into_iter().find_map(|f| match f {
+            Filter::Deflate(level) => Some(level),
+            _ => None,
+        }))
+    }
+
+    /// Get the storage statistics.
+    pub fn stats(&self) -> Result<PacketTableStats> {
+        let dataset = self.dataset()?;
+        Ok(PacketTableStats {
+            num_packets: self.num_packets()?,
+            packet_size: self.packet_size,
+            storage_size: dataset.storage_size(),
+            allocated_chunks: dataset.num_chunks().unwrap_or_default() as u64,
+        })
+    }
+}
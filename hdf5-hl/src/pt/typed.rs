@@ -0,0 +1,217 @@
+use super::size_of_metadata;
+use crate::*;
+use dst_container::*;
+use hdf5::{Datatype, H5Type};
+use hdf5_dst::H5TypeUnsized;
+use std::{fmt::Debug, ptr::Pointee};
+
+/// A [`PacketTable`] with cached element type.
+///
+/// The packet size is checked once on creation, and the [`Datatype`] too
+/// if the cheap checks are enabled. The writes skip the per-call checks of [`PacketTable`],
+/// and only compare the metadata, and the [`Datatype`] if the expensive checks are enabled.
+pub struct TypedPacketTable<T: ?Sized> {
+    table: PacketTable,
+    dtype: Datatype,
+    metadata: <T as Pointee>::Metadata,
+}
+
+impl<T: ?Sized> Debug for TypedPacketTable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<HDF5: typed packet table>")
+    }
+}
+
+impl PacketTable {
+    /// Create a typed view with a cached [`Datatype`].
+    pub fn typed<T: H5Type>(self) -> Result<TypedPacketTable<T>> {
+        let dtype = Datatype::from_type::<T>()?;
+        TypedPacketTable::new(self, dtype, ())
+    }
+
+    /// Create a typed view of unsized packets with a cached [`Datatype`] built from the metadata.
+    pub fn typed_unsized<T: ?Sized + H5TypeUnsized>(
+        self,
+        metadata: <T as Pointee>::Metadata,
+    ) -> Result<TypedPacketTable<T>> {
+        let ptr: *const T = std::ptr::from_raw_parts(std::ptr::null::<()>(), metadata);
+        // SAFETY: only the metadata is used.
+        let dtype = Datatype::from_descriptor(&unsafe { (*ptr).type_descriptor() })?;
+        TypedPacketTable::new(self, dtype, metadata)
+    }
+}
+
+/// A complete builder of [`TypedPacketTable`], which keeps the element type.
+///
+/// The settings should be set on [`PacketTableBuilder`] before the element type.
+pub struct PacketTableBuilderTypedFor<T: ?Sized> {
+    builder: PacketTableBuilderTyped,
+    metadata: <T as Pointee>::Metadata,
+}
+
+impl<T: ?Sized> Clone for PacketTableBuilderTypedFor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            builder: self.builder.clone(),
+            metadata: self.metadata,
+        }
+    }
+}
+
+impl PacketTableBuilder {
+    /// Set the element type of the packet table, and keep it for [`TypedPacketTable`].
+    pub fn dtype_for<T: H5Type>(self) -> PacketTableBuilderTypedFor<T> {
+        PacketTableBuilderTypedFor {
+            builder: self.dtype::<T>(),
+            metadata: (),
+        }
+    }
+
+    /// Set the unsized element type of the packet table with raw pointee metadata,
+    /// and keep it for [`TypedPacketTable`].
+    pub fn dtype_unsized_for<T: ?Sized + H5TypeUnsized>(
+        self,
+        metadata: <T as Pointee>::Metadata,
+    ) -> PacketTableBuilderTypedFor<T> {
+        PacketTableBuilderTypedFor {
+            builder: self.dtype_unsized::<T>(metadata),
+            metadata,
+        }
+    }
+}
+
+impl<T: ?Sized + H5TypeUnsized> PacketTableBuilderTypedFor<T> {
+    /// Create the [`TypedPacketTable`] of unsized packets with the metadata.
+    pub fn create_typed_unsized(self, table_name: impl AsRef<str>) -> Result<TypedPacketTable<T>> {
+        self.builder
+            .create(table_name)?
+            .typed_unsized::<T>(self.metadata)
+    }
+
+    /// Forget the element type.
+    pub fn untyped(self) -> PacketTableBuilderTyped {
+        self.builder
+    }
+}
+
+impl<T: H5Type> PacketTableBuilderTypedFor<T> {
+    /// Create the [`TypedPacketTable`].
+    pub fn create_typed(self, table_name: impl AsRef<str>) -> Result<TypedPacketTable<T>> {
+        self.builder.create(table_name)?.typed::<T>()
+    }
+}
+
+impl<T: ?Sized> TypedPacketTable<T> {
+    fn new(
+        table: PacketTable,
+        dtype: Datatype,
+        metadata: <T as Pointee>::Metadata,
+    ) -> Result<Self> {
+        let typed = Self {
+            table,
+            dtype,
+            metadata,
+        };
+        typed
+            .table
+            .check_input_size(size_of_metadata::<T>(metadata))?;
+        if typed.table.validation_profile().cheap_checks() {
+            typed.check_dtype()?;
+        }
+        Ok(typed)
+    }
+
+    fn check_dtype(&self) -> Result<()> {
+        if self.table.dtype()? != self.dtype {
+            return Err(Error::SchemaMismatch {
+                expected: Box::new(self.dtype.to_descriptor()?),
+                actual: Box::new(self.table.type_descriptor()?),
+            });
+        }
+        Ok(())
+    }
+
+    /// The packet size depends on the metadata, so its check cannot be skipped.
+    fn check(&self, metadata: <T as Pointee>::Metadata) -> Result<()> {
+        if metadata != self.metadata {
+            return Err(Error::MetadataMismatch {
+                expected_size: size_of_metadata::<T>(self.metadata),
+                actual_size: size_of_metadata::<T>(metadata),
+            });
+        }
+        if self.table.validation_profile().expensive_checks() {
+            self.check_dtype()?;
+        }
+        Ok(())
+    }
+
+    /// Get the cached [`Datatype`].
+    pub fn cached_dtype(&self) -> &Datatype {
+        &self.dtype
+    }
+
+    /// Get the underlying [`PacketTable`].
+    pub fn inner(&self) -> &PacketTable {
+        &self.table
+    }
+
+    /// Get the underlying [`PacketTable`] mutably.
+    pub fn inner_mut(&mut self) -> &mut PacketTable {
+        &mut self.table
+    }
+
+    /// Unwrap the underlying [`PacketTable`].
+    pub fn into_inner(self) -> PacketTable {
+        self.table
+    }
+
+    /// Push one element into the packet table.
+    pub fn push(&mut self, val: &T) -> Result<()>
+    where
+        T: H5TypeUnsized,
+    {
+        let (ptr, metadata) = (val as *const T).to_raw_parts();
+        self.check(metadata)?;
+        self.table.append_ptr(1, ptr)
+    }
+
+    /// Append an unsized vector into the packet table.
+    pub fn append_unsized(&mut self, vec: &FixedVec<T>) -> Result<()>
+    where
+        T: H5TypeUnsized,
+    {
+        if vec.is_empty() {
+            return Ok(());
+        }
+        let (ptr, metadata) = vec.as_ptr().to_raw_parts();
+        self.check(metadata)?;
+        self.table.append_ptr(vec.len(), ptr)
+    }
+
+    /// Read from a specified packet index and take some data.
+    pub fn read_unsized(&self, start: u64, len: usize, buffer: &mut FixedVec<T>) -> Result<()> {
+        self.check(buffer.as_ptr().to_raw_parts().1)?;
+        self.table.read_unsized(start, len, buffer)
+    }
+}
+
+impl<T> TypedPacketTable<T> {
+    /// Append a slice into the packet table.
+    pub fn append(&mut self, slice: &[T]) -> Result<()>
+    where
+        T: H5Type,
+    {
+        self.check(())?;
+        if slice.is_empty() {
+            return Ok(());
+        }
+        self.table
+            .append_ptr(slice.len(), slice.as_ptr() as *const _)
+    }
+
+    /// Read from a specified packet index and take some data.
+    pub fn read(&self, start: u64, len: usize) -> Result<Vec<T>> {
+        self.check(())?;
+        self.table.read(start, len)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::*;
+use dst_container::*;
+use hdf5::{types::TypeDescriptor, Datatype, H5Type};
+use hdf5_dst::H5TypeUnsized;
+use std::{ops::RangeBounds, ptr::Pointee};
+
+/// A view of a [`PacketTable`] which could only append packets.
+///
+/// The full handle is unreachable through it:
+///
+/// ```compile_fail
+/// # use hdf5_hl::*;
+/// fn truncate(mut view: AppendOnlyTable<'_>) {
+///     view.truncate(0).unwrap();
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use hdf5_hl::*;
+/// fn read(view: AppendOnlyTable<'_>) {
+///     view.read_all::<u32>().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AppendOnlyTable<'a>(&'a mut PacketTable);
+
+impl AppendOnlyTable<'_> {
+    /// See [`PacketTable::push`].
+    pub fn push<T: ?Sized + H5TypeUnsized>(&mut self, val: &T) -> Result<()> {
+        self.0.push(val)
+    }
+
+    /// See [`PacketTable::append`].
+    pub fn append<T: H5Type>(&mut self, slice: &[T]) -> Result<()> {
+        self.0.append(slice)
+    }
+
+    /// See [`PacketTable::append_many`].
+    pub fn append_many<T: H5Type>(&mut self, batches: &[&[T]]) -> Result<()> {
+        self.0.append_many(batches)
+    }
+
+    /// See [`PacketTable::append_unsized`].
+    pub fn append_unsized<T: ?Sized + H5TypeUnsized>(&mut self, vec: &FixedVec<T>) -> Result<()> {
+        self.0.append_unsized(vec)
+    }
+
+    /// See [`PacketTable::flush`].
+    pub fn flush(&self) -> Result<()> {
+        self.0.flush()
+    }
+
+    /// See [`PacketTable::num_packets`].
+    pub fn num_packets(&self) -> Result<u64> {
+        self.0.num_packets()
+    }
+
+    /// See [`PacketTable::type_descriptor`].
+    pub fn type_descriptor(&self) -> Result<TypeDescriptor> {
+        self.0.type_descriptor()
+    }
+}
+
+/// A view of a [`PacketTable`] which could only read packets.
+///
+/// The full handle is unreachable through it:
+///
+/// ```compile_fail
+/// # use hdf5_hl::*;
+/// fn append(mut view: ReadOnlyView<'_>) {
+///     view.append(&[0u32]).unwrap();
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use hdf5_hl::*;
+/// fn dataset(view: ReadOnlyView<'_>) {
+///     view.dataset().unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOnlyView<'a>(&'a PacketTable);
+
+impl<'a> ReadOnlyView<'a> {
+    /// See [`PacketTable::num_packets`].
+    pub fn num_packets(&self) -> Result<u64> {
+        self.0.num_packets()
+    }
+
+    /// See [`PacketTable::len`].
+    pub fn len(&self) -> Result<usize> {
+        self.0.len()
+    }
+
+    /// See [`PacketTable::is_empty`].
+    pub fn is_empty(&self) -> Result<bool> {
+        self.0.is_empty()
+    }
+
+    /// See [`PacketTable::dtype`].
+    pub fn dtype(&self) -> Result<Datatype> {
+        self.0.dtype()
+    }
+
+    /// See [`PacketTable::type_descriptor`].
+    pub fn type_descriptor(&self) -> Result<TypeDescriptor> {
+        self.0.type_descriptor()
+    }
+
+    /// See [`PacketTable::read`].
+    pub fn read<T>(&self, start: u64, len: usize) -> Result<Vec<T>> {
+        self.0.read(start, len)
+    }
+
+    /// See [`PacketTable::read_all`].
+    pub fn read_all<T>(&self) -> Result<Vec<T>> {
+        self.0.read_all()
+    }
+
+    /// See [`PacketTable::get`].
+    pub fn get<T>(&self, index: u64) -> Result<T> {
+        self.0.get(index)
+    }
+
+    /// See [`PacketTable::read_array`].
+    pub fn read_array<T, const N: usize>(&self, start: u64) -> Result<[T; N]> {
+        self.0.read_array(start)
+    }
+
+    /// See [`PacketTable::read_unsized`].
+    pub fn read_unsized<T: ?Sized>(
+        &self,
+        start: u64,
+        len: usize,
+        buffer: &mut FixedVec<T>,
+    ) -> Result<()> {
+        self.0.read_unsized(start, len, buffer)
+    }
+
+    /// See [`PacketTable::read_fields`].
+    pub fn read_fields<T: H5Type>(&self, range: impl RangeBounds<u64>) -> Result<Vec<T>> {
+        self.0.read_fields(range)
+    }
+
+    /// See [`PacketTable::iter_indexed_from`].
+    pub fn iter_indexed_from<T>(
+        &self,
+        start: u64,
+    ) -> impl Iterator<Item = Result<(u64, T)>> + use<'a, T> {
+        self.0.iter_indexed_from(start)
+    }
+
+    /// See [`PacketTable::iter`].
+    pub fn iter<T>(&self) -> impl Iterator<Item = Result<T>> + use<'a, T> {
+        self.0.iter()
+    }
+
+    /// See [`PacketTable::for_each_packet`].
+    pub fn for_each_packet<T, F: FnMut(&T) -> Result<()>>(&self, chunk: usize, f: F) -> Result<()> {
+        self.0.for_each_packet(chunk, f)
+    }
+
+    /// See [`PacketTable::for_each_packet_unsized`].
+    pub fn for_each_packet_unsized<T: ?Sized, F: FnMut(&T) -> Result<()>>(
+        &self,
+        metadata: <T as Pointee>::Metadata,
+        chunk: usize,
+        f: F,
+    ) -> Result<()> {
+        self.0.for_each_packet_unsized(metadata, chunk, f)
+    }
+}
+
+impl PacketTable {
+    /// Get a view which could only append packets, e.g., for plugins.
+    pub fn append_only(&mut self) -> AppendOnlyTable<'_> {
+        AppendOnlyTable(self)
+    }
+
+    /// Get a view which could only read packets, e.g., for plugins.
+    ///
+    /// The view has no cursor sharing the index of the table,
+    /// use the iterators instead.
+    pub fn read_only(&self) -> ReadOnlyView<'_> {
+        ReadOnlyView(self)
+    }
+
+    /// Flush the file containing the packet table.
+    pub fn flush(&self) -> Result<()> {
+        self.dataset()?.file()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn views() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u32>()
+            .create("data")
+            .unwrap();
+
+        let mut append = table.append_only();
+        append.push(&0u32).unwrap();
+        append.append(&[1u32, 2]).unwrap();
+        append.append_many(&[&[3u32], &[4, 5]]).unwrap();
+        append.flush().unwrap();
+        assert_eq!(append.num_packets().unwrap(), 6);
+        assert!(append.append(&[0u64]).is_err());
+
+        let view = table.read_only();
+        assert_eq!(view.len().unwrap(), 6);
+        assert_eq!(view.read_all::<u32>().unwrap(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(view.get::<u32>(4).unwrap(), 4);
+        assert_eq!(view.read_array::<u32, 2>(1).unwrap(), [1, 2]);
+        assert_eq!(
+            view.iter::<u32>().collect::<Result<Vec<_>>>().unwrap(),
+            [0, 1, 2, 3, 4, 5]
+        );
+        let mut sum = 0;
+        view.for_each_packet::<u32, _>(4, |v| {
+            sum += v;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(sum, 15);
+    }
+}
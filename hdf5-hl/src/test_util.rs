@@ -0,0 +1,11 @@
+//! Helpers shared by the tests.
+
+use hdf5::h5lock;
+use hdf5_sys::h5f::{H5Fget_obj_count, H5F_OBJ_ALL};
+
+/// Count the open ids of the file, to check that none is leaked.
+pub fn open_ids(file: &hdf5::File) -> isize {
+    let count = h5lock!(H5Fget_obj_count(file.id(), H5F_OBJ_ALL));
+    assert!(count >= 0);
+    count
+}
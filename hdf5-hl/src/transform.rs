@@ -0,0 +1,336 @@
+use crate::{Error, Result};
+use hdf5::types::{CompoundField, CompoundType, TypeDescriptor};
+use std::{mem::MaybeUninit, sync::Arc};
+
+/// A transform applied to every record at the write boundary.
+///
+/// The table stores the output records, while the callers append or overwrite the input records.
+/// See [`PacketTableBuilderTyped::with_transform`](crate::PacketTableBuilderTyped::with_transform)
+/// and [`PacketTable::with_transform`](crate::PacketTable::with_transform).
+pub trait RecordTransform: Send + Sync {
+    /// Get the descriptor of the output records from the input one.
+    fn output_descriptor(&self, input: &TypeDescriptor) -> TypeDescriptor;
+
+    /// Transform one record. The lengths of the slices are the sizes of the descriptors.
+    ///
+    /// The bytes are uninitialized, because the records may contain padding.
+    /// The output is zeroed before calling.
+    fn transform(&self, input: &[MaybeUninit<u8>], output: &mut [MaybeUninit<u8>]) -> Result<()>;
+}
+
+/// A transform attached to a table or builder.
+#[derive(Clone)]
+pub(crate) struct TableTransform {
+    pub transform: Arc<dyn RecordTransform>,
+    pub input: TypeDescriptor,
+    pub output: TypeDescriptor,
+    // The output buffer reused between the writes.
+    staging: Vec<MaybeUninit<u8>>,
+}
+
+impl TableTransform {
+    /// Validate the output descriptor of the transform.
+    pub fn new(transform: Arc<dyn RecordTransform>, input: TypeDescriptor) -> Result<Self> {
+        let output = transform.output_descriptor(&input);
+        if output.size() == 0 {
+            return Err(Error::Other(
+                "The output descriptor of the transform is empty.".to_string(),
+            ));
+        }
+        if let TypeDescriptor::Compound(compound) = &output
+            && let Some(field) = compound
+                .fields
+                .iter()
+                .find(|f| f.offset + f.ty.size() > compound.size)
+        {
+            return Err(Error::Other(format!(
+                "The member {} exceeds the output size {}.",
+                field.name, compound.size
+            )));
+        }
+        hdf5_dst::check_descriptor(&output)?;
+        Ok(Self {
+            transform,
+            input,
+            output,
+            staging: vec![],
+        })
+    }
+
+    /// Transform `len` records into the staging buffer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` should point to `len` input records.
+    pub unsafe fn apply(&mut self, ptr: *const (), len: usize) -> Result<&[MaybeUninit<u8>]> {
+        let in_size = self.input.size();
+        let out_size = self.output.size();
+        // SAFETY: the bytes are read as uninitialized, because of the padding.
+        let input =
+            unsafe { std::slice::from_raw_parts(ptr as *const MaybeUninit<u8>, len * in_size) };
+        self.staging.clear();
+        self.staging.resize(len * out_size, MaybeUninit::new(0));
+        for (input, output) in input
+            .chunks_exact(in_size)
+            .zip(self.staging.chunks_exact_mut(out_size))
+        {
+            self.transform.transform(input, output)?;
+        }
+        Ok(&self.staging)
+    }
+}
+
+/// Get the alignment of the type in C.
+fn align_of(ty: &TypeDescriptor) -> usize {
+    match ty {
+        TypeDescriptor::Compound(ty) => {
+            ty.fields.iter().map(|f| align_of(&f.ty)).max().unwrap_or(1)
+        }
+        TypeDescriptor::FixedArray(ty, _) => align_of(ty),
+        TypeDescriptor::FixedAscii(_) | TypeDescriptor::FixedUnicode(_) => 1,
+        TypeDescriptor::VarLenArray(_)
+        | TypeDescriptor::VarLenAscii
+        | TypeDescriptor::VarLenUnicode => std::mem::align_of::<usize>(),
+        ty => ty.size().max(1),
+    }
+}
+
+/// A [`RecordTransform`] dropping compound members by name.
+///
+/// The remaining members are laid out in order like `#[repr(C)]`,
+/// so that the layout matches the native type when the table is opened again.
+#[derive(Debug, Clone)]
+pub struct DropMembers {
+    names: Vec<String>,
+    // (input offset, output offset, size) of the remaining members.
+    plan: Vec<(usize, usize, usize)>,
+}
+
+impl DropMembers {
+    /// Create the transform for the input descriptor.
+    /// The input should be a compound containing all the names.
+    pub fn new(input: &TypeDescriptor, names: &[&str]) -> Result<Self> {
+        let TypeDescriptor::Compound(compound) = input else {
+            return Err(Error::Other(
+                "Only compound members could be dropped.".to_string(),
+            ));
+        };
+        if let Some(name) = names
+            .iter()
+            .find(|name| !compound.fields.iter().any(|f| f.name == **name))
+        {
+            return Err(Error::Other(format!("No member named {name}.")));
+        }
+        let mut this = Self {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            plan: vec![],
+        };
+        let TypeDescriptor::Compound(output) = this.output_descriptor(input) else {
+            unreachable!()
+        };
+        this.plan = output
+            .fields
+            .iter()
+            .map(|out| {
+                let field = compound.fields.iter().find(|f| f.name == out.name).unwrap();
+                (field.offset, out.offset, out.ty.size())
+            })
+            .collect();
+        Ok(this)
+    }
+}
+
+impl RecordTransform for DropMembers {
+    fn output_descriptor(&self, input: &TypeDescriptor) -> TypeDescriptor {
+        let TypeDescriptor::Compound(compound) = input else {
+            return input.clone();
+        };
+        let mut offset = 0usize;
+        let mut align = 1;
+        let fields = compound
+            .fields
+            .iter()
+            .filter(|f| !self.names.contains(&f.name))
+            .enumerate()
+            .map(|(index, f)| {
+                let field_align = align_of(&f.ty);
+                align = align.max(field_align);
+                offset = offset.next_multiple_of(field_align);
+                let field = CompoundField::new(&f.name, f.ty.clone(), offset, index);
+                offset += f.ty.size();
+                field
+            })
+            .collect();
+        TypeDescriptor::Compound(CompoundType {
+            fields,
+            size: offset.next_multiple_of(align),
+        })
+    }
+
+    fn transform(&self, input: &[MaybeUninit<u8>], output: &mut [MaybeUninit<u8>]) -> Result<()> {
+        for &(from, to, size) in &self.plan {
+            output[to..to + size].copy_from_slice(&input[from..from + size]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use hdf5::{
+        types::{CompoundField, CompoundType, TypeDescriptor},
+        H5Type,
+    };
+    use std::mem::MaybeUninit;
+    use tempfile::NamedTempFile;
+
+    #[derive(H5Type, Clone, Copy)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        debug: u64,
+        value: f64,
+    }
+
+    #[derive(H5Type, Debug, PartialEq)]
+    #[repr(C)]
+    struct Kept {
+        id: u32,
+        value: f64,
+    }
+
+    struct BadSize;
+
+    impl RecordTransform for BadSize {
+        fn output_descriptor(&self, _input: &TypeDescriptor) -> TypeDescriptor {
+            TypeDescriptor::Compound(CompoundType {
+                fields: vec![CompoundField::new("id", u32::type_descriptor(), 0, 0)],
+                size: 2,
+            })
+        }
+
+        fn transform(
+            &self,
+            _input: &[MaybeUninit<u8>],
+            _output: &mut [MaybeUninit<u8>],
+        ) -> Result<()> {
+            unreachable!()
+        }
+    }
+
+    fn record(i: u32) -> Record {
+        Record {
+            id: i,
+            debug: 0xdead,
+            value: i as f64 / 2.0,
+        }
+    }
+
+    #[test]
+    fn drop_members() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let transform = DropMembers::new(&Record::type_descriptor(), &["debug"]).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Record>()
+            .with_transform(Box::new(transform))
+            .unwrap()
+            .create("data")
+            .unwrap();
+        table.append(&[record(0), record(1)]).unwrap();
+        table.push(&record(2)).unwrap();
+        {
+            let mut writer = PacketTableBufWriter::<Record>::new(&mut table, 2);
+            for i in 3..6 {
+                writer.push(record(i)).unwrap();
+            }
+        }
+
+        let TypeDescriptor::Compound(stored) = table.dtype().unwrap().to_descriptor().unwrap()
+        else {
+            unreachable!()
+        };
+        assert!(stored.fields.iter().all(|f| f.name != "debug"));
+        assert_eq!(stored.size, size_of::<Kept>());
+        assert_eq!(
+            table.read_fields::<Kept>(..).unwrap(),
+            (0..6)
+                .map(|i| Kept {
+                    id: i,
+                    value: i as f64 / 2.0,
+                })
+                .collect::<Vec<_>>()
+        );
+        assert!(table.append(&[0u64]).is_err());
+
+        assert_eq!(
+            table.read_all::<Kept>().unwrap()[5],
+            Kept { id: 5, value: 2.5 }
+        );
+
+        let table = PacketTable::open(&file, "data").unwrap();
+        let transform = DropMembers::new(&Record::type_descriptor(), &["debug"]).unwrap();
+        let mut table = table
+            .with_transform(Box::new(transform), Record::type_descriptor())
+            .unwrap();
+        table.push(&record(6)).unwrap();
+        assert_eq!(table.get::<Kept>(6).unwrap(), Kept { id: 6, value: 3.0 });
+        table.write_at(0, &record(10)).unwrap();
+        table.overwrite(1, &[record(11)]).unwrap();
+        assert_eq!(table.get::<Kept>(0).unwrap(), Kept { id: 10, value: 5.0 });
+        assert_eq!(table.get::<Kept>(1).unwrap(), Kept { id: 11, value: 5.5 });
+        assert!(table.write_at(0, &Kept { id: 0, value: 0.0 }).is_err());
+
+        // The output doesn't match the stored `Kept`.
+        let transform = DropMembers::new(&Record::type_descriptor(), &["value"]).unwrap();
+        let res = PacketTable::open(&file, "data")
+            .unwrap()
+            .with_transform(Box::new(transform), Record::type_descriptor());
+        assert!(matches!(res, Err(Error::TransformMismatch { .. })));
+        assert!(DropMembers::new(&Record::type_descriptor(), &["missing"]).is_err());
+    }
+
+    #[test]
+    fn refresh_mismatch() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        let transform = DropMembers::new(&Record::type_descriptor(), &["debug"]).unwrap();
+        let mut table = PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Record>()
+            .with_transform(Box::new(transform))
+            .unwrap()
+            .create("data")
+            .unwrap();
+        table.push(&record(0)).unwrap();
+
+        PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<u8>()
+            .create("replaced")
+            .unwrap();
+        file.relink("data", "old").unwrap();
+        file.relink("replaced", "data").unwrap();
+        assert!(matches!(
+            table.refresh(),
+            Err(Error::TransformMismatch { .. })
+        ));
+        // The old handle is kept.
+        table.push(&record(1)).unwrap();
+        assert_eq!(table.num_packets().unwrap(), 2);
+        assert_eq!(table.get::<Kept>(1).unwrap(), Kept { id: 1, value: 0.5 });
+    }
+
+    #[test]
+    fn bad_output() {
+        let file = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(file.path()).unwrap();
+        assert!(PacketTable::builder(&file)
+            .chunk(4)
+            .dtype::<Record>()
+            .with_transform(Box::new(BadSize))
+            .is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The strictness of the validation before calling HDF5.
+///
+/// Memory-safety checks, e.g., the packet size check, are always performed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum ValidationProfile {
+    /// Skip everything except the memory-safety checks.
+    Fast = 0,
+    /// Perform the cached and cheap checks.
+    #[default]
+    Standard = 1,
+    /// Perform every check, including the expensive ones on each call.
+    Strict = 2,
+}
+
+impl ValidationProfile {
+    const fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Self::Fast,
+            2 => Self::Strict,
+            _ => Self::Standard,
+        }
+    }
+
+    /// Determine if the cheap checks should be performed.
+    #[inline]
+    pub const fn cheap_checks(self) -> bool {
+        !matches!(self, Self::Fast)
+    }
+
+    /// Determine if the expensive checks should be performed.
+    #[inline]
+    pub const fn expensive_checks(self) -> bool {
+        matches!(self, Self::Strict)
+    }
+}
+
+static VALIDATION_PROFILE: AtomicU8 = AtomicU8::new(ValidationProfile::Standard as u8);
+
+/// Set the global [`ValidationProfile`].
+pub fn set_validation_profile(profile: ValidationProfile) {
+    VALIDATION_PROFILE.store(profile as u8, Ordering::Relaxed);
+}
+
+/// Get the global [`ValidationProfile`].
+#[inline]
+pub fn validation_profile() -> ValidationProfile {
+    ValidationProfile::from_u8(VALIDATION_PROFILE.load(Ordering::Relaxed))
+}
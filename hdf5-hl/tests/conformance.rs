@@ -0,0 +1,133 @@
+//! Conformance tests against the reference file written by h5py.
+//!
+//! `data/reference.h5` is generated by `data/generate_reference.py`,
+//! and checked in, so that the tests don't require Python.
+
+use hdf5::types::{FixedAscii, TypeDescriptor, VarLenUnicode};
+use hdf5::H5Type;
+use hdf5_hl::{Error, PacketTable};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, H5Type)]
+#[repr(C)]
+struct Complex {
+    r: f64,
+    i: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, H5Type)]
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: f64,
+}
+
+fn reference() -> hdf5::File {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/reference.h5");
+    hdf5::File::open(path).unwrap()
+}
+
+fn descriptor(file: &hdf5::File, name: &str) -> TypeDescriptor {
+    file.dataset(name)
+        .unwrap()
+        .dtype()
+        .unwrap()
+        .to_descriptor()
+        .unwrap()
+}
+
+#[test]
+fn read_reference() {
+    let file = reference();
+
+    let fixed = file
+        .dataset("fixed_ascii")
+        .unwrap()
+        .read_1d::<FixedAscii<8>>()
+        .unwrap();
+    assert_eq!(fixed[0].as_str(), "hello");
+    assert_eq!(fixed[1].as_str(), "world");
+
+    let vlen = file
+        .dataset("vlen_str")
+        .unwrap()
+        .read_1d::<VarLenUnicode>()
+        .unwrap();
+    assert_eq!(vlen[0].as_str(), "hello");
+    assert_eq!(vlen[1].as_str(), "world");
+
+    let bools = file.dataset("bools").unwrap().read_1d::<bool>().unwrap();
+    assert_eq!(bools.as_slice().unwrap(), &[true, false, true]);
+
+    let complex = file
+        .dataset("complex")
+        .unwrap()
+        .read_1d::<Complex>()
+        .unwrap();
+    assert_eq!(
+        complex.as_slice().unwrap(),
+        &[Complex { r: 1.0, i: 2.0 }, Complex { r: 3.0, i: -4.0 }]
+    );
+
+    let padded = file.dataset("padded").unwrap().read_1d::<Padded>().unwrap();
+    assert_eq!(
+        padded.as_slice().unwrap(),
+        &[Padded { a: 1, b: 1.5 }, Padded { a: 2, b: 2.5 }]
+    );
+
+    let table = PacketTable::open(&file, "table").unwrap();
+    assert_eq!(
+        table.read_all::<i32>().unwrap(),
+        (0..100).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn write_like_reference() {
+    let reference = reference();
+    let file = NamedTempFile::new().unwrap();
+    let file = hdf5::File::create(file.path()).unwrap();
+
+    file.new_dataset::<FixedAscii<8>>()
+        .shape(2)
+        .create("fixed_ascii")
+        .unwrap();
+    file.new_dataset::<VarLenUnicode>()
+        .shape(2)
+        .create("vlen_str")
+        .unwrap();
+    file.new_dataset::<bool>().shape(3).create("bools").unwrap();
+    file.new_dataset::<Complex>()
+        .shape(2)
+        .create("complex")
+        .unwrap();
+    file.new_dataset::<Padded>()
+        .shape(2)
+        .create("padded")
+        .unwrap();
+    PacketTable::builder(&file)
+        .chunk(16)
+        .dtype::<i32>()
+        .create("table")
+        .unwrap();
+
+    for name in [
+        "fixed_ascii",
+        "vlen_str",
+        "bools",
+        "complex",
+        "padded",
+        "table",
+    ] {
+        let expected = descriptor(&reference, name);
+        let actual = descriptor(&file, name);
+        if actual != expected {
+            let diff = Error::SchemaMismatch {
+                expected: Box::new(expected),
+                actual: Box::new(actual),
+            };
+            panic!("{name}: {diff}");
+        }
+    }
+}
@@ -0,0 +1,30 @@
+"""Generate the h5py reference file for the conformance tests.
+
+Run from this directory:
+
+    python generate_reference.py
+
+The generated `reference.h5` is checked in, and read by `conformance.rs`,
+so that the tests don't require Python. Regenerate it after changing this script.
+"""
+
+import h5py
+import numpy as np
+
+with h5py.File("reference.h5", "w") as f:
+    f.create_dataset("fixed_ascii", data=np.array([b"hello", b"world"], dtype="S8"))
+    f.create_dataset(
+        "vlen_str",
+        data=["hello", "world"],
+        dtype=h5py.string_dtype("utf-8"),
+    )
+    f.create_dataset("bools", data=np.array([True, False, True]))
+    f.create_dataset("complex", data=np.array([1 + 2j, 3 - 4j], dtype=np.complex128))
+    padded = np.dtype([("a", "u1"), ("b", "<f8")], align=True)
+    f.create_dataset("padded", data=np.array([(1, 1.5), (2, 2.5)], dtype=padded))
+    f.create_dataset(
+        "table",
+        data=np.arange(100, dtype="<i4"),
+        maxshape=(None,),
+        chunks=(16,),
+    )